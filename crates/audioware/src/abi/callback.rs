@@ -51,6 +51,7 @@ pub enum FireCallback {
     Untag(FireUntagCallback),
     AddContainerStreamingPrefetch(FireAddContainerStreamingPrefetchCallback),
     RemoveContainerStreamingPrefetch(FireRemoveContainerStreamingPrefetchCallback),
+    Marker(FireMarkerCallback),
 }
 
 impl FireCallback {
@@ -88,7 +89,8 @@ impl FireCallback {
             })
             | Self::RemoveContainerStreamingPrefetch(
                 FireRemoveContainerStreamingPrefetchCallback { event_name, .. },
-            ) => *event_name,
+            )
+            | Self::Marker(FireMarkerCallback { event_name, .. }) => *event_name,
         }
     }
     pub fn event_type(&self) -> EventActionType {
@@ -113,6 +115,8 @@ impl FireCallback {
             Self::RemoveContainerStreamingPrefetch(_) => {
                 EventActionType::RemoveContainerStreamingPrefetch
             }
+            // reached while playing
+            Self::Marker(_) => EventActionType::Play,
         }
     }
     pub fn entity_id(&self) -> Option<EntityId> {
@@ -130,6 +134,7 @@ impl FireCallback {
             Self::Untag(x) => Some(x.entity_id),
             Self::AddContainerStreamingPrefetch(x) => Some(x.entity_id),
             Self::RemoveContainerStreamingPrefetch(x) => Some(x.entity_id),
+            Self::Marker(x) => Some(x.entity_id),
             Self::SetGlobalParameter(_) => None,
         }
     }
@@ -140,6 +145,7 @@ impl FireCallback {
             Self::PlayOneShot(x) => Some(x.base.emitter_name),
             Self::SetParameter(x) => Some(x.base.emitter_name),
             Self::SetSwitch(x) => Some(x.base.emitter_name),
+            Self::Marker(x) => Some(x.emitter_name),
             Self::SetAppearanceName(_)
             | Self::SetEntityName(_)
             | Self::Stop(_)
@@ -167,6 +173,8 @@ impl FireCallback {
             Self::AddContainerStreamingPrefetch(x) => x.wwise_id,
             Self::RemoveContainerStreamingPrefetch(x) => x.wwise_id,
             Self::SetGlobalParameter(x) => x.wwise_id,
+            // only known to Audioware
+            Self::Marker(_) => WwiseId::default(),
         }
     }
 
@@ -188,6 +196,7 @@ impl FireCallback {
                 EventHookType::RemoveContainerStreamingPrefetch
             }
             Self::SetGlobalParameter(_) => EventHookType::SetGlobalParameter,
+            Self::Marker(_) => EventHookType::Marker,
         }
     }
 }
@@ -344,6 +353,15 @@ pub struct FireRemoveContainerStreamingPrefetchCallback {
     pub wwise_id: WwiseId,
 }
 
+#[derive(Debug, Clone)]
+pub struct FireMarkerCallback {
+    pub event_name: EventName,
+    pub marker_name: CName,
+    pub entity_id: EntityId,
+    pub emitter_name: CName,
+    pub time: f32,
+}
+
 impl std::fmt::Display for Callback {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
@@ -468,6 +486,14 @@ impl std::fmt::Display for Callback {
                 f,
                 "fire callback(s) for remove container streaming prefetch event for {event_name}",
             ),
+            Self::FireCallbacks(FireCallback::Marker(FireMarkerCallback {
+                event_name,
+                marker_name,
+                ..
+            })) => write!(
+                f,
+                "fire callback(s) for marker {marker_name} event for {event_name}",
+            ),
             Self::Unregister { .. } => write!(f, "unregister callback"),
         }
     }
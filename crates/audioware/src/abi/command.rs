@@ -3,9 +3,9 @@ use audioware_manifest::{PlayerGender, ScnDialogLineType, Settings};
 use kira::Tween;
 use red4ext_rs::types::{CName, Cruid, EntityId};
 
-use crate::ControlId;
+use crate::{ControlId, PositionalId, Vector4};
 
-use super::{TagName, TargetId};
+use super::{TagName, TargetFootprint, TargetId};
 
 /// Sound inner command.
 #[derive(Clone)]
@@ -43,6 +43,23 @@ pub enum Command {
         ext: Option<Settings>,
         control_id: ControlId,
     },
+    PlayOnPositionalEmitter {
+        event_name: CName,
+        id: PositionalId,
+        ext: Option<Settings>,
+    },
+    EnqueueAndPlayOnPositionalEmitter {
+        event_name: CName,
+        id: PositionalId,
+        ext: Option<Settings>,
+        control_id: ControlId,
+    },
+    PlayAtPosition {
+        event_name: CName,
+        position: Vector4,
+        ext: Option<Settings>,
+        emitter_settings: Option<TargetFootprint>,
+    },
     PlayOverThePhone {
         event_name: CName,
         emitter_name: CName,
@@ -66,6 +83,11 @@ pub enum Command {
         tag_name: TagName,
         tween: Option<Tween>,
     },
+    StopOnPositionalEmitter {
+        event_name: CName,
+        id: PositionalId,
+        tween: Option<Tween>,
+    },
     StopVanilla {
         event_name: CName,
         entity_id: Option<EntityId>,
@@ -91,6 +113,14 @@ pub enum Command {
         entity_id: Option<EntityId>,
         emitter_name: Option<CName>,
     },
+    ActivateSnapshot {
+        name: CName,
+        tween: Option<Tween>,
+    },
+    DeactivateSnapshot {
+        name: CName,
+        tween: Option<Tween>,
+    },
 }
 
 impl std::fmt::Debug for Command {
@@ -121,6 +151,26 @@ impl std::fmt::Debug for Command {
                 "Command::PlayOnEmitter {{ event_name: {event_name}, entity_id: {entity_id}, emitter_name: {}, .. }}",
                 tag_name.as_str()
             ),
+            Command::PlayOnPositionalEmitter { event_name, id, .. } => write!(
+                f,
+                "Command::PlayOnPositionalEmitter {{ event_name: {event_name}, id: {id}, .. }}"
+            ),
+            Command::EnqueueAndPlayOnPositionalEmitter { event_name, id, .. } => write!(
+                f,
+                "Command::EnqueueAndPlayOnPositionalEmitter {{ event_name: {event_name}, id: {id}, .. }}"
+            ),
+            Command::PlayAtPosition {
+                event_name,
+                position,
+                ..
+            } => write!(
+                f,
+                "Command::PlayAtPosition {{ event_name: {event_name}, position: {position}, .. }}"
+            ),
+            Command::StopOnPositionalEmitter { event_name, id, .. } => write!(
+                f,
+                "Command::StopOnPositionalEmitter {{ event_name: {event_name}, id: {id}, .. }}"
+            ),
             Command::PlaySceneDialog {
                 string_id,
                 entity_id,
@@ -172,6 +222,12 @@ impl std::fmt::Debug for Command {
                     .unwrap_or("None".into()),
                 emitter_name.map(|x| x.as_str()).unwrap_or("None")
             ),
+            Command::ActivateSnapshot { name, .. } => {
+                write!(f, "Command::ActivateSnapshot {{ name: {name}, .. }}")
+            }
+            Command::DeactivateSnapshot { name, .. } => {
+                write!(f, "Command::DeactivateSnapshot {{ name: {name}, .. }}")
+            }
             x => write!(f, "{x:?}"),
         }
     }
@@ -186,16 +242,23 @@ impl std::fmt::Display for Command {
                 Command::PlayVanilla { .. } => "play vanilla",
                 Command::Play { .. } => "play",
                 Command::PlayOnEmitter { .. } => "play on emitter",
+                Command::PlayOnPositionalEmitter { .. } => "play on positional emitter",
+                Command::PlayAtPosition { .. } => "play at position",
                 Command::PlayOverThePhone { .. } => "play over the phone",
                 Command::PlaySceneDialog { .. } => "play scene dialog",
                 Command::StopSceneDialog { .. } => "stop scene dialog",
                 Command::StopOnEmitter { .. } => "stop on emitter",
+                Command::StopOnPositionalEmitter { .. } => "stop on positional emitter",
                 Command::StopVanilla { .. } => "stop vanilla",
                 Command::Stop { .. } => "stop",
                 Command::Switch { .. } => "switch",
                 Command::SwitchVanilla { .. } => "switch vanilla",
                 Command::EnqueueAndPlay { .. } => "enqueue and play",
                 Command::EnqueueAndPlayOnEmitter { .. } => "enqueue and play on emitter",
+                Command::EnqueueAndPlayOnPositionalEmitter { .. } =>
+                    "enqueue and play on positional emitter",
+                Command::ActivateSnapshot { .. } => "activate snapshot",
+                Command::DeactivateSnapshot { .. } => "deactivate snapshot",
             }
         )
     }
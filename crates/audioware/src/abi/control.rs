@@ -3,11 +3,11 @@ use std::time::Duration;
 use audioware_core::{Amplitude, Panning};
 use crossbeam::channel::{Sender, bounded};
 use humantime::format_duration;
-use kira::Tween;
+use kira::{Decibels, Tween};
 use red4ext_rs::types::{CName, EntityId, Ref};
 
 use crate::{
-    ControlId, ToTween,
+    ControlId, PositionalId, ToTween,
     abi::{DynamicEmitterEvent, TargetId, command::Command, types::DynamicSoundEvent},
     engine::{next_control_id, queue},
     utils::warns,
@@ -30,6 +30,35 @@ pub enum DynamicSound {
         value: kira::Panning,
         tween: Option<Tween>,
     },
+    SetFilterCutoff {
+        id: ControlId,
+        /// Index among sound own filters, in declaration order.
+        filter: usize,
+        value: f64,
+        tween: Option<Tween>,
+    },
+    SetFilterResonance {
+        id: ControlId,
+        /// Index among sound own filters, in declaration order.
+        filter: usize,
+        value: f64,
+        tween: Option<Tween>,
+    },
+    SetDistortionDrive {
+        id: ControlId,
+        value: Decibels,
+        tween: Option<Tween>,
+    },
+    SetDelayFeedback {
+        id: ControlId,
+        value: Decibels,
+        tween: Option<Tween>,
+    },
+    SetReverbMix {
+        id: ControlId,
+        value: f32,
+        tween: Option<Tween>,
+    },
     Position {
         id: ControlId,
         output: Sender<f32>,
@@ -73,6 +102,35 @@ pub enum DynamicEmitter {
         value: f64,
         tween: Option<Tween>,
     },
+    SetFilterCutoff {
+        id: ControlId,
+        /// Index among sound own filters, in declaration order.
+        filter: usize,
+        value: f64,
+        tween: Option<Tween>,
+    },
+    SetFilterResonance {
+        id: ControlId,
+        /// Index among sound own filters, in declaration order.
+        filter: usize,
+        value: f64,
+        tween: Option<Tween>,
+    },
+    SetDistortionDrive {
+        id: ControlId,
+        value: Decibels,
+        tween: Option<Tween>,
+    },
+    SetDelayFeedback {
+        id: ControlId,
+        value: Decibels,
+        tween: Option<Tween>,
+    },
+    SetReverbMix {
+        id: ControlId,
+        value: f32,
+        tween: Option<Tween>,
+    },
     Position {
         id: ControlId,
         output: Sender<f32>,
@@ -162,6 +220,82 @@ impl DynamicSoundEvent {
             tween: tween.into_tween(),
         });
     }
+    pub fn set_filter_cutoff(&self, value: f32, tween: Ref<crate::Tween>, filter: i32) {
+        if !(20.0..=20_000.0).contains(&value) {
+            warns!("invalid filter cutoff ({value})");
+            return;
+        }
+        let Ok(filter) = usize::try_from(filter) else {
+            warns!("invalid filter index ({filter})");
+            return;
+        };
+        if self.id.get().is_none() {
+            return;
+        }
+        queue::control_sound(DynamicSound::SetFilterCutoff {
+            id: *self.id.get().unwrap(),
+            filter,
+            value: value as f64,
+            tween: tween.into_tween(),
+        });
+    }
+    pub fn set_filter_resonance(&self, value: f32, tween: Ref<crate::Tween>, filter: i32) {
+        if !(0.0..=1.0).contains(&value) {
+            warns!("invalid filter resonance ({value})");
+            return;
+        }
+        let Ok(filter) = usize::try_from(filter) else {
+            warns!("invalid filter index ({filter})");
+            return;
+        };
+        if self.id.get().is_none() {
+            return;
+        }
+        queue::control_sound(DynamicSound::SetFilterResonance {
+            id: *self.id.get().unwrap(),
+            filter,
+            value: value as f64,
+            tween: tween.into_tween(),
+        });
+    }
+    pub fn set_distortion_drive(&self, value: f32, tween: Ref<crate::Tween>) {
+        if self.id.get().is_none() {
+            return;
+        }
+        queue::control_sound(DynamicSound::SetDistortionDrive {
+            id: *self.id.get().unwrap(),
+            value: Decibels(value),
+            tween: tween.into_tween(),
+        });
+    }
+    pub fn set_delay_feedback(&self, value: f32, tween: Ref<crate::Tween>) {
+        if value >= 0. {
+            warns!("invalid delay feedback, must be below 0dB ({value})");
+            return;
+        }
+        if self.id.get().is_none() {
+            return;
+        }
+        queue::control_sound(DynamicSound::SetDelayFeedback {
+            id: *self.id.get().unwrap(),
+            value: Decibels(value),
+            tween: tween.into_tween(),
+        });
+    }
+    pub fn set_reverb_mix(&self, value: f32, tween: Ref<crate::Tween>) {
+        if !(0.0..=1.0).contains(&value) {
+            warns!("invalid reverb mix ({value})");
+            return;
+        }
+        if self.id.get().is_none() {
+            return;
+        }
+        queue::control_sound(DynamicSound::SetReverbMix {
+            id: *self.id.get().unwrap(),
+            value,
+            tween: tween.into_tween(),
+        });
+    }
     pub fn position(&self) -> f32 {
         if self.id.get().is_none() {
             return -1.;
@@ -240,6 +374,21 @@ impl std::fmt::Display for DynamicSound {
             DynamicSound::SetPanning { id, .. } => {
                 write!(f, "set dynamic sound panning ({id})")
             }
+            DynamicSound::SetFilterCutoff { id, .. } => {
+                write!(f, "set dynamic sound filter cutoff ({id})")
+            }
+            DynamicSound::SetFilterResonance { id, .. } => {
+                write!(f, "set dynamic sound filter resonance ({id})")
+            }
+            DynamicSound::SetDistortionDrive { id, .. } => {
+                write!(f, "set dynamic sound distortion drive ({id})")
+            }
+            DynamicSound::SetDelayFeedback { id, .. } => {
+                write!(f, "set dynamic sound delay feedback ({id})")
+            }
+            DynamicSound::SetReverbMix { id, .. } => {
+                write!(f, "set dynamic sound reverb mix ({id})")
+            }
             DynamicSound::Pause { id, .. } => {
                 write!(f, "pause dynamic sound ({id})")
             }
@@ -291,6 +440,26 @@ impl DynamicEmitterEvent {
         });
         true
     }
+    /// Play on positional emitter instead, regardless of tag name.
+    pub fn play_on_positional_emitter(&self, id: u64) -> bool {
+        let Some(id) = PositionalId::try_new(id) else {
+            warns!("invalid positional emitter id: {id}");
+            return false;
+        };
+        if let Err(control_id) = self.id.set(next_control_id()) {
+            warns!(
+                "dynamic emitter already initialized for positional emitter {id} ({control_id})"
+            );
+            return false;
+        }
+        queue::send(Command::EnqueueAndPlayOnPositionalEmitter {
+            event_name: *self.name.get(),
+            id,
+            ext: self.ext.borrow().clone(),
+            control_id: *self.id.get().unwrap(),
+        });
+        true
+    }
     pub fn set_volume(&self, value: f32, tween: Ref<crate::Tween>) {
         let Ok(value) = Amplitude::try_from(value) else {
             warns!("invalid amplitude ({value})");
@@ -315,6 +484,82 @@ impl DynamicEmitterEvent {
             tween: tween.into_tween(),
         });
     }
+    pub fn set_filter_cutoff(&self, value: f32, tween: Ref<crate::Tween>, filter: i32) {
+        if !(20.0..=20_000.0).contains(&value) {
+            warns!("invalid filter cutoff ({value})");
+            return;
+        }
+        let Ok(filter) = usize::try_from(filter) else {
+            warns!("invalid filter index ({filter})");
+            return;
+        };
+        if self.id.get().is_none() {
+            return;
+        }
+        queue::control_emitter(DynamicEmitter::SetFilterCutoff {
+            id: *self.id.get().unwrap(),
+            filter,
+            value: value as f64,
+            tween: tween.into_tween(),
+        });
+    }
+    pub fn set_filter_resonance(&self, value: f32, tween: Ref<crate::Tween>, filter: i32) {
+        if !(0.0..=1.0).contains(&value) {
+            warns!("invalid filter resonance ({value})");
+            return;
+        }
+        let Ok(filter) = usize::try_from(filter) else {
+            warns!("invalid filter index ({filter})");
+            return;
+        };
+        if self.id.get().is_none() {
+            return;
+        }
+        queue::control_emitter(DynamicEmitter::SetFilterResonance {
+            id: *self.id.get().unwrap(),
+            filter,
+            value: value as f64,
+            tween: tween.into_tween(),
+        });
+    }
+    pub fn set_distortion_drive(&self, value: f32, tween: Ref<crate::Tween>) {
+        if self.id.get().is_none() {
+            return;
+        }
+        queue::control_emitter(DynamicEmitter::SetDistortionDrive {
+            id: *self.id.get().unwrap(),
+            value: Decibels(value),
+            tween: tween.into_tween(),
+        });
+    }
+    pub fn set_delay_feedback(&self, value: f32, tween: Ref<crate::Tween>) {
+        if value >= 0. {
+            warns!("invalid delay feedback, must be below 0dB ({value})");
+            return;
+        }
+        if self.id.get().is_none() {
+            return;
+        }
+        queue::control_emitter(DynamicEmitter::SetDelayFeedback {
+            id: *self.id.get().unwrap(),
+            value: Decibels(value),
+            tween: tween.into_tween(),
+        });
+    }
+    pub fn set_reverb_mix(&self, value: f32, tween: Ref<crate::Tween>) {
+        if !(0.0..=1.0).contains(&value) {
+            warns!("invalid reverb mix ({value})");
+            return;
+        }
+        if self.id.get().is_none() {
+            return;
+        }
+        queue::control_emitter(DynamicEmitter::SetReverbMix {
+            id: *self.id.get().unwrap(),
+            value,
+            tween: tween.into_tween(),
+        });
+    }
     pub fn stop(&self, tween: Ref<crate::Tween>) {
         if self.id.get().is_none() {
             return;
@@ -390,6 +635,21 @@ impl std::fmt::Display for DynamicEmitter {
             DynamicEmitter::SetPlaybackRate { id, .. } => {
                 write!(f, "set dynamic emitter playback rate ({id})")
             }
+            DynamicEmitter::SetFilterCutoff { id, .. } => {
+                write!(f, "set dynamic emitter filter cutoff ({id})")
+            }
+            DynamicEmitter::SetFilterResonance { id, .. } => {
+                write!(f, "set dynamic emitter filter resonance ({id})")
+            }
+            DynamicEmitter::SetDistortionDrive { id, .. } => {
+                write!(f, "set dynamic emitter distortion drive ({id})")
+            }
+            DynamicEmitter::SetDelayFeedback { id, .. } => {
+                write!(f, "set dynamic emitter delay feedback ({id})")
+            }
+            DynamicEmitter::SetReverbMix { id, .. } => {
+                write!(f, "set dynamic emitter reverb mix ({id})")
+            }
             DynamicEmitter::Pause { id, .. } => {
                 write!(f, "pause dynamic emitter ({id})")
             }
@@ -1,3 +1,5 @@
+use std::time::Duration;
+
 use audioware_core::Amplitude;
 use crossbeam::channel::Sender;
 use debug_ignore::DebugIgnore;
@@ -12,7 +14,7 @@ pub use replacement::ReplacementNotification;
 pub use session::Session;
 pub use system::System;
 
-use crate::CameraComponent;
+use crate::{CameraComponent, PositionalId, Vector4, engine::Anchor};
 
 use super::{TagName, TargetFootprint, TargetId};
 
@@ -24,6 +26,7 @@ pub enum Lifecycle {
         tag_name: TagName,
         emitter_name: Option<CName>,
         emitter_settings: Option<TargetFootprint>,
+        anchor: Option<Anchor>,
         sender: Sender<bool>,
     },
     UnregisterEmitter {
@@ -31,6 +34,21 @@ pub enum Lifecycle {
         tag_name: TagName,
         sender: Sender<bool>,
     },
+    RegisterPositionalEmitter {
+        id: PositionalId,
+        position: Vector4,
+        emitter_settings: Option<TargetFootprint>,
+        lifetime: Option<Duration>,
+        sender: Sender<bool>,
+    },
+    UnregisterPositionalEmitter {
+        id: PositionalId,
+        sender: Sender<bool>,
+    },
+    SetPositionalEmitterPosition {
+        id: PositionalId,
+        position: Vector4,
+    },
     OnEmitterDies {
         entity_id: EntityId,
     },
@@ -101,6 +119,15 @@ impl std::fmt::Display for Lifecycle {
             Lifecycle::UnregisterEmitter { entity_id, .. } => {
                 write!(f, "unregister emitter [{entity_id}]")
             }
+            Lifecycle::RegisterPositionalEmitter { id, position, .. } => {
+                write!(f, "register positional emitter at {position} [{id}]")
+            }
+            Lifecycle::UnregisterPositionalEmitter { id, .. } => {
+                write!(f, "unregister positional emitter [{id}]")
+            }
+            Lifecycle::SetPositionalEmitterPosition { id, position } => {
+                write!(f, "set positional emitter position {position} [{id}]")
+            }
             Lifecycle::OnEmitterDies { entity_id } => {
                 write!(f, "on emitter dies [{entity_id}]")
             }
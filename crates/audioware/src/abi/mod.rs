@@ -1,3 +1,5 @@
+use std::time::Duration;
+
 use audioware_core::Amplitude;
 use audioware_manifest::{Locale, PlayerGender, ScnDialogLineType, Validate};
 use command::Command;
@@ -18,10 +20,10 @@ use crate::{
     AddContainerStreamingPrefetchEvent, AudioEventCallbackAssetTarget,
     AudioEventCallbackEntityTarget, AudioEventCallbackEventTarget, AudioEventCallbackHandler,
     AudioEventCallbackSystem, AudioEventCallbackTarget, Audioware, EmitterSettings,
-    EngineSoundEvent, Event, Handler, LocalizationPackage, PlayEvent, PlayExternalEvent,
-    PlayOneShotEvent, RemoveContainerStreamingPrefetchEvent, SetAppearanceNameEvent,
-    SetEntityNameEvent, SetGlobalParameterEvent, SetParameterEvent, SetSwitchEvent, StopSoundEvent,
-    StopTaggedEvent, TagEvent, ToTween, Tween, UntagEvent,
+    EngineSoundEvent, Event, Handler, LocalizationPackage, MarkerEvent, PlayEvent,
+    PlayExternalEvent, PlayOneShotEvent, PositionalId, RemoveContainerStreamingPrefetchEvent,
+    SetAppearanceNameEvent, SetEntityNameEvent, SetGlobalParameterEvent, SetParameterEvent,
+    SetSwitchEvent, StopSoundEvent, StopTaggedEvent, TagEvent, ToTween, Tween, UntagEvent, Vector4,
     engine::{AudioEventManager, Engine, Mute, eq::Preset, state},
     queue,
     utils::{fails, lifecycle, warns},
@@ -83,6 +85,14 @@ pub fn exports() -> impl Exportable {
                     final c"OnEmitterIncapacitated" => AudioSystemExt::on_emitter_incapacitated,
                     final c"OnEmitterDefeated" => AudioSystemExt::on_emitter_defeated,
                     final c"EmittersCount" => AudioSystemExt::emitters_count,
+                    final c"RegisterPositionalEmitter" => AudioSystemExt::register_positional_emitter,
+                    final c"UnregisterPositionalEmitter" => AudioSystemExt::unregister_positional_emitter,
+                    final c"SetPositionalEmitterPosition" => AudioSystemExt::set_positional_emitter_position,
+                    final c"PlayOnPositionalEmitter" => AudioSystemExt::play_on_positional_emitter,
+                    final c"StopOnPositionalEmitter" => AudioSystemExt::stop_on_positional_emitter,
+                    final c"PlayAtPosition" => AudioSystemExt::play_at_position,
+                    final c"ActivateSnapshot" => AudioSystemExt::activate_snapshot,
+                    final c"DeactivateSnapshot" => AudioSystemExt::deactivate_snapshot,
                     final c"Duration" => AudioSystemExt::duration,
                     final c"IsDebug" => AudioSystemExt::is_debug,
                     final c"SemanticVersion" => AudioSystemExt::semantic_version,
@@ -271,6 +281,16 @@ pub fn exports() -> impl Exportable {
                     final c"WwiseID" => RemoveContainerStreamingPrefetchEvent::wwise_id,
                 ])
                 .build(),
+        ClassExport::<MarkerEvent>::builder()
+                .base(EngineSoundEvent::NAME)
+                .methods(methods![
+                    final c"EventName" => MarkerEvent::event_name,
+                    final c"MarkerName" => MarkerEvent::marker_name,
+                    final c"EntityID" => MarkerEvent::entity_id,
+                    final c"EmitterName" => MarkerEvent::emitter_name,
+                    final c"Time" => MarkerEvent::time,
+                ])
+                .build(),
         ClassExport::<AudioEventCallbackHandler>::builder()
                 .base(IScriptable::NAME)
                 .methods(methods![
@@ -297,6 +317,11 @@ pub fn exports() -> impl Exportable {
                     final c"SetVolume" => DynamicSoundEvent::set_volume,
                     final c"SetPlaybackRate" => DynamicSoundEvent::set_playback_rate,
                     final c"SetPanning" => DynamicSoundEvent::set_panning,
+                    final c"SetFilterCutoff" => DynamicSoundEvent::set_filter_cutoff,
+                    final c"SetFilterResonance" => DynamicSoundEvent::set_filter_resonance,
+                    final c"SetDistortionDrive" => DynamicSoundEvent::set_distortion_drive,
+                    final c"SetDelayFeedback" => DynamicSoundEvent::set_delay_feedback,
+                    final c"SetReverbMix" => DynamicSoundEvent::set_reverb_mix,
                     final c"Position" => DynamicSoundEvent::position,
                     final c"Stop" => DynamicSoundEvent::stop,
                     final c"Pause" => DynamicSoundEvent::pause,
@@ -314,6 +339,11 @@ pub fn exports() -> impl Exportable {
                 .methods(methods![
                     final c"SetVolume" => DynamicEmitterEvent::set_volume,
                     final c"SetPlaybackRate" => DynamicEmitterEvent::set_playback_rate,
+                    final c"SetFilterCutoff" => DynamicEmitterEvent::set_filter_cutoff,
+                    final c"SetFilterResonance" => DynamicEmitterEvent::set_filter_resonance,
+                    final c"SetDistortionDrive" => DynamicEmitterEvent::set_distortion_drive,
+                    final c"SetDelayFeedback" => DynamicEmitterEvent::set_delay_feedback,
+                    final c"SetReverbMix" => DynamicEmitterEvent::set_reverb_mix,
                     final c"Position" => DynamicEmitterEvent::position,
                     final c"Stop" => DynamicEmitterEvent::stop,
                     final c"Pause" => DynamicEmitterEvent::pause,
@@ -321,6 +351,7 @@ pub fn exports() -> impl Exportable {
                     final c"ResumeAt" => DynamicEmitterEvent::resume_at,
                     final c"SeekTo" => DynamicEmitterEvent::seek_to,
                     final c"SeekBy" => DynamicEmitterEvent::seek_by,
+                    final c"PlayOnPositionalEmitter" => DynamicEmitterEvent::play_on_positional_emitter,
                 ])
                 .build(),
         g!(c"Audioware.OnGameSessionBeforeStart",   Audioware::on_game_session_before_start),
@@ -538,6 +569,14 @@ pub trait SceneLifecycle {
     fn on_emitter_incapacitated(&self, entity_id: EntityId);
     fn on_emitter_defeated(&self, entity_id: EntityId);
     fn emitters_count(&self) -> i32;
+    fn register_positional_emitter(
+        &self,
+        position: Vector4,
+        emitter_settings: Ref<EmitterSettings>,
+        lifetime: Opt<f32>,
+    ) -> u64;
+    fn unregister_positional_emitter(&self, id: u64) -> bool;
+    fn set_positional_emitter_position(&self, id: u64, position: Vector4);
 }
 
 impl SceneLifecycle for AudioSystemExt {
@@ -562,6 +601,7 @@ impl SceneLifecycle for AudioSystemExt {
                 return false;
             }
         };
+        let anchor = unsafe { emitter_settings.fields() }.and_then(EmitterSettings::anchor);
         let emitter_settings = match TargetFootprint::try_new(emitter_settings, *entity_id) {
             Ok(emitter_settings) => emitter_settings,
             Err(e) => {
@@ -581,6 +621,7 @@ impl SceneLifecycle for AudioSystemExt {
             entity_id,
             emitter_name: emitter_name.into_option(),
             emitter_settings,
+            anchor,
             sender,
         });
         if let Ok(registered) = receiver.recv() {
@@ -635,6 +676,70 @@ impl SceneLifecycle for AudioSystemExt {
     fn emitters_count(&self) -> i32 {
         Engine::<CpalBackend>::emitters_count()
     }
+
+    fn register_positional_emitter(
+        &self,
+        position: Vector4,
+        emitter_settings: Ref<EmitterSettings>,
+        lifetime: Opt<f32>,
+    ) -> u64 {
+        let lifetime = match lifetime.into_option() {
+            None => None,
+            Some(x) if x == 0. => None,
+            Some(x) if x > 0. && x.is_finite() => Some(Duration::from_secs_f32(x)),
+            Some(x) => {
+                warns!("lifetime must be positive, or 0.0 to persist until unregistered ({x})");
+                return 0;
+            }
+        };
+        let emitter_settings = match TargetFootprint::try_new_positional(emitter_settings) {
+            Ok(emitter_settings) => emitter_settings,
+            Err(e) => {
+                warns!(
+                    "{}",
+                    e.iter()
+                        .map(|e| format!("{e}"))
+                        .collect::<Vec<_>>()
+                        .join(", ")
+                );
+                return 0;
+            }
+        };
+        let id = PositionalId::next();
+        let (sender, receiver) = bounded(0);
+        queue::notify(Lifecycle::RegisterPositionalEmitter {
+            id,
+            position,
+            emitter_settings,
+            lifetime,
+            sender,
+        });
+        if let Ok(true) = receiver.recv() {
+            return id.into();
+        }
+        0
+    }
+
+    fn unregister_positional_emitter(&self, id: u64) -> bool {
+        let Some(id) = PositionalId::try_new(id) else {
+            warns!("invalid positional emitter id: {id}");
+            return false;
+        };
+        let (sender, receiver) = bounded(0);
+        queue::notify(Lifecycle::UnregisterPositionalEmitter { id, sender });
+        if let Ok(unregistered) = receiver.recv() {
+            return unregistered;
+        }
+        false
+    }
+
+    fn set_positional_emitter_position(&self, id: u64, position: Vector4) {
+        let Some(id) = PositionalId::try_new(id) else {
+            warns!("invalid positional emitter id: {id}");
+            return;
+        };
+        queue::notify(Lifecycle::SetPositionalEmitterPosition { id, position });
+    }
 }
 
 pub trait ExtCommand {
@@ -678,6 +783,19 @@ pub trait ExtCommand {
         switch_name_tween: Ref<Tween>,
         switch_value_ext: Ref<AudioSettingsExt>,
     );
+    fn play_on_positional_emitter(&self, event_name: CName, id: u64, ext: Ref<AudioSettingsExt>);
+    fn stop_on_positional_emitter(&self, event_name: CName, id: u64, tween: Ref<Tween>);
+    /// Play sound once at given position, without entity.
+    fn play_at_position(
+        &self,
+        event_name: CName,
+        position: Vector4,
+        ext: Ref<AudioSettingsExt>,
+        emitter_settings: Ref<EmitterSettings>,
+    );
+    /// Blend to named mix snapshot, stacked with other active ones by priority.
+    fn activate_snapshot(&self, name: CName, tween: Ref<Tween>);
+    fn deactivate_snapshot(&self, name: CName, tween: Ref<Tween>);
 }
 
 impl ExtCommand for AudioSystemExt {
@@ -805,6 +923,82 @@ impl ExtCommand for AudioSystemExt {
         });
     }
 
+    fn play_on_positional_emitter(&self, event_name: CName, id: u64, ext: Ref<AudioSettingsExt>) {
+        let Some(id) = PositionalId::try_new(id) else {
+            warns!("invalid positional emitter id: {id}");
+            return;
+        };
+        let ext = ext.into_settings();
+        if let Some(Err(e)) = ext.as_ref().map(Validate::validate) {
+            warns!("invalid audio settings: {:#?}", e);
+            return;
+        }
+        queue::send(Command::PlayOnPositionalEmitter {
+            event_name,
+            id,
+            ext,
+        });
+    }
+
+    fn stop_on_positional_emitter(&self, event_name: CName, id: u64, tween: Ref<Tween>) {
+        let Some(id) = PositionalId::try_new(id) else {
+            warns!("invalid positional emitter id: {id}");
+            return;
+        };
+        queue::send(Command::StopOnPositionalEmitter {
+            event_name,
+            id,
+            tween: tween.into_tween(),
+        });
+    }
+
+    fn play_at_position(
+        &self,
+        event_name: CName,
+        position: Vector4,
+        ext: Ref<AudioSettingsExt>,
+        emitter_settings: Ref<EmitterSettings>,
+    ) {
+        let ext = ext.into_settings();
+        if let Some(Err(e)) = ext.as_ref().map(Validate::validate) {
+            warns!("invalid audio settings: {:#?}", e);
+            return;
+        }
+        let emitter_settings = match TargetFootprint::try_new_positional(emitter_settings) {
+            Ok(emitter_settings) => emitter_settings,
+            Err(e) => {
+                warns!(
+                    "{}",
+                    e.iter()
+                        .map(|e| format!("{e}"))
+                        .collect::<Vec<_>>()
+                        .join(", ")
+                );
+                return;
+            }
+        };
+        queue::send(Command::PlayAtPosition {
+            event_name,
+            position,
+            ext,
+            emitter_settings,
+        });
+    }
+
+    fn activate_snapshot(&self, name: CName, tween: Ref<Tween>) {
+        queue::send(Command::ActivateSnapshot {
+            name,
+            tween: tween.into_tween(),
+        });
+    }
+
+    fn deactivate_snapshot(&self, name: CName, tween: Ref<Tween>) {
+        queue::send(Command::DeactivateSnapshot {
+            name,
+            tween: tween.into_tween(),
+        });
+    }
+
     fn play_over_the_phone(&self, event_name: CName, emitter_name: CName, gender: CName) {
         match PlayerGender::try_from(gender) {
             Ok(gender) => {
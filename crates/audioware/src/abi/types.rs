@@ -7,8 +7,10 @@ use std::{
     time::Duration,
 };
 
-use audioware_core::{Amplitude, SpatialTrackSettings};
-use audioware_manifest::{Interpolation, Locale, LocaleExt, PlayerGender, Region, Settings};
+use audioware_core::{Amplitude, AttenuationPoint, SpatialTrackSettings};
+use audioware_manifest::{
+    Interpolation, Locale, LocaleExt, PlayerGender, Ranged, Region, Settings, TimeDilation,
+};
 use kira::{Easing, backend::cpal::CpalBackend, track::SpatialTrackDistances};
 use red4ext_rs::{
     ScriptClass,
@@ -17,9 +19,13 @@ use red4ext_rs::{
 };
 
 use crate::{
-    AUDIOWARE_VERSION, AsEntity, ControlId, ElasticTween, EmitterDistances, EmitterSettings,
-    Entity, Event, EventName, LinearTween, ToEasing, Tween, abi::fails, engine::Engine,
-    error::ValidationError, get_player,
+    AUDIOWARE_VERSION, AsEntity, AttenuationPreset, ControlId, ElasticTween, EmitterAttenuation,
+    EmitterCone, EmitterCurve, EmitterDistances, EmitterOcclusion, EmitterSettings, Entity, Event,
+    EventName, LinearTween, ToEasing, Tween,
+    abi::fails,
+    engine::{Anchor, Engine},
+    error::ValidationError,
+    get_player,
 };
 
 /// Represents a region in time.
@@ -48,6 +54,7 @@ pub struct AudioSettingsExt {
     panning: f32,
     playback_rate: f32,
     affected_by_time_dilation: bool,
+    preserve_pitch_on_time_dilation: bool,
 }
 
 impl Default for AudioSettingsExt {
@@ -61,6 +68,7 @@ impl Default for AudioSettingsExt {
             panning: 0.0,
             playback_rate: 1.,
             affected_by_time_dilation: true,
+            preserve_pitch_on_time_dilation: false,
         }
     }
 }
@@ -221,11 +229,41 @@ impl ToSettings for AudioSettingsExt {
             start_position: Some(Duration::from_secs_f32(self.start_position)),
             region: self.region.into_region(),
             r#loop: Some(self.r#loop),
-            volume: Some(volume),
+            volume: Some(Ranged::Fixed(volume)),
             fade_in_tween: self.fade_in.into_interpolation(),
+            // stopping from scripts always specifies tween
+            fade_out_tween: None,
+            on_stop: None,
             panning: Some(self.panning),
-            playback_rate: Some(kira::PlaybackRate(self.playback_rate as f64)),
-            affected_by_time_dilation: Some(self.affected_by_time_dilation),
+            playback_rate: Some(Ranged::Fixed(kira::PlaybackRate(self.playback_rate as f64))),
+            affected_by_time_dilation: Some(
+                match (
+                    self.affected_by_time_dilation,
+                    self.preserve_pitch_on_time_dilation,
+                ) {
+                    (false, _) => TimeDilation::Unaffected,
+                    (true, false) => TimeDilation::Pitch,
+                    (true, true) => TimeDilation::Stretch,
+                },
+            ),
+            // loudness is only analyzed on load
+            normalize: None,
+            // frames are only processed on load
+            reverse: None,
+            time_stretch: None,
+            // markers are only declared in manifests
+            markers: None,
+            effects: None,
+            spatial: None,
+            // instance limits are only declared in manifests
+            max_instances: None,
+            instance_scope: None,
+            steal: None,
+            cooldown: None,
+            min_retrigger_interval: None,
+            retrigger_scope: None,
+            duck: None,
+            ducking: None,
         })
     }
 }
@@ -270,6 +308,72 @@ impl ToSettingsExt for EmitterSettings {
         let attenuation_function = self.attenuation_function.into_easing();
         self.enable_spatialization.hash(&mut state);
         self.persist_until_sounds_finish.hash(&mut state);
+        let attenuation_curve = self
+            .attenuation
+            .is_null()
+            .not()
+            .then(|| unsafe { self.attenuation.fields() }.cloned())
+            .flatten();
+        attenuation_curve.hash(&mut state);
+        let attenuation_curve = attenuation_curve.and_then(ToSettings::into_settings);
+        let occlusion = self
+            .occlusion
+            .is_null()
+            .not()
+            .then(|| unsafe { self.occlusion.fields() }.cloned())
+            .flatten();
+        occlusion.hash(&mut state);
+        let occlusion_model = occlusion
+            .and_then(ToSettings::into_settings)
+            .unwrap_or_default();
+        let cone = self
+            .cone
+            .is_null()
+            .not()
+            .then(|| unsafe { self.cone.fields() }.cloned())
+            .flatten();
+        cone.hash(&mut state);
+        let cone = cone.and_then(ToSettings::into_settings);
+        ((self.doppler_factor * 100.).clamp(0., u64::MAX as f32) as u64).hash(&mut state);
+        let doppler_factor = match self.doppler_factor {
+            x if x == 0. => None,
+            x if (0.0..=10.0).contains(&x) => Some(x),
+            x => {
+                fails!("invalid doppler factor: must be between 0.0 and 10.0 (got {x})");
+                None
+            }
+        };
+        let air_absorption = self
+            .air_absorption
+            .is_null()
+            .not()
+            .then(|| unsafe { self.air_absorption.fields() }.cloned())
+            .flatten();
+        air_absorption.hash(&mut state);
+        let air_absorption = air_absorption
+            .and_then(ToSettings::into_settings)
+            .filter(|x| {
+                let valid = x.values().all(|x| (20.0..=20_000.0).contains(&x));
+                if !valid {
+                    fails!("invalid air absorption: cutoffs must be between 20.0 and 20000.0");
+                }
+                valid
+            });
+        let reverb_send = self
+            .reverb_send
+            .is_null()
+            .not()
+            .then(|| unsafe { self.reverb_send.fields() }.cloned())
+            .flatten();
+        reverb_send.hash(&mut state);
+        let reverb_send = reverb_send.and_then(ToSettings::into_settings).filter(|x| {
+            let valid = x.values().all(|x| (0.0..=1.0).contains(&x));
+            if !valid {
+                fails!("invalid reverb send: levels must be between 0.0 and 1.0");
+            }
+            valid
+        });
+        self.priority.hash(&mut state);
         let hash = state.finish();
         if hash == 0 {
             fails!("emitter settings hash should not be 0");
@@ -279,6 +383,7 @@ impl ToSettingsExt for EmitterSettings {
             SpatialTrackSettings {
                 distances: distances.unwrap_or(defaults.unwrap_or_default()),
                 attenuation_function,
+                attenuation_curve,
                 spatialization_strength: if self.enable_spatialization {
                     0.75
                 } else {
@@ -288,6 +393,12 @@ impl ToSettingsExt for EmitterSettings {
                 affected_by_reverb_mix: self.affected_by_reverb_mix,
                 affected_by_environmental_preset: self.affected_by_environmental_preset,
                 enable_occlusion: self.enable_occlusion,
+                occlusion_model,
+                cone,
+                doppler_factor,
+                air_absorption,
+                reverb_send,
+                priority: self.priority,
             },
             // SAFETY: checked above
             unsafe { std::num::NonZeroU64::new_unchecked(hash) },
@@ -295,6 +406,126 @@ impl ToSettingsExt for EmitterSettings {
     }
 }
 
+impl ToSettings for EmitterAttenuation {
+    type Settings = audioware_core::AttenuationCurve;
+    fn into_settings(self) -> Option<Self::Settings> {
+        let points = match self.preset {
+            AttenuationPreset::Logarithmic => {
+                return Some(Self::Settings::Preset(
+                    audioware_core::AttenuationPreset::Logarithmic,
+                ));
+            }
+            AttenuationPreset::InverseSquare => {
+                return Some(Self::Settings::Preset(
+                    audioware_core::AttenuationPreset::InverseSquare,
+                ));
+            }
+            AttenuationPreset::Custom => self
+                .distances
+                .iter()
+                .zip(self.gains.iter())
+                .map(|(distance, gain)| {
+                    Amplitude::try_from(*gain)
+                        .ok()
+                        .filter(|x| x.as_factor() <= 1.)
+                        .map(|gain| AttenuationPoint {
+                            distance: *distance,
+                            gain,
+                        })
+                })
+                .collect::<Option<Vec<_>>>(),
+        };
+        let Some(points) = points.filter(|x| {
+            !x.is_empty()
+                && self.distances.len() == self.gains.len()
+                && x.iter().all(|x| x.distance >= 0.)
+                && x.windows(2).all(|x| x[0].distance < x[1].distance)
+        }) else {
+            fails!(
+                "invalid attenuation: gains must be between 0.0 and 1.0, for each increasing distance"
+            );
+            return None;
+        };
+        Some(Self::Settings::Points(points))
+    }
+}
+
+impl ToSettings for EmitterCone {
+    type Settings = audioware_core::Cone;
+    fn into_settings(self) -> Option<Self::Settings> {
+        let angles = 0.0..=360.0;
+        if !angles.contains(&self.inner_angle)
+            || !angles.contains(&self.outer_angle)
+            || self.inner_angle > self.outer_angle
+        {
+            fails!("invalid cone: angles must be between 0.0 and 360.0, inner within outer");
+            return None;
+        }
+        let Ok(outer_gain) = Amplitude::try_from(self.outer_gain) else {
+            fails!("invalid cone: outer gain cannot be negative");
+            return None;
+        };
+        if self.outer_cutoff != 0. && !(20.0..=20_000.0).contains(&self.outer_cutoff) {
+            fails!("invalid cone: outer cutoff must be between 20.0 and 20000.0 (or 0.0 for none)");
+            return None;
+        }
+        Some(Self::Settings {
+            inner_angle: self.inner_angle,
+            outer_angle: self.outer_angle,
+            outer_gain,
+            outer_cutoff: (self.outer_cutoff != 0.).then_some(self.outer_cutoff as f64),
+        })
+    }
+}
+
+impl ToSettings for EmitterOcclusion {
+    type Settings = audioware_core::OcclusionModel;
+    fn into_settings(self) -> Option<Self::Settings> {
+        let Ok(volume) = Amplitude::try_from(self.volume) else {
+            fails!("invalid occlusion: volume cannot be negative");
+            return None;
+        };
+        if volume.as_factor() > 1. {
+            fails!("invalid occlusion: volume must be between 0.0 and 1.0");
+            return None;
+        }
+        if !(20.0..=20_000.0).contains(&self.cutoff) {
+            fails!("invalid occlusion: cutoff must be between 20.0 and 20000.0");
+            return None;
+        }
+        let Ok(smoothing) = Duration::try_from_secs_f32(self.smoothing) else {
+            fails!("invalid occlusion: smoothing cannot be negative");
+            return None;
+        };
+        Some(Self::Settings {
+            volume,
+            cutoff: self.cutoff as f64,
+            smoothing,
+        })
+    }
+}
+
+impl ToSettings for EmitterCurve {
+    type Settings = audioware_core::DistanceCurve;
+    fn into_settings(self) -> Option<Self::Settings> {
+        if self.distances.len() != self.values.len() {
+            fails!("invalid curve: there must be one value for each distance");
+            return None;
+        }
+        let points = self
+            .distances
+            .iter()
+            .copied()
+            .zip(self.values.iter().copied())
+            .collect();
+        let curve = Self::Settings::new(points);
+        if curve.is_none() {
+            fails!("invalid curve: distances must be positive and increasing");
+        }
+        curve
+    }
+}
+
 impl ToSettings for EmitterDistances {
     type Settings = SpatialTrackDistances;
     fn into_settings(self) -> Option<Self::Settings> {
@@ -409,7 +640,7 @@ impl std::fmt::Display for TargetId {
     }
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct TargetFootprint((SpatialTrackSettings, NonZero<u64>));
 
 impl TargetFootprint {
@@ -420,6 +651,24 @@ impl TargetFootprint {
         use crate::engine::ToDistances;
         Ok(value.into_settings_ext(entity_id.to_distances()).map(Self))
     }
+    /// Without entity to infer default distances from.
+    pub fn try_new_positional(
+        value: Ref<super::EmitterSettings>,
+    ) -> Result<Option<Self>, Vec<ValidationError>> {
+        Ok(value.into_settings_ext(None).map(Self))
+    }
+}
+
+impl EmitterSettings {
+    /// Component slot and offset sounds come from, if any.
+    pub fn anchor(&self) -> Option<Anchor> {
+        let slot = Some(self.slot).filter(|x| x != &CName::undefined() && x.as_str() != "None");
+        let offset = self.offset;
+        if slot.is_none() && offset.x == 0. && offset.y == 0. && offset.z == 0. {
+            return None;
+        }
+        Some(Anchor { slot, offset })
+    }
 }
 
 impl Deref for TargetFootprint {
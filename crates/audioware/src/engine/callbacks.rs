@@ -12,7 +12,7 @@ use red4ext_rs::{
 use crate::{
     AddContainerStreamingPrefetchEvent, AnyTarget, AudioEventCallbackHandler,
     AudioEventCallbackSystem, ClassName, EngineSoundEvent, EventActionType, EventName,
-    FunctionName, PlayEvent, PlayExternalEvent, PlayOneShotEvent,
+    FunctionName, MarkerEvent, PlayEvent, PlayExternalEvent, PlayOneShotEvent,
     RemoveContainerStreamingPrefetchEvent, SetAppearanceNameEvent, SetEntityNameEvent,
     SetGlobalParameterEvent, SetParameterEvent, SetSwitchEvent, StopSoundEvent, StopTaggedEvent,
     TagEvent, UntagEvent,
@@ -197,6 +197,7 @@ where
             FireCallback::RemoveContainerStreamingPrefetch(f) => {
                 self.call(|x: &mut RemoveContainerStreamingPrefetchEvent| x.hydrate(f))
             }
+            FireCallback::Marker(f) => self.call(|x: &mut MarkerEvent| x.hydrate(f)),
         }
     }
 }
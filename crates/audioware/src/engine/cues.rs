@@ -0,0 +1,169 @@
+use std::time::{Duration, Instant};
+
+use audioware_bank::{BankCues, BankDeclaredSettings, Banks, Id, SceneId};
+use audioware_manifest::{Cue, ScnDialogLineType};
+use either::Either;
+use kira::backend::Backend;
+use red4ext_rs::types::{CName, EntityId};
+
+use crate::{
+    ControlId,
+    engine::{Engine, traits::position::PlayingPosition},
+    propagate_subtitles,
+};
+
+/// Timed subtitles waiting to be propagated
+/// while their sound plays.
+///
+/// Cues are borrowed from banks on each update.
+pub struct PendingCues {
+    control_id: ControlId,
+    entity_id: EntityId,
+    emitter_name: CName,
+    key: Either<Id, SceneId>,
+    next: usize,
+    /// When sound starts, if delayed.
+    starts: Option<Instant>,
+}
+
+impl PendingCues {
+    pub fn new(
+        control_id: ControlId,
+        entity_id: EntityId,
+        emitter_name: CName,
+        key: Either<Id, SceneId>,
+    ) -> Self {
+        Self {
+            control_id,
+            entity_id,
+            emitter_name,
+            key,
+            next: 0,
+            starts: None,
+        }
+    }
+    /// Hold cues back until sound starts, after `delay` if any.
+    pub fn delayed(mut self, delay: Option<Duration>) -> Self {
+        self.starts = delay.map(|x| Instant::now() + x);
+        self
+    }
+    fn cues<'a>(&self, banks: &'a Banks) -> Option<&'a [Cue]> {
+        match &self.key {
+            Either::Left(key) => banks.cues(key),
+            Either::Right(key) => banks.cues(key),
+        }
+    }
+    /// Time stretch speed, since audio is already stretched on load.
+    fn speed(&self, banks: &Banks) -> f64 {
+        match &self.key {
+            Either::Left(key) => banks.declared_settings(key),
+            Either::Right(key) => banks.declared_settings(key),
+        }
+        .and_then(|x| x.time_stretch)
+        .unwrap_or(1.)
+    }
+    fn cue_name(&self, banks: &Banks, nth: usize) -> Option<CName> {
+        match &self.key {
+            Either::Left(key) => banks.cue_name(key, nth),
+            Either::Right(key) => banks.cue_name(key, nth),
+        }
+    }
+    /// Propagate cues reached at given position,
+    /// returning whether some are still pending.
+    fn update(&mut self, position: f64, banks: &Banks) -> bool {
+        let Some(cues) = self.cues(banks) else {
+            return false;
+        };
+        let speed = self.speed(banks);
+        while let Some(cue) = cues.get(self.next) {
+            if cue.starts.as_secs_f64() / speed > position {
+                break;
+            }
+            let reaction = self.cue_name(banks, self.next);
+            self.next += 1;
+            let remaining = cue.ends.as_secs_f64() / speed - position;
+            // cues skipped over (e.g. on seek) are not displayed
+            if let Some(reaction) = reaction
+                && remaining > 0.
+            {
+                propagate_subtitles(
+                    reaction,
+                    self.entity_id,
+                    self.emitter_name,
+                    cue.line.line,
+                    remaining as f32,
+                );
+            }
+        }
+        self.next < cues.len()
+    }
+}
+
+/// Subtitle waiting for its delayed sound to start.
+pub struct DelayedSubtitle {
+    reaction: CName,
+    entity_id: EntityId,
+    emitter_name: CName,
+    line_type: ScnDialogLineType,
+    duration: f32,
+    starts: Instant,
+}
+
+impl DelayedSubtitle {
+    pub fn new(
+        reaction: CName,
+        entity_id: EntityId,
+        emitter_name: CName,
+        line_type: ScnDialogLineType,
+        duration: f32,
+        delay: Duration,
+    ) -> Self {
+        Self {
+            reaction,
+            entity_id,
+            emitter_name,
+            line_type,
+            duration,
+            starts: Instant::now() + delay,
+        }
+    }
+}
+
+impl<B: Backend> Engine<B> {
+    pub fn update_cues(&mut self) {
+        let now = Instant::now();
+        self.delayed_subtitles.retain(|x| {
+            if x.starts > now {
+                return true;
+            }
+            propagate_subtitles(
+                x.reaction,
+                x.entity_id,
+                x.emitter_name,
+                x.line_type,
+                x.duration,
+            );
+            false
+        });
+        if self.pending_cues.is_empty() {
+            return;
+        }
+        let tracks = &self.tracks;
+        let scene = self.scene.as_ref();
+        let banks = &self.banks;
+        self.pending_cues.retain_mut(|pending| {
+            if pending.starts.is_some_and(|x| x > now) {
+                return true;
+            }
+            let id = pending.control_id;
+            match tracks
+                .playing_position(id)
+                .or_else(|| scene.and_then(|x| x.playing_position(id)))
+            {
+                Some(position) => pending.update(position, banks),
+                // sound stopped or is gone
+                None => false,
+            }
+        });
+    }
+}
@@ -0,0 +1,191 @@
+//! Tracks automatically ducked while dialogues play.
+
+use std::{
+    collections::HashMap,
+    time::{Duration, Instant},
+};
+
+use audioware_manifest::{DuckSource, DuckTarget, Settings, Source, TrackDucking};
+use kira::{Decibels, Easing, StartTime, Tween};
+
+use crate::{
+    ControlId,
+    engine::modulators::{DuckLevels, Parameter},
+};
+
+/// Tracks ducked by sounds playing on dialogue, vocal or holocall tracks,
+/// unless configured otherwise in manifests.
+pub const DIALOGUE: &[DuckTarget] = &[DuckTarget::Music, DuckTarget::Sfx];
+pub const DEPTH: Decibels = Decibels(-9.);
+pub const ATTACK: Duration = Duration::from_millis(150);
+pub const RELEASE: Duration = Duration::from_millis(800);
+
+/// Sound ducking other tracks while it plays.
+#[derive(Debug, Clone)]
+pub struct Ducker {
+    pub control_id: ControlId,
+    pub targets: Vec<DuckTarget>,
+    pub depth: Decibels,
+    pub attack: Duration,
+    pub release: Duration,
+}
+
+impl Ducker {
+    /// Sound declared `duck` and `ducking` if any,
+    /// or else the ones of the `track` it plays on.
+    pub fn new(
+        control_id: ControlId,
+        settings: Option<&Settings>,
+        track: Option<DuckSource>,
+        matrix: &HashMap<DuckSource, TrackDucking>,
+    ) -> Option<Self> {
+        let targets = targets(settings, track, matrix);
+        if targets.is_empty() {
+            return None;
+        }
+        let declared = settings.and_then(|x| x.ducking).unwrap_or_default();
+        let configured = track
+            .and_then(|x| matrix.get(&x))
+            .map(|x| x.ducking)
+            .unwrap_or_default();
+        Some(Self {
+            control_id,
+            targets: targets.to_vec(),
+            depth: declared
+                .depth
+                .or(configured.depth)
+                .map(|x| x.as_decibels())
+                .unwrap_or(DEPTH),
+            attack: declared.attack.or(configured.attack).unwrap_or(ATTACK),
+            release: declared.release.or(configured.release).unwrap_or(RELEASE),
+        })
+    }
+}
+
+/// Whether sound would duck any track, see [Ducker::new].
+pub fn ducks(
+    settings: Option<&Settings>,
+    track: Option<DuckSource>,
+    matrix: &HashMap<DuckSource, TrackDucking>,
+) -> bool {
+    !targets(settings, track, matrix).is_empty()
+}
+
+/// Track sound from given source plays on, when not spatialized.
+pub fn track_of(source: &Source) -> DuckSource {
+    match source {
+        Source::Sfx | Source::Ono => DuckSource::Sfx,
+        Source::Voices => DuckSource::Dialogue,
+        Source::Playlist => DuckSource::Radioport,
+        Source::Music => DuckSource::Music,
+        Source::Jingle => DuckSource::CarRadio,
+    }
+}
+
+fn targets<'a>(
+    settings: Option<&'a Settings>,
+    track: Option<DuckSource>,
+    matrix: &'a HashMap<DuckSource, TrackDucking>,
+) -> &'a [DuckTarget] {
+    settings
+        .and_then(|x| x.duck.as_deref())
+        .or_else(|| {
+            track
+                .and_then(|x| matrix.get(&x))
+                .and_then(|x| x.duck.as_deref())
+        })
+        .unwrap_or(match track {
+            Some(DuckSource::Dialogue | DuckSource::Vocal | DuckSource::Holocall) => DIALOGUE,
+            _ => &[],
+        })
+}
+
+/// Sounds currently ducking, along with current depth and release of each ducked track.
+#[derive(Debug, Default)]
+pub struct Duckers {
+    active: Vec<Ducker>,
+    /// Sounds yet to start, see [Duckers::duck].
+    pending: Vec<(Instant, Ducker)>,
+    levels: HashMap<DuckTarget, (Decibels, Duration)>,
+}
+
+impl Duckers {
+    /// Duck tracks as soon as sound starts, after `delay` if any.
+    pub fn duck(&mut self, ducker: Ducker, delay: Option<Duration>, levels: &mut DuckLevels) {
+        match delay {
+            Some(delay) => self.pending.push((Instant::now() + delay, ducker)),
+            None => {
+                self.active.push(ducker);
+                self.apply(levels);
+            }
+        }
+    }
+    /// Duck tracks once delayed sounds start,
+    /// and release them once last sound ducking them ended.
+    pub fn sync(&mut self, alive: impl Fn(ControlId) -> bool, levels: &mut DuckLevels) {
+        if self.active.is_empty() && self.pending.is_empty() && self.levels.is_empty() {
+            return;
+        }
+        let now = Instant::now();
+        let count = self.active.len();
+        let mut started = false;
+        self.pending.retain(|(starts, ducker)| {
+            if *starts > now {
+                return true;
+            }
+            self.active.push(ducker.clone());
+            started = true;
+            false
+        });
+        self.active.retain(|x| alive(x.control_id));
+        if started || self.active.len() != count {
+            self.apply(levels);
+        }
+    }
+    /// Release all ducked tracks at once.
+    pub fn clear(&mut self, levels: &mut DuckLevels) {
+        self.active.clear();
+        self.pending.clear();
+        self.apply(levels);
+    }
+    fn apply(&mut self, levels: &mut DuckLevels) {
+        for target in DuckTarget::ALL {
+            let deepest = self
+                .active
+                .iter()
+                .filter(|x| x.targets.contains(&target))
+                .min_by(|a, b| a.depth.0.total_cmp(&b.depth.0));
+            let current = self.levels.get(&target).copied();
+            match (deepest, current) {
+                (Some(ducker), Some((depth, _))) if ducker.depth == depth => {
+                    self.levels.insert(target, (depth, ducker.release));
+                }
+                (Some(ducker), current) => {
+                    let duration = match current {
+                        Some((depth, release)) if ducker.depth.0 > depth.0 => release,
+                        _ => ducker.attack,
+                    };
+                    levels
+                        .level_mut(target)
+                        .update(ducker.depth, linear(duration));
+                    self.levels.insert(target, (ducker.depth, ducker.release));
+                }
+                (None, Some((_, release))) => {
+                    levels
+                        .level_mut(target)
+                        .update(Decibels::IDENTITY, linear(release));
+                    self.levels.remove(&target);
+                }
+                (None, None) => {}
+            }
+        }
+    }
+}
+
+fn linear(duration: Duration) -> Tween {
+    Tween {
+        start_time: StartTime::Immediate,
+        duration,
+        easing: Easing::Linear,
+    }
+}
@@ -1,4 +1,8 @@
-use kira::effect::filter::FilterHandle;
+use kira::{
+    Mix, Tween,
+    effect::filter::{FilterBuilder, FilterHandle, FilterMode},
+    track::TrackBuilder,
+};
 use red4ext_rs::NativeRepr;
 
 use crate::utils;
@@ -21,6 +25,21 @@ pub const EQ_LOW_PASS_UNDERWATER_CUTOFF: f64 = 500.;
 pub struct EQ {
     pub lowpass: LowPass,
     pub highpass: HighPass,
+    pub preset: Preset,
+    pub cutoffs: Cutoffs,
+}
+
+/// Cutoffs overriding [Preset] ones, e.g. set by mix snapshots.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct Cutoffs {
+    pub low_pass: Option<f64>,
+    pub high_pass: Option<f64>,
+}
+
+/// Filters of a single track, left open unless set by mix snapshots.
+pub struct TrackEQ {
+    lowpass: LowPass,
+    highpass: HighPass,
 }
 
 pub struct LowPass(pub FilterHandle);
@@ -54,45 +73,82 @@ pub trait EqPass {
     fn set_preset(&mut self, preset: Preset);
 }
 
-impl EqPass for LowPass {
-    fn set_preset(&mut self, preset: Preset) {
-        match preset {
-            Preset::None => {
-                self.0.set_mix(0., DEFAULT);
+impl LowPass {
+    fn apply(&mut self, preset: Preset, cutoff: Option<f64>, tween: Tween) {
+        match (cutoff, preset) {
+            (Some(cutoff), _) => {
+                self.0.set_cutoff(cutoff, tween);
+                self.0.set_mix(1., tween);
+            }
+            (None, Preset::None) => {
+                self.0.set_mix(0., tween);
             }
-            Preset::OnThePhone => {
-                self.0.set_cutoff(EQ_LOW_PASS_PHONE_CUTOFF, DEFAULT);
-                self.0.set_resonance(EQ_RESONANCE, DEFAULT);
-                self.0.set_mix(1., DEFAULT);
+            (None, Preset::OnThePhone) => {
+                self.0.set_cutoff(EQ_LOW_PASS_PHONE_CUTOFF, tween);
+                self.0.set_resonance(EQ_RESONANCE, tween);
+                self.0.set_mix(1., tween);
             }
-            Preset::Underwater => {
-                self.0.set_cutoff(EQ_LOW_PASS_UNDERWATER_CUTOFF, DEFAULT);
-                self.0.set_resonance(EQ_RESONANCE, DEFAULT);
-                self.0.set_mix(1., DEFAULT);
+            (None, Preset::Underwater) => {
+                self.0.set_cutoff(EQ_LOW_PASS_UNDERWATER_CUTOFF, tween);
+                self.0.set_resonance(EQ_RESONANCE, tween);
+                self.0.set_mix(1., tween);
             }
         }
     }
 }
 
-impl EqPass for HighPass {
-    fn set_preset(&mut self, preset: Preset) {
-        match preset {
-            Preset::None | Preset::Underwater => {
-                self.0.set_mix(0., DEFAULT);
+impl HighPass {
+    fn apply(&mut self, preset: Preset, cutoff: Option<f64>, tween: Tween) {
+        match (cutoff, preset) {
+            (Some(cutoff), _) => {
+                self.0.set_cutoff(cutoff, tween);
+                self.0.set_mix(1., tween);
+            }
+            (None, Preset::None | Preset::Underwater) => {
+                self.0.set_mix(0., tween);
             }
-            Preset::OnThePhone => {
-                self.0.set_cutoff(EQ_HIGH_PASS_PHONE_CUTOFF, DEFAULT);
-                self.0.set_resonance(EQ_RESONANCE, DEFAULT);
-                self.0.set_mix(1., DEFAULT);
+            (None, Preset::OnThePhone) => {
+                self.0.set_cutoff(EQ_HIGH_PASS_PHONE_CUTOFF, tween);
+                self.0.set_resonance(EQ_RESONANCE, tween);
+                self.0.set_mix(1., tween);
             }
         }
     }
 }
 
+impl EQ {
+    /// Override preset cutoffs, falling back to preset for the ones unset.
+    pub fn set_cutoffs(&mut self, cutoffs: Cutoffs, tween: Tween) {
+        self.cutoffs = cutoffs;
+        self.lowpass.apply(self.preset, cutoffs.low_pass, tween);
+        self.highpass.apply(self.preset, cutoffs.high_pass, tween);
+    }
+}
+
 impl EqPass for EQ {
     fn set_preset(&mut self, preset: Preset) {
-        self.lowpass.set_preset(preset);
-        self.highpass.set_preset(preset);
+        self.preset = preset;
+        self.lowpass.apply(preset, self.cutoffs.low_pass, DEFAULT);
+        self.highpass.apply(preset, self.cutoffs.high_pass, DEFAULT);
         utils::silly!("updated preset successfully to {preset}");
     }
 }
+
+impl TrackEQ {
+    pub fn add(builder: &mut TrackBuilder) -> Self {
+        Self {
+            lowpass: LowPass(builder.add_effect(FilterBuilder::default().mix(Mix::DRY))),
+            highpass: HighPass(
+                builder.add_effect(
+                    FilterBuilder::default()
+                        .mode(FilterMode::HighPass)
+                        .mix(Mix::DRY),
+                ),
+            ),
+        }
+    }
+    pub fn set_cutoffs(&mut self, cutoffs: Cutoffs, tween: Tween) {
+        self.lowpass.apply(Preset::None, cutoffs.low_pass, tween);
+        self.highpass.apply(Preset::None, cutoffs.high_pass, tween);
+    }
+}
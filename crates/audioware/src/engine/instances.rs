@@ -0,0 +1,194 @@
+//! Per-sound cap on instances playing at once.
+
+use std::{
+    cmp::Ordering,
+    collections::HashMap,
+    time::{Duration, Instant},
+};
+
+use audioware_manifest::{InstanceLimit, InstanceScope, Settings, Steal};
+use kira::{Decibels, Tween};
+use red4ext_rs::types::{CName, EntityId};
+
+use crate::{
+    ControlId,
+    engine::{
+        Scene,
+        tracks::Tracks,
+        traits::{position::PlayingPosition, stop::StopControlled, volume::PlayingVolume},
+        tweens::STOLEN,
+    },
+    utils::lifecycle,
+};
+
+/// Instance of a sound declared with `max_instances`.
+#[derive(Debug, Clone, Copy)]
+pub struct Instance {
+    pub control_id: ControlId,
+    pub started: Instant,
+    pub entity_id: Option<EntityId>,
+    /// Emitter tag name, when played on emitter.
+    pub tag_name: Option<CName>,
+}
+
+impl Instance {
+    /// Instance starting after `delay` if any.
+    pub fn new(
+        control_id: ControlId,
+        entity_id: Option<EntityId>,
+        tag_name: Option<CName>,
+        delay: Option<Duration>,
+    ) -> Self {
+        Self {
+            control_id,
+            started: Instant::now() + delay.unwrap_or_default(),
+            entity_id,
+            tag_name,
+        }
+    }
+    fn shares_scope(&self, scope: InstanceScope, other: &Self) -> bool {
+        match scope {
+            InstanceScope::Global => true,
+            InstanceScope::PerEntity => self.entity_id == other.entity_id,
+            InstanceScope::PerEmitter => {
+                self.entity_id == other.entity_id && self.tag_name == other.tag_name
+            }
+        }
+    }
+}
+
+/// Whether a new instance may play.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Admission {
+    Admit,
+    /// Instance to fade out to make room.
+    Steal(ControlId),
+    Reject,
+}
+
+/// Where an instance stands relatively to listener.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Placement {
+    pub distance: f32,
+    pub gain: Decibels,
+}
+
+/// Instances of sounds declared with `max_instances`, per event name.
+#[derive(Debug, Default)]
+pub struct Instances(HashMap<CName, Vec<Instance>>);
+
+impl Instances {
+    /// Decide whether `candidate` may play, and which instance it would steal if any.
+    ///
+    /// Nothing gets stopped nor forgotten yet, see [Instances::admitted].
+    pub fn admit(
+        &mut self,
+        event_name: CName,
+        limit: InstanceLimit,
+        candidate: &Instance,
+        alive: impl Fn(ControlId) -> bool,
+        placement: impl Fn(&Instance) -> Placement,
+        volume: impl Fn(ControlId) -> Decibels,
+    ) -> Admission {
+        let Some(instances) = self.0.get_mut(&event_name) else {
+            return Admission::Admit;
+        };
+        instances.retain(|x| alive(x.control_id));
+        let competing = instances
+            .iter()
+            .filter(|x| x.shares_scope(limit.scope, candidate));
+        if competing.clone().count() < limit.max as usize {
+            return Admission::Admit;
+        }
+        let victim = match limit.steal {
+            Steal::RejectNew => return Admission::Reject,
+            Steal::Oldest => competing.min_by_key(|x| x.started),
+            Steal::Quietest => competing.min_by(|a, b| {
+                let a = volume(a.control_id).0 + placement(a).gain.0;
+                let b = volume(b.control_id).0 + placement(b).gain.0;
+                a.total_cmp(&b)
+            }),
+            Steal::Farthest => competing.max_by(|a, b| {
+                placement(a)
+                    .distance
+                    .partial_cmp(&placement(b).distance)
+                    .unwrap_or(Ordering::Equal)
+            }),
+        };
+        match victim {
+            Some(x) => Admission::Steal(x.control_id),
+            None => Admission::Admit,
+        }
+    }
+    /// Decide whether `candidate` may play, comparing instances as they currently play.
+    pub fn admission(
+        &mut self,
+        event_name: CName,
+        limit: InstanceLimit,
+        candidate: &Instance,
+        tracks: &Tracks,
+        scene: Option<&Scene>,
+    ) -> Admission {
+        let admission = self.admit(
+            event_name,
+            limit,
+            candidate,
+            |id| {
+                tracks.playing_position(id).is_some()
+                    || scene.is_some_and(|x| x.playing_position(id).is_some())
+            },
+            |x| {
+                scene
+                    .zip(x.entity_id)
+                    .and_then(|(scene, entity_id)| scene.placement(&entity_id, x.tag_name.as_ref()))
+                    .unwrap_or_default()
+            },
+            |id| {
+                tracks
+                    .playing_volume(id)
+                    .or_else(|| scene.and_then(|x| x.playing_volume(id)))
+                    .unwrap_or(Decibels::IDENTITY)
+            },
+        );
+        if admission == Admission::Reject {
+            lifecycle!("too many instances, rejected {event_name}");
+        }
+        admission
+    }
+    /// Keep track of `instance` once it plays, fading out the one it stole if any.
+    pub fn admitted(
+        &mut self,
+        event_name: CName,
+        instance: Instance,
+        admission: Admission,
+        fade_out: Option<Tween>,
+        tracks: &mut Tracks,
+        scene: Option<&mut Scene>,
+    ) {
+        if let Admission::Steal(id) = admission {
+            if let Some(instances) = self.0.get_mut(&event_name) {
+                instances.retain(|x| x.control_id != id);
+            }
+            let tween = fade_out.unwrap_or(STOLEN);
+            tracks.stop_controlled(id, tween);
+            if let Some(scene) = scene {
+                scene.stop_controlled(id, tween);
+            }
+            lifecycle!("too many instances, stole one of {event_name}");
+        }
+        self.insert(event_name, instance);
+    }
+    pub fn insert(&mut self, event_name: CName, instance: Instance) {
+        self.0.entry(event_name).or_default().push(instance);
+    }
+    pub fn clear(&mut self) {
+        self.0.clear();
+    }
+}
+
+/// Fade-out tween sound is declared with, if any.
+pub fn declared_fade_out(settings: Option<&Settings>) -> Option<Tween> {
+    settings
+        .and_then(|x| x.fade_out_tween.clone())
+        .map(Tween::from)
+}
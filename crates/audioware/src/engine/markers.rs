@@ -0,0 +1,150 @@
+use audioware_manifest::Settings;
+use kira::backend::Backend;
+use red4ext_rs::types::{CName, Cruid, EntityId};
+
+use crate::{
+    ControlId, EventName,
+    abi::callback::{FireCallback, FireMarkerCallback},
+    engine::{Engine, callbacks::Dispatch, traits::position::PlayingPosition},
+    utils::warns,
+};
+
+/// Named markers notified to scripts
+/// whenever their sound plays through them.
+pub struct PendingMarkers {
+    control_id: ControlId,
+    event_name: EventName,
+    entity_id: EntityId,
+    emitter_name: CName,
+    /// Markers name and position (in seconds), sorted by position.
+    markers: Vec<(CName, f64)>,
+    /// Whether sound plays backwards.
+    reverse: bool,
+    /// Position on last update, if any.
+    last: Option<f64>,
+}
+
+/// Event name markers of a scene dialog are notified with:
+/// its RUID, as WolvenKit and Codeware represent it.
+pub fn scene_event_name(cruid: Cruid) -> CName {
+    CName::new(&(i64::from(cruid) as u64).to_string())
+}
+
+/// Whether settings declare any marker.
+pub fn has_markers(settings: Option<&Settings>) -> bool {
+    settings.is_some_and(|x| x.markers.as_ref().is_some_and(|x| !x.is_empty()))
+}
+
+impl PendingMarkers {
+    /// Markers declared in settings, if any.
+    fn try_new(
+        control_id: ControlId,
+        event_name: EventName,
+        entity_id: Option<EntityId>,
+        emitter_name: Option<CName>,
+        settings: Option<&Settings>,
+    ) -> Option<Self> {
+        let settings = settings?;
+        // audio is already stretched on load
+        let speed = settings.time_stretch.unwrap_or(1.);
+        let mut markers = settings
+            .markers
+            .as_deref()?
+            .iter()
+            .map(|x| (CName::new(&x.name), x.time.as_secs_f64() / speed))
+            .collect::<Vec<_>>();
+        markers.sort_by(|a, b| a.1.total_cmp(&b.1));
+        Some(Self {
+            control_id,
+            event_name,
+            entity_id: entity_id.unwrap_or_default(),
+            emitter_name: emitter_name.unwrap_or(CName::undefined()),
+            markers,
+            reverse: settings.reverse.unwrap_or(false),
+            last: None,
+        })
+    }
+    /// Watch markers declared in settings, if any.
+    pub fn watch(
+        pending: &mut Vec<Self>,
+        control_id: Option<ControlId>,
+        event_name: CName,
+        entity_id: Option<EntityId>,
+        emitter_name: Option<CName>,
+        settings: Option<&Settings>,
+    ) {
+        let Some(control_id) = control_id.filter(|_| has_markers(settings)) else {
+            return;
+        };
+        let Ok(event_name) = EventName::try_from(event_name) else {
+            warns!("cannot notify markers for undefined event name");
+            return;
+        };
+        pending.extend(Self::try_new(
+            control_id,
+            event_name,
+            entity_id,
+            emitter_name,
+            settings,
+        ));
+    }
+    /// Whether marker was crossed between last update and position.
+    fn crossed(reverse: bool, last: Option<f64>, position: f64, marker: f64) -> bool {
+        match reverse {
+            false => last.is_none_or(|last| marker > last) && marker <= position,
+            true => last.is_none_or(|last| marker <= last) && marker > position,
+        }
+    }
+    /// Notify markers crossed since last update.
+    fn update(&mut self, position: f64, fired: &mut Vec<FireCallback>) {
+        // whenever looping or seeking against playback direction, markers are crossed anew
+        let last = self.last.filter(|x| match self.reverse {
+            false => *x <= position,
+            true => *x >= position,
+        });
+        self.last = Some(position);
+        let reverse = self.reverse;
+        fired.extend(
+            self.markers
+                .iter()
+                .filter(|(_, x)| Self::crossed(reverse, last, position, *x))
+                .map(|(name, time)| {
+                    FireCallback::Marker(FireMarkerCallback {
+                        event_name: self.event_name,
+                        marker_name: *name,
+                        entity_id: self.entity_id,
+                        emitter_name: self.emitter_name,
+                        time: *time as f32,
+                    })
+                }),
+        );
+    }
+}
+
+impl<B: Backend> Engine<B> {
+    pub fn update_markers(&mut self) {
+        if self.pending_markers.is_empty() {
+            return;
+        }
+        let tracks = &self.tracks;
+        let scene = self.scene.as_ref();
+        let mut fired = vec![];
+        self.pending_markers.retain_mut(|pending| {
+            let id = pending.control_id;
+            match tracks
+                .playing_position(id)
+                .or_else(|| scene.and_then(|x| x.playing_position(id)))
+            {
+                Some(position) => {
+                    pending.update(position, &mut fired);
+                    true
+                }
+                // sound stopped or is gone
+                None => false,
+            }
+        });
+        for fire in fired {
+            self.dispatch(fire);
+        }
+    }
+}
@@ -1,4 +1,5 @@
 use std::{
+    borrow::Borrow,
     fmt::Debug,
     num::NonZero,
     ops::{Div, Not},
@@ -6,14 +7,18 @@ use std::{
 };
 
 use audioware_bank::{
-    BankData, BankSettings, Banks, Id, Initialization, InitializationOutcome, SceneId, TryGet,
-    error::registry::ErrorDisplay,
+    BankData, BankDeclaredSettings, BankSettings, Banks, Id, Initialization, InitializationOutcome,
+    SceneId, TryGet, error::registry::ErrorDisplay,
 };
-use audioware_core::{Amplitude, SceneDialogSettings, SpatialTrackSettings, With};
-use audioware_manifest::{Locale, ScnDialogLineType, Source, ValidateFor};
+use audioware_core::{
+    Amplitude, SceneDialogSettings, SpatialTrackSettings, With, base_playback_rate, base_volume,
+};
+use audioware_manifest::{DuckSource, Locale, ScnDialogLineType, Settings, Source, ValidateFor};
 use debug_ignore::DebugIgnore;
+use ducking::{Duckers, track_of};
 use either::Either;
 use eq::{EqPass, Preset};
+use instances::Instances;
 use kira::{
     AudioManager, AudioManagerSettings, Decibels, Easing, StartTime, Tween,
     backend::Backend,
@@ -23,20 +28,29 @@ use kira::{
 use modulators::{Modulators, Parameter};
 pub use mutes::{AudioEventManager, Mute};
 use red4ext_rs::types::{CName, Cruid, EntityId, GameInstance, Opt, WeakRef};
-pub use scene::{AffectedByTimeDilation, DilationUpdate, Scene};
+use retriggers::delay_start;
+pub use scene::{AffectedByTimeDilation, Anchor, DilationUpdate, Scene};
+use snapshots::Snapshots;
 use state::{SpokenLocale, ToGender};
 use tracks::Tracks;
-use tweens::{DEFAULT, IMMEDIATELY, LAST_BREATH};
+use tweens::{BLENDED, DEFAULT, IMMEDIATELY, LAST_BREATH};
+
+use cues::{DelayedSubtitle, PendingCues};
+use markers::{PendingMarkers, scene_event_name};
+use plays::{Origin, Speaker};
 
 use crate::{
     AsAudioSystem, AsGameInstance, AsGameObjectExt, CameraComponent, ControlId, GameObject,
+    PositionalId, Vector4,
     abi::{callback::Callback, lifecycle::ReplacementNotification},
     engine::{
-        tracks::TrackEntryOptions,
-        traits::{Handle, stop::StopBy, store::Store},
+        tracks::{Inserts, TrackEntryOptions},
+        traits::{
+            Handle, position::PlayingPosition, release::ReleasePolicy, stop::StopBy, store::Store,
+        },
     },
     error::{EngineError, Error},
-    propagate_subtitles, resolve_any_entity,
+    resolve_any_entity,
     utils::{fails, lifecycle, success, warns},
 };
 
@@ -50,9 +64,16 @@ pub mod traits;
 
 mod callbacks;
 mod controls;
+mod cues;
+mod ducking;
+mod instances;
+mod markers;
 mod modulators;
 mod mutes;
+mod plays;
+mod retriggers;
 mod scene;
+mod snapshots;
 mod tracks;
 mod tweens;
 
@@ -63,14 +84,22 @@ static BANKS: parking_lot::RwLock<Option<Banks>> = parking_lot::RwLock::new(None
 
 pub struct Engine<B: Backend> {
     pub scene: Option<Scene>,
+    /// Scenes let go of, until their sounds play out their declared release.
+    pub released_scenes: Vec<Scene>,
     pub tracks: Tracks,
     pub modulators: Modulators,
     pub manager: AudioManager<B>,
     pub report: Initialization,
     pub banks: Banks,
     pub last_volume: Option<Decibels>,
+    pub instances: Instances,
+    pub duckers: Duckers,
+    pub snapshots: Snapshots,
     pub pending_mutes: Vec<ReplacementNotification>,
     pub pending_callbacks: Vec<Callback>,
+    pub pending_cues: Vec<PendingCues>,
+    pub delayed_subtitles: Vec<DelayedSubtitle>,
+    pub pending_markers: Vec<PendingMarkers>,
 }
 
 #[cfg(debug_assertions)]
@@ -104,12 +133,19 @@ where
             banks,
             manager,
             scene: None,
+            released_scenes: Vec::new(),
             modulators,
             tracks,
             report,
             last_volume: None,
+            instances: Instances::default(),
+            duckers: Duckers::default(),
+            snapshots: Snapshots::default(),
             pending_mutes: Vec::with_capacity(32),
             pending_callbacks: Vec::with_capacity(32),
+            pending_cues: Vec::with_capacity(8),
+            delayed_subtitles: Vec::with_capacity(8),
+            pending_markers: Vec::with_capacity(8),
         })
     }
 
@@ -156,8 +192,8 @@ where
     }
 
     pub fn stop_scene_emitters_and_actors(&mut self) {
-        if let Some(mut scene) = self.scene.take() {
-            scene.stop_emitters_and_actors(IMMEDIATELY);
+        if let Some(scene) = self.scene.take() {
+            self.released_scenes.extend(scene.release());
         }
     }
 
@@ -169,62 +205,121 @@ where
         control_id: Option<ControlId>,
     ) {
         let spoken = SpokenLocale::get();
-        match self.banks.ids.try_get(&event_name, &spoken, Some(&gender)) {
-            Ok(key) => {
-                let data = self.banks.data(key);
-                let destination = &mut self.tracks.holocall;
-                let dilatable = true;
-                let duration: f32;
-                match data {
-                    Either::Left(data) => {
-                        duration = data.duration().as_secs_f32();
-                        if let Ok(handle) = destination.play(data) {
-                            self.tracks.handles.statics.store(Handle::new(
-                                event_name,
-                                handle,
-                                TrackEntryOptions {
-                                    entity_id: None,
-                                    emitter_name: Some(emitter_name),
-                                    affected_by_time_dilation: dilatable,
-                                },
-                                control_id,
-                            ));
-                        }
-                    }
-                    Either::Right(data) => {
-                        duration = data.duration().as_secs_f32();
-                        if let Ok(handle) = destination.play(data) {
-                            self.tracks.handles.streams.store(Handle::new(
-                                event_name,
-                                handle,
-                                TrackEntryOptions {
-                                    entity_id: None,
-                                    emitter_name: Some(emitter_name),
-                                    affected_by_time_dilation: dilatable,
-                                },
-                                control_id,
-                            ));
-                        }
-                    }
-                };
-                if !emitter_name.as_str().is_empty() && emitter_name.as_str() != "None" {
-                    propagate_subtitles(
-                        event_name,
-                        EntityId::default(),
-                        emitter_name,
-                        ScnDialogLineType::Holocall,
-                        duration,
-                    );
-                } else if *key.source() == Source::Voices {
-                    warns!(
-                        "cannot propagate subtitles for voice, emitterName must be defined: {event_name}"
+        let key = match self.banks.ids.try_get(&event_name, &spoken, Some(&gender)) {
+            Ok(key) => key.clone(),
+            Err(e) => {
+                warns!("cannot play over the phone: {e}");
+                return;
+            }
+        };
+        let origin = Origin {
+            event_name,
+            entity_id: None,
+            tag_name: Some(emitter_name),
+        };
+        let Some(prepared) = self.prepare(
+            Either::Left(&key),
+            origin,
+            Some(DuckSource::Holocall),
+            control_id,
+        ) else {
+            return;
+        };
+        let control_id = prepared.control_id;
+        let settings = self.banks.declared_settings(&key);
+        let destination: &mut TrackHandle = &mut self.tracks.holocall;
+        let time_dilation = settings
+            .map(AffectedByTimeDilation::time_dilation)
+            .unwrap_or_default();
+        let mut inserts = destination.inserts(settings);
+        let destination = inserts.as_deref_mut().unwrap_or(destination);
+        let mut data = self.banks.data(&key);
+        let duration = data
+            .as_ref()
+            .either(|x| x.duration(), |x| x.duration())
+            .as_secs_f32();
+        if let Some(delay) = prepared.delay {
+            delay_start(&mut data, delay);
+        }
+        let stored = match data {
+            Either::Left(data) => {
+                let release = ReleasePolicy::new(settings, data.settings.playback_rate);
+                let rate = base_playback_rate(data.settings.playback_rate);
+                let volume = base_volume(data.settings.volume);
+                if let Ok(handle) = destination.play(data) {
+                    self.tracks.handles.statics.store(
+                        Handle::new(
+                            event_name,
+                            handle,
+                            TrackEntryOptions {
+                                entity_id: None,
+                                emitter_name: Some(emitter_name),
+                                time_dilation,
+                            },
+                            control_id,
+                            release,
+                            rate,
+                            volume,
+                        )
+                        .with_inserts(inserts),
                     );
+                    true
+                } else {
+                    false
                 }
             }
-            Err(e) => {
-                warns!("cannot play over the phone: {e}");
+            Either::Right(data) => {
+                let release = ReleasePolicy::new(settings, data.settings.playback_rate);
+                let rate = base_playback_rate(data.settings.playback_rate);
+                let volume = base_volume(data.settings.volume);
+                if let Ok(handle) = destination.play(data) {
+                    self.tracks.handles.streams.store(
+                        Handle::new(
+                            event_name,
+                            handle,
+                            TrackEntryOptions {
+                                entity_id: None,
+                                emitter_name: Some(emitter_name),
+                                time_dilation,
+                            },
+                            control_id,
+                            release,
+                            rate,
+                            volume,
+                        )
+                        .with_inserts(inserts),
+                    );
+                    true
+                } else {
+                    false
+                }
             }
+        };
+        if !stored {
+            return;
         }
+        let speaker = if !emitter_name.as_str().is_empty() && emitter_name.as_str() != "None" {
+            Some(Speaker {
+                entity_id: EntityId::default(),
+                emitter_name,
+                line_type: ScnDialogLineType::Holocall,
+                duration: Some(duration),
+            })
+        } else {
+            if *key.source() == Source::Voices {
+                warns!(
+                    "cannot propagate subtitles for voice, emitterName must be defined: {event_name}"
+                );
+            }
+            None
+        };
+        self.played(
+            Either::Left(&key),
+            origin,
+            prepared,
+            Some(emitter_name),
+            speaker,
+        );
     }
 
     #[allow(clippy::too_many_arguments)]
@@ -249,12 +344,14 @@ where
             warns!("cannot play sound on undefined entity: {}", entity_id);
             return;
         }
-        let Some(ref mut scene) = self.scene else {
+        if self.scene.is_none() {
             warns!("scene is not available, and therefore dialogs aren't either");
             return;
-        };
-        let gender = entity_id.to_gender();
+        }
         let spoken = SpokenLocale::get();
+        let gender = entity_id
+            .to_gender()
+            .or_else(|| self.banks.any_scene_gender(&string_id, &spoken));
         let Ok(key) = self
             .banks
             .scene_ids
@@ -264,56 +361,130 @@ where
             return;
         };
         let key = key.clone();
+        let track = match (is_player, is_holocall) {
+            (true, _) => DuckSource::Vocal,
+            (_, true) => DuckSource::Holocall,
+            _ => DuckSource::Dialogue,
+        };
+        let origin = Origin {
+            event_name: scene_event_name(string_id),
+            entity_id: Some(entity_id),
+            tag_name: None,
+        };
+        let Some(prepared) = self.prepare(Either::Right(&key), origin, Some(track), control_id)
+        else {
+            return;
+        };
+        let control_id = prepared.control_id;
+        let settings = self.banks.declared_settings(&key);
         let scene_settings = SceneDialogSettings {
             is_rewind,
-            seek_time,
+            // audio is already stretched on load
+            seek_time: seek_time / settings.and_then(|x| x.time_stretch).unwrap_or(1.) as f32,
+        };
+        let Some(ref mut scene) = self.scene else {
+            return;
         };
-        if is_player {
+        let stored = if is_player {
             let data = self.banks.data(&key);
             let destination: &mut TrackHandle = &mut self.tracks.v.vocal;
+            let mut inserts = destination.inserts(settings);
+            let destination = inserts.as_deref_mut().unwrap_or(destination);
             match data.with(scene_settings) {
                 Either::Left(data) => {
+                    let release = ReleasePolicy::new(settings, data.settings.playback_rate);
+                    let rate = base_playback_rate(data.settings.playback_rate);
+                    let volume = base_volume(data.settings.volume);
                     if let Ok(handle) = destination.play(data) {
-                        scene
-                            .actors
-                            .v
-                            .store(Handle::new(string_id, handle, (), control_id));
+                        scene.actors.v.store(
+                            Handle::new(string_id, handle, (), control_id, release, rate, volume)
+                                .with_inserts(inserts),
+                        );
+                        true
+                    } else {
+                        false
                     }
                 }
                 Either::Right(data) => {
+                    let release = ReleasePolicy::new(settings, data.settings.playback_rate);
+                    let rate = base_playback_rate(data.settings.playback_rate);
+                    let volume = base_volume(data.settings.volume);
                     if let Ok(handle) = destination.play(data) {
-                        scene
-                            .actors
-                            .v
-                            .store(Handle::new(string_id, handle, (), control_id));
+                        scene.actors.v.store(
+                            Handle::new(string_id, handle, (), control_id, release, rate, volume)
+                                .with_inserts(inserts),
+                        );
+                        true
+                    } else {
+                        false
                     }
                 }
             }
         } else if is_holocall {
             let data = self.banks.data(&key);
             let destination: &mut TrackHandle = &mut self.tracks.holocall;
+            let mut inserts = destination.inserts(settings);
+            let destination = inserts.as_deref_mut().unwrap_or(destination);
             match data.with(scene_settings) {
                 Either::Left(data) => {
+                    let release = ReleasePolicy::new(settings, data.settings.playback_rate);
+                    let rate = base_playback_rate(data.settings.playback_rate);
+                    let volume = base_volume(data.settings.volume);
                     if let Ok(handle) = destination.play(data) {
-                        scene
-                            .actors
-                            .holocall
-                            .store(Handle::new(string_id, handle, (), control_id));
+                        scene.actors.holocall.store(
+                            Handle::new(string_id, handle, (), control_id, release, rate, volume)
+                                .with_inserts(inserts),
+                        );
+                        true
+                    } else {
+                        false
                     }
                 }
                 Either::Right(data) => {
+                    let release = ReleasePolicy::new(settings, data.settings.playback_rate);
+                    let rate = base_playback_rate(data.settings.playback_rate);
+                    let volume = base_volume(data.settings.volume);
                     if let Ok(handle) = destination.play(data) {
-                        scene
-                            .actors
-                            .holocall
-                            .store(Handle::new(string_id, handle, (), control_id));
+                        scene.actors.holocall.store(
+                            Handle::new(string_id, handle, (), control_id, release, rate, volume)
+                                .with_inserts(inserts),
+                        );
+                        true
+                    } else {
+                        false
                     }
                 }
             }
         } else {
-            self.play_on_actor(string_id, entity_id, &key, scene_settings, control_id);
+            self.play_on_actor(string_id, entity_id, &key, scene_settings, control_id)
+        };
+        if !stored {
+            return;
         }
-        // red engine handles subtitles automatically
+        // red engine handles subtitles automatically, unless timed subtitles are defined
+        let speaker = if prepared.cues {
+            let emitter_name = resolve_any_entity(entity_id)
+                .cast::<GameObject>()
+                .and_then(|x| x.is_null().not().then(|| x.resolve_display_name()));
+            match emitter_name {
+                Some(emitter_name) => Some(Speaker {
+                    entity_id,
+                    emitter_name: CName::new(emitter_name.as_str()),
+                    line_type: ScnDialogLineType::default(),
+                    duration: None,
+                }),
+                None => {
+                    warns!(
+                        "cannot propagate subtitles for scene dialog, couldn't resolve emitter name: {} [{entity_id}]",
+                        string_id.error_display()
+                    );
+                    None
+                }
+            }
+        } else {
+            None
+        };
+        self.played(Either::Right(&key), origin, prepared, None, speaker);
     }
 
     pub fn play<T>(
@@ -325,92 +496,147 @@ where
         line_type: Option<ScnDialogLineType>,
         control_id: Option<ControlId>,
     ) where
-        StaticSoundData: With<Option<T>>,
-        StreamingSoundData<FromFileError>: With<Option<T>>,
+        Either<StaticSoundData, StreamingSoundData<FromFileError>>: With<Option<T>>,
         T: AffectedByTimeDilation
             + ValidateFor<Either<StaticSoundData, StreamingSoundData<FromFileError>>>,
     {
         let spoken = SpokenLocale::get();
-        let gender = entity_id.as_ref().and_then(ToGender::to_gender);
-        match self
+        let gender = entity_id
+            .as_ref()
+            .and_then(ToGender::to_gender)
+            .or_else(|| self.banks.any_gender(&event_name, &spoken));
+        let key = match self
             .banks
             .ids
             .try_get(&event_name, &spoken, gender.as_ref())
         {
-            Ok(key) => {
-                let data = self.banks.data(key);
-                if let Some(Err(e)) = ext.as_ref().map(|x| x.validate_for(&data)) {
-                    warns!("invalid setting(s) for audio: {e:#?}");
-                    return;
+            Ok(key) => key.clone(),
+            Err(e) => {
+                warns!("cannot play sound: {e}");
+                return;
+            }
+        };
+        let data = self.banks.data(&key);
+        if let Some(Err(e)) = ext.as_ref().map(|x| x.validate_for(&data)) {
+            warns!("invalid setting(s) for audio: {e:#?}");
+            return;
+        }
+        let is_v = self
+            .scene
+            .as_ref()
+            .is_some_and(|x| Some(x.listener_id()) == entity_id);
+        let track = match is_v {
+            true => key.is_vocal().then_some(DuckSource::Vocal),
+            false => Some(track_of(key.source())),
+        };
+        let origin = Origin {
+            event_name,
+            entity_id,
+            tag_name: emitter_name,
+        };
+        let Some(prepared) = self.prepare(Either::Left(&key), origin, track, control_id) else {
+            return;
+        };
+        let control_id = prepared.control_id;
+        let settings = self.banks.declared_settings(&key);
+        let time_dilation = ext
+            .as_ref()
+            .map(AffectedByTimeDilation::time_dilation)
+            .or_else(|| settings.map(AffectedByTimeDilation::time_dilation))
+            .unwrap_or_default();
+        let destination: &mut TrackHandle = if is_v {
+            if key.is_vocal() {
+                &mut self.tracks.v.vocal
+            } else {
+                &mut self.tracks.v.emissive
+            }
+        } else {
+            key.to_output_destination(&mut self.tracks)
+        };
+        let mut inserts = destination.inserts(settings);
+        let destination = inserts.as_deref_mut().unwrap_or(destination);
+        let duration = data
+            .as_ref()
+            .either(|x| x.duration(), |x| x.duration())
+            .as_secs_f32();
+        let mut data = data.with(ext);
+        if let Some(delay) = prepared.delay {
+            delay_start(&mut data, delay);
+        }
+        let stored = match data {
+            Either::Left(data) => {
+                let release = ReleasePolicy::new(settings, data.settings.playback_rate);
+                let rate = base_playback_rate(data.settings.playback_rate);
+                let volume = base_volume(data.settings.volume);
+                if let Ok(handle) = destination.play(data) {
+                    self.tracks.handles.statics.store(
+                        Handle::new(
+                            event_name,
+                            handle,
+                            TrackEntryOptions {
+                                entity_id,
+                                emitter_name,
+                                time_dilation,
+                            },
+                            control_id,
+                            release,
+                            rate,
+                            volume,
+                        )
+                        .with_inserts(inserts),
+                    );
+                    true
+                } else {
+                    false
                 }
-                let duration: f32;
-                let dilatable = ext
-                    .as_ref()
-                    .map(AffectedByTimeDilation::affected_by_time_dilation)
-                    .unwrap_or(true);
-                let is_v = self
-                    .scene
-                    .as_ref()
-                    .is_some_and(|x| Some(x.listener_id()) == entity_id);
-                let destination: &mut TrackHandle = if is_v {
-                    if key.is_vocal() {
-                        &mut self.tracks.v.vocal
-                    } else {
-                        &mut self.tracks.v.emissive
-                    }
+            }
+            Either::Right(data) => {
+                let release = ReleasePolicy::new(settings, data.settings.playback_rate);
+                let rate = base_playback_rate(data.settings.playback_rate);
+                let volume = base_volume(data.settings.volume);
+                if let Ok(handle) = destination.play(data) {
+                    self.tracks.handles.streams.store(
+                        Handle::new(
+                            event_name,
+                            handle,
+                            TrackEntryOptions {
+                                entity_id,
+                                emitter_name,
+                                time_dilation,
+                            },
+                            control_id,
+                            release,
+                            rate,
+                            volume,
+                        )
+                        .with_inserts(inserts),
+                    );
+                    true
                 } else {
-                    key.to_output_destination(&mut self.tracks)
-                };
-                match data {
-                    Either::Left(data) => {
-                        duration = data.duration().as_secs_f32();
-                        if let Ok(handle) = destination.play(data.with(ext)) {
-                            self.tracks.handles.statics.store(Handle::new(
-                                event_name,
-                                handle,
-                                TrackEntryOptions {
-                                    entity_id,
-                                    emitter_name,
-                                    affected_by_time_dilation: dilatable,
-                                },
-                                control_id,
-                            ));
-                        }
-                    }
-                    Either::Right(data) => {
-                        duration = data.duration().as_secs_f32();
-                        if let Ok(handle) = destination.play(data.with(ext)) {
-                            self.tracks.handles.streams.store(Handle::new(
-                                event_name,
-                                handle,
-                                TrackEntryOptions {
-                                    entity_id,
-                                    emitter_name,
-                                    affected_by_time_dilation: dilatable,
-                                },
-                                control_id,
-                            ));
-                        }
-                    }
+                    false
                 }
-                if let (Some(entity_id), Some(emitter_name)) = (entity_id, emitter_name) {
-                    propagate_subtitles(
-                        event_name,
-                        entity_id,
-                        emitter_name,
-                        line_type.unwrap_or_default(),
-                        duration,
-                    )
-                } else if *key.source() == Source::Voices {
+            }
+        };
+        if !stored {
+            return;
+        }
+        let speaker = match (entity_id, emitter_name) {
+            (Some(entity_id), Some(emitter_name)) => Some(Speaker {
+                entity_id,
+                emitter_name,
+                line_type: line_type.unwrap_or_default(),
+                duration: Some(duration),
+            }),
+            _ => {
+                if *key.source() == Source::Voices {
                     warns!(
                         "cannot propagate subtitles for voice, both entityID and emitterName must be defined: {event_name}"
                     );
                 }
+                None
             }
-            Err(e) => {
-                warns!("cannot play sound: {e}");
-            }
-        }
+        };
+        self.played(Either::Left(&key), origin, prepared, emitter_name, speaker);
     }
 
     pub fn play_on_emitter<T>(
@@ -423,67 +649,176 @@ where
     ) where
         Either<StaticSoundData, StreamingSoundData<FromFileError>>: With<Option<T>>,
         T: AffectedByTimeDilation
-            + ValidateFor<Either<StaticSoundData, StreamingSoundData<FromFileError>>>,
+            + ValidateFor<Either<StaticSoundData, StreamingSoundData<FromFileError>>>
+            + Borrow<Settings>,
     {
         if !entity_id.is_defined() {
             warns!("cannot play sound on undefined entity: {sound_name}");
             return;
         }
-        let gender = entity_id.to_gender();
+        if self.scene.is_none() {
+            return;
+        }
         let spoken = SpokenLocale::get();
-        if let Some(ref mut scene) = self.scene {
-            match self
-                .banks
-                .ids
-                .try_get(&sound_name, &spoken, gender.as_ref())
-            {
-                Ok(key) => {
-                    match scene.emitters.play_on_emitter(
-                        key,
-                        &self.banks,
-                        sound_name,
-                        entity_id,
-                        tag_name,
-                        ext,
-                        control_id,
-                    ) {
-                        Err(e) => {
-                            warns!("cannot play sound on emitter: {e}");
-                        }
-                        Ok((duration, emitter_name)) => {
-                            let emitter_name = match emitter_name {
-                                Some(emitter_name) => Some(emitter_name.as_str().to_string()),
-                                None => {
-                                    let go = resolve_any_entity(entity_id).cast::<GameObject>();
-
-                                    go.and_then(|x| {
-                                        x.is_null().not().then(|| x.resolve_display_name())
-                                    })
-                                }
-                            };
-                            if let Some(emitter_name) = emitter_name {
-                                propagate_subtitles(
-                                    sound_name,
-                                    entity_id,
-                                    CName::new(emitter_name.as_str()),
-                                    ScnDialogLineType::default(),
-                                    duration,
-                                );
-                            } else if *key.source() == Source::Voices {
-                                warns!(
-                                    "cannot propagate subtitles for voice, couldn't resolve emitter name: {sound_name} [{entity_id}]"
-                                );
-                            }
-                        }
-                    };
-                }
-                Err(e) => {
-                    warns!("cannot play sound: {e}");
+        let gender = entity_id
+            .to_gender()
+            .or_else(|| self.banks.any_gender(&sound_name, &spoken));
+        let key = match self
+            .banks
+            .ids
+            .try_get(&sound_name, &spoken, gender.as_ref())
+        {
+            Ok(key) => key.clone(),
+            Err(e) => {
+                warns!("cannot play sound: {e}");
+                return;
+            }
+        };
+        let origin = Origin {
+            event_name: sound_name,
+            entity_id: Some(entity_id),
+            tag_name: Some(tag_name),
+        };
+        let Some(prepared) = self.prepare(Either::Left(&key), origin, None, control_id) else {
+            return;
+        };
+        let Some(ref mut scene) = self.scene else {
+            return;
+        };
+        let (duration, emitter_name) = match scene.emitters.play_on_emitter(
+            &key,
+            &self.banks,
+            sound_name,
+            entity_id,
+            tag_name,
+            ext,
+            prepared.control_id,
+            prepared.delay,
+        ) {
+            Ok(played) => played,
+            Err(e) => {
+                warns!("cannot play sound on emitter: {e}");
+                return;
+            }
+        };
+        let speaker = match emitter_name {
+            Some(emitter_name) => Some(emitter_name.as_str().to_string()),
+            None => {
+                let go = resolve_any_entity(entity_id).cast::<GameObject>();
+
+                go.and_then(|x| x.is_null().not().then(|| x.resolve_display_name()))
+            }
+        };
+        let speaker = match speaker {
+            Some(speaker) => Some(Speaker {
+                entity_id,
+                emitter_name: CName::new(speaker.as_str()),
+                line_type: ScnDialogLineType::default(),
+                duration: Some(duration),
+            }),
+            None => {
+                if *key.source() == Source::Voices {
+                    warns!(
+                        "cannot propagate subtitles for voice, couldn't resolve emitter name: {sound_name} [{entity_id}]"
+                    );
                 }
+                None
+            }
+        };
+        self.played(Either::Left(&key), origin, prepared, emitter_name, speaker);
+    }
+
+    pub fn play_on_positional_emitter<T>(
+        &mut self,
+        sound_name: CName,
+        id: PositionalId,
+        ext: Option<T>,
+        control_id: Option<ControlId>,
+    ) -> bool
+    where
+        Either<StaticSoundData, StreamingSoundData<FromFileError>>: With<Option<T>>,
+        T: AffectedByTimeDilation
+            + ValidateFor<Either<StaticSoundData, StreamingSoundData<FromFileError>>>
+            + Borrow<Settings>,
+    {
+        if self.scene.is_none() {
+            lifecycle!("scene is not initialized");
+            return false;
+        }
+        let spoken = SpokenLocale::get();
+        let gender = self.banks.any_gender(&sound_name, &spoken);
+        let key = match self
+            .banks
+            .ids
+            .try_get(&sound_name, &spoken, gender.as_ref())
+        {
+            Ok(key) => key.clone(),
+            Err(e) => {
+                warns!("cannot play sound: {e}");
+                return false;
+            }
+        };
+        let origin = Origin {
+            event_name: sound_name,
+            entity_id: None,
+            tag_name: None,
+        };
+        let Some(prepared) = self.prepare(Either::Left(&key), origin, None, control_id) else {
+            return false;
+        };
+        let Some(ref mut scene) = self.scene else {
+            return false;
+        };
+        match scene.positionals.play(
+            id,
+            &key,
+            &self.banks,
+            sound_name,
+            ext,
+            prepared.control_id,
+            prepared.delay,
+        ) {
+            Ok(duration) => {
+                // no entity nor name to speak for
+                let speaker = Speaker {
+                    entity_id: EntityId::default(),
+                    emitter_name: CName::undefined(),
+                    line_type: ScnDialogLineType::default(),
+                    duration: Some(duration),
+                };
+                self.played(Either::Left(&key), origin, prepared, None, Some(speaker));
+                true
+            }
+            Err(e) => {
+                warns!("cannot play sound on positional emitter: {e}");
+                false
             }
         }
     }
 
+    /// Play once at given position, from an emitter removed as soon as it finishes.
+    pub fn play_at_position<T>(
+        &mut self,
+        sound_name: CName,
+        position: Vector4,
+        ext: Option<T>,
+        emitter_settings: Option<&(SpatialTrackSettings, NonZero<u64>)>,
+    ) where
+        Either<StaticSoundData, StreamingSoundData<FromFileError>>: With<Option<T>>,
+        T: AffectedByTimeDilation
+            + ValidateFor<Either<StaticSoundData, StreamingSoundData<FromFileError>>>
+            + Borrow<Settings>,
+    {
+        let id = PositionalId::next();
+        if !self.register_positional_emitter(id, position, emitter_settings, None, true) {
+            return;
+        }
+        if !self.play_on_positional_emitter(sound_name, id, ext, None) {
+            self.unregister_positional_emitter(id);
+        }
+    }
+
+    /// Play scene dialog on actor, and tell whether it got stored.
     pub fn play_on_actor(
         &mut self,
         sound_name: Cruid,
@@ -491,39 +826,64 @@ where
         key: &SceneId,
         scene_settings: SceneDialogSettings,
         control_id: Option<ControlId>,
-    ) {
-        if let Some(ref mut scene) = self.scene {
-            if !scene.exists_actor(&entity_id)
-                && let Err(e) = scene.add_actor(&mut self.manager, entity_id, &self.tracks.ambience)
-            {
-                warns!("could not add actor {entity_id}: {e}");
-                return;
-            }
-            let data = self.banks.data(key);
-            let ext = self.banks.settings(key);
-            if let Some(Err(e)) = ext.as_ref().map(|x| x.validate_for(&data)) {
-                warns!("invalid setting(s) for actor audio: {e:#?}");
-                return;
-            }
-            let mut slot = scene
-                .actors
-                .emitters
-                .get_mut(&entity_id)
-                .expect("actor should automatically have been added if missing");
-            match data.with(scene_settings) {
-                Either::Left(data) => {
-                    if let Ok(handle) = slot.value_mut().track_mut().play(data) {
-                        slot.handles
-                            .statics
-                            .store(Handle::new(sound_name, handle, (), control_id));
-                    }
+    ) -> bool {
+        let Some(ref mut scene) = self.scene else {
+            return false;
+        };
+        if !scene.exists_actor(&entity_id)
+            && let Err(e) = scene.add_actor(&mut self.manager, entity_id, &self.tracks.ambience)
+        {
+            warns!("could not add actor {entity_id}: {e}");
+            return false;
+        }
+        let data = self.banks.data(key);
+        let ext = self.banks.settings(key);
+        if let Some(Err(e)) = ext.as_ref().map(|x| x.validate_for(&data)) {
+            warns!("invalid setting(s) for actor audio: {e:#?}");
+            return false;
+        }
+        let mut slot = scene
+            .actors
+            .emitters
+            .get_mut(&entity_id)
+            .expect("actor should automatically have been added if missing");
+        let settings = self.banks.declared_settings(key);
+        let mut inserts = slot.value_mut().track_mut().inserts(settings);
+        match data.with(scene_settings) {
+            Either::Left(data) => {
+                let release = ReleasePolicy::new(settings, data.settings.playback_rate);
+                let rate = base_playback_rate(data.settings.playback_rate);
+                let volume = base_volume(data.settings.volume);
+                let handle = match inserts.as_deref_mut() {
+                    Some(track) => track.play(data),
+                    None => slot.value_mut().track_mut().play(data),
+                };
+                if let Ok(handle) = handle {
+                    slot.handles.statics.store(
+                        Handle::new(sound_name, handle, (), control_id, release, rate, volume)
+                            .with_inserts(inserts),
+                    );
+                    true
+                } else {
+                    false
                 }
-                Either::Right(data) => {
-                    if let Ok(handle) = slot.value_mut().track_mut().play(data) {
-                        slot.handles
-                            .streams
-                            .store(Handle::new(sound_name, handle, (), control_id));
-                    }
+            }
+            Either::Right(data) => {
+                let release = ReleasePolicy::new(settings, data.settings.playback_rate);
+                let rate = base_playback_rate(data.settings.playback_rate);
+                let volume = base_volume(data.settings.volume);
+                let handle = match inserts.as_deref_mut() {
+                    Some(track) => track.play(data),
+                    None => slot.value_mut().track_mut().play(data),
+                };
+                if let Ok(handle) = handle {
+                    slot.handles.streams.store(
+                        Handle::new(sound_name, handle, (), control_id, release, rate, volume)
+                            .with_inserts(inserts),
+                    );
+                    true
+                } else {
+                    false
                 }
             }
         }
@@ -560,12 +920,14 @@ where
         emitter_name: Option<CName>,
         tween: Option<Tween>,
     ) {
-        self.tracks.stop_by(
-            event_name,
-            entity_id,
-            emitter_name,
-            tween.unwrap_or_default(),
-        );
+        match tween {
+            Some(tween) => self
+                .tracks
+                .stop_by(event_name, entity_id, emitter_name, tween),
+            None => self
+                .tracks
+                .release_by(event_name, entity_id, emitter_name, Default::default()),
+        }
     }
 
     #[allow(clippy::too_many_arguments)]
@@ -624,7 +986,18 @@ where
         tween: Option<Tween>,
     ) {
         if let Some(x) = self.scene.as_mut() {
-            x.stop_on_emitter(event_name, entity_id, tag_name, tween.unwrap_or_default());
+            x.stop_on_emitter(event_name, entity_id, tag_name, tween);
+        }
+    }
+
+    pub fn stop_on_positional_emitter(
+        &mut self,
+        event_name: CName,
+        id: PositionalId,
+        tween: Option<Tween>,
+    ) {
+        if let Some(x) = self.scene.as_mut() {
+            x.positionals.stop_on_emitter(event_name, &id, tween);
         }
     }
 
@@ -647,9 +1020,12 @@ where
         if let Some(x) = self.scene.as_mut() {
             x.reclaim()
         }
+        self.released_scenes.retain(Scene::any_lingering_handle);
     }
 
     pub fn reset(&mut self) {
+        self.snapshots
+            .clear(IMMEDIATELY, &mut self.modulators, &mut self.tracks);
         self.set_reverb_mix(0.);
         self.set_preset(eq::Preset::None);
     }
@@ -668,6 +1044,7 @@ where
         tag_name: CName,
         emitter_name: Option<CName>,
         emitter_settings: Option<&(SpatialTrackSettings, NonZero<u64>)>,
+        anchor: Option<Anchor>,
     ) -> bool {
         match self.scene {
             Some(ref mut scene) => scene
@@ -677,6 +1054,7 @@ where
                     tag_name,
                     emitter_name,
                     emitter_settings,
+                    anchor,
                     &self.tracks.ambience,
                 )
                 .inspect_err(|e| warns!("failed to register emitter: {e}"))
@@ -698,6 +1076,55 @@ where
         }
     }
 
+    pub fn register_positional_emitter(
+        &mut self,
+        id: PositionalId,
+        position: Vector4,
+        emitter_settings: Option<&(SpatialTrackSettings, NonZero<u64>)>,
+        lifetime: Option<Duration>,
+        one_shot: bool,
+    ) -> bool {
+        match self.scene {
+            Some(ref mut scene) => scene
+                .add_positional(
+                    &mut self.manager,
+                    id,
+                    position,
+                    emitter_settings,
+                    lifetime,
+                    one_shot,
+                    &self.tracks.ambience,
+                )
+                .inspect_err(|e| warns!("failed to register positional emitter: {e}"))
+                .is_ok(),
+            None => {
+                lifecycle!("scene is not initialized");
+                false
+            }
+        }
+    }
+
+    pub fn unregister_positional_emitter(&mut self, id: PositionalId) -> bool {
+        match self.scene {
+            Some(ref mut scene) => scene.positionals.unregister(&id),
+            None => {
+                lifecycle!("scene is not initialized");
+                false
+            }
+        }
+    }
+
+    pub fn set_positional_emitter_position(&mut self, id: PositionalId, position: Vector4) {
+        match self.scene {
+            Some(ref mut scene) => {
+                if !scene.positionals.set_position(&id, position) {
+                    warns!("cannot move unknown positional emitter [{id}]");
+                }
+            }
+            None => lifecycle!("scene is not initialized"),
+        }
+    }
+
     pub fn set_listener_dilation(&mut self, value: DilationUpdate) {
         match self.scene {
             Some(ref mut scene) => {
@@ -786,7 +1213,7 @@ where
     pub fn sync_scene(&mut self) {
         match self.scene {
             Some(ref mut scene) => {
-                if let Err(e) = scene.sync() {
+                if let Err(e) = scene.sync(&mut self.manager, &self.banks, &self.tracks.ambience) {
                     lifecycle!("failed to sync scene: {e}")
                 }
             }
@@ -794,6 +1221,20 @@ where
         }
     }
 
+    /// Release ducked tracks once last sound ducking them ended.
+    pub fn update_ducking(&mut self) {
+        let (tracks, scene) = (&self.tracks, &self.scene);
+        self.duckers.sync(
+            |id| {
+                tracks.playing_position(id).is_some()
+                    || scene
+                        .as_ref()
+                        .is_some_and(|x| x.playing_position(id).is_some())
+            },
+            &mut self.modulators.ducking,
+        );
+    }
+
     pub fn mute(&mut self, value: bool) {
         if value {
             self.manager
@@ -825,13 +1266,40 @@ where
     }
 
     pub fn set_reverb_mix(&mut self, value: f32) {
-        self.modulators.reverb_mix.update(value, DEFAULT);
+        self.snapshots
+            .set_reverb_mix(value, DEFAULT, &mut self.modulators);
     }
 
     pub fn set_preset(&mut self, preset: Preset) {
         self.tracks.ambience.equalizer().set_preset(preset);
     }
 
+    pub fn activate_snapshot(&mut self, name: CName, tween: Option<Tween>) {
+        let Some(snapshot) = self.banks.snapshots.get(&name).cloned() else {
+            warns!("unknown snapshot: {name}");
+            return;
+        };
+        self.snapshots.activate(
+            name,
+            snapshot,
+            tween.unwrap_or(BLENDED),
+            &mut self.modulators,
+            &mut self.tracks,
+        );
+        lifecycle!("activated snapshot {name}");
+    }
+
+    pub fn deactivate_snapshot(&mut self, name: CName, tween: Option<Tween>) {
+        if self.snapshots.deactivate(
+            name,
+            tween.unwrap_or(BLENDED),
+            &mut self.modulators,
+            &mut self.tracks,
+        ) {
+            lifecycle!("deactivated snapshot {name}");
+        }
+    }
+
     pub fn exists(sound: &CName) -> bool {
         #[cfg(not(feature = "hot-reload"))]
         return BANKS.get().map(|x| x.exists(sound)).unwrap_or(false);
@@ -857,9 +1325,19 @@ where
 
     #[allow(dead_code)]
     pub fn clear(&mut self) {
+        self.pending_cues.clear();
+        self.delayed_subtitles.clear();
+        self.pending_markers.clear();
+        self.instances.clear();
+        self.duckers.clear(&mut self.modulators.ducking);
+        self.snapshots
+            .clear(IMMEDIATELY, &mut self.modulators, &mut self.tracks);
         self.tracks.clear();
-        if let Some(scene) = self.scene.as_mut() {
-            scene.clear();
+        if let Some(scene) = self.scene.take() {
+            self.released_scenes.extend(scene.release());
+            if let Err(e) = self.try_new_scene() {
+                lifecycle!("failed to create new scene: {e}");
+            }
         }
     }
 
@@ -0,0 +1,33 @@
+use audioware_manifest::DuckTarget;
+use kira::{AudioManager, backend::Backend};
+
+use crate::error::Error;
+
+use super::{Level, Parameter};
+
+/// Duck levels of each track which can be ducked.
+pub struct DuckLevels {
+    pub music: Level,
+    pub sfx: Level,
+    pub radioport: Level,
+    pub car_radio: Level,
+}
+
+impl DuckLevels {
+    pub fn try_new<B: Backend>(manager: &mut AudioManager<B>) -> Result<Self, Error> {
+        Ok(Self {
+            music: Level::try_new(manager)?,
+            sfx: Level::try_new(manager)?,
+            radioport: Level::try_new(manager)?,
+            car_radio: Level::try_new(manager)?,
+        })
+    }
+    pub fn level_mut(&mut self, target: DuckTarget) -> &mut Level {
+        match target {
+            DuckTarget::Music => &mut self.music,
+            DuckTarget::Sfx => &mut self.sfx,
+            DuckTarget::Radioport => &mut self.radioport,
+            DuckTarget::CarRadio => &mut self.car_radio,
+        }
+    }
+}
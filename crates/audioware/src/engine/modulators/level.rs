@@ -0,0 +1,35 @@
+use kira::{
+    AudioManager, Decibels, Tween, Value,
+    backend::Backend,
+    effect::{EffectBuilder, volume_control::VolumeControlBuilder},
+    modulator::tweener::{TweenerBuilder, TweenerHandle},
+};
+
+use crate::error::Error;
+
+use super::{ATTENUATION_MAPPING, Parameter};
+
+/// Volume of a track on top of its volume setting,
+/// e.g. when ducked by dialogues or set by mix snapshots.
+pub struct Level(TweenerHandle);
+impl Parameter for Level {
+    type Value = Decibels;
+
+    fn try_new<B: Backend>(manager: &mut AudioManager<B>) -> Result<Self, Error> {
+        let handle = manager.add_modulator(TweenerBuilder {
+            initial_value: Decibels::IDENTITY.0 as f64,
+        })?;
+        Ok(Self(handle))
+    }
+
+    fn try_effect(&self) -> Result<impl EffectBuilder, Error> {
+        Ok(VolumeControlBuilder::new(Value::from_modulator(
+            &self.0,
+            ATTENUATION_MAPPING,
+        )))
+    }
+
+    fn update(&mut self, value: Self::Value, tween: Tween) {
+        self.0.set(value.0 as f64, tween);
+    }
+}
@@ -0,0 +1,36 @@
+use audioware_manifest::MixTrack;
+use kira::{AudioManager, backend::Backend};
+
+use crate::error::Error;
+
+use super::{Level, Parameter};
+
+/// Mix levels of each track which can be set by snapshots.
+pub struct MixLevels {
+    pub music: Level,
+    pub sfx: Level,
+    pub dialogue: Level,
+    pub radioport: Level,
+    pub car_radio: Level,
+}
+
+impl MixLevels {
+    pub fn try_new<B: Backend>(manager: &mut AudioManager<B>) -> Result<Self, Error> {
+        Ok(Self {
+            music: Level::try_new(manager)?,
+            sfx: Level::try_new(manager)?,
+            dialogue: Level::try_new(manager)?,
+            radioport: Level::try_new(manager)?,
+            car_radio: Level::try_new(manager)?,
+        })
+    }
+    pub fn level_mut(&mut self, track: MixTrack) -> &mut Level {
+        match track {
+            MixTrack::Music => &mut self.music,
+            MixTrack::Sfx => &mut self.sfx,
+            MixTrack::Dialogue => &mut self.dialogue,
+            MixTrack::Radioport => &mut self.radioport,
+            MixTrack::CarRadio => &mut self.car_radio,
+        }
+    }
+}
@@ -7,11 +7,20 @@ use kira::{
 use crate::error::Error;
 
 mod volume;
-pub use volume::VOLUME_MAPPING;
+pub use volume::{ATTENUATION_MAPPING, VOLUME_MAPPING};
 
 mod reverb;
 pub use reverb::*;
 
+mod level;
+pub use level::Level;
+
+mod ducking;
+pub use ducking::DuckLevels;
+
+mod mix;
+pub use mix::MixLevels;
+
 pub trait Parameter {
     type Value;
     fn try_new<B: Backend>(manager: &mut AudioManager<B>) -> Result<Self, Error>
@@ -23,11 +32,19 @@ pub trait Parameter {
 
 pub struct Modulators {
     pub reverb_mix: ReverbMix,
+    pub ducking: DuckLevels,
+    pub mix: MixLevels,
 }
 
 impl Modulators {
     pub fn try_new<B: Backend>(manager: &mut AudioManager<B>) -> Result<Self, Error> {
         let reverb_mix = ReverbMix::try_new(manager)?;
-        Ok(Self { reverb_mix })
+        let ducking = DuckLevels::try_new(manager)?;
+        let mix = MixLevels::try_new(manager)?;
+        Ok(Self {
+            reverb_mix,
+            ducking,
+            mix,
+        })
     }
 }
@@ -3,3 +3,13 @@ pub const VOLUME_MAPPING: kira::Mapping<kira::Decibels> = kira::Mapping {
     output_range: (kira::Decibels::SILENCE, kira::Decibels::IDENTITY),
     easing: kira::Easing::OutPowf(3.0), // more realistic volume scaling
 };
+
+/// Identity mapping, from decibels to decibels.
+pub const ATTENUATION_MAPPING: kira::Mapping<kira::Decibels> = kira::Mapping {
+    input_range: (
+        kira::Decibels::SILENCE.0 as f64,
+        kira::Decibels::IDENTITY.0 as f64,
+    ),
+    output_range: (kira::Decibels::SILENCE, kira::Decibels::IDENTITY),
+    easing: kira::Easing::Linear,
+};
@@ -0,0 +1,222 @@
+//! Steps shared by every play path:
+//! before sound plays, see [Engine::prepare], and once it is stored, see [Engine::played].
+
+use std::time::Duration;
+
+use audioware_bank::{BankCues, BankDeclaredSettings, Banks, Id, SceneId};
+use audioware_manifest::{DuckSource, Retrigger, ScnDialogLineType, Settings};
+use either::Either;
+use kira::backend::Backend;
+use red4ext_rs::types::{CName, EntityId};
+
+use crate::{
+    ControlId,
+    engine::{
+        Engine,
+        cues::{DelayedSubtitle, PendingCues},
+        ducking::{Ducker, ducks},
+        instances::{Admission, Instance, declared_fade_out},
+        markers::{PendingMarkers, has_markers},
+        next_control_id,
+        retriggers::Retriggered,
+    },
+    propagate_subtitles,
+    utils::lifecycle,
+};
+
+/// Who plays a sound, for retriggers, instances and markers.
+#[derive(Debug, Clone, Copy)]
+pub struct Origin {
+    /// Also used as subtitle reaction.
+    pub event_name: CName,
+    pub entity_id: Option<EntityId>,
+    /// Emitter tag name, or emitter name when not played on emitter.
+    pub tag_name: Option<CName>,
+}
+
+/// Sound allowed to play, see [Engine::prepare].
+pub struct Prepared {
+    pub control_id: Option<ControlId>,
+    /// Start delay imposed by retrigger protection, if any.
+    pub delay: Option<Duration>,
+    retrigger: Option<Retrigger>,
+    instance: Option<(Instance, Admission)>,
+    track: Option<DuckSource>,
+    /// Whether timed subtitles are defined.
+    pub cues: bool,
+}
+
+/// Whom subtitles are propagated for, once sound is stored.
+pub struct Speaker {
+    pub entity_id: EntityId,
+    pub emitter_name: CName,
+    pub line_type: ScnDialogLineType,
+    /// Sound duration (in seconds),
+    /// unless red engine propagates regular subtitles on its own.
+    pub duration: Option<f32>,
+}
+
+fn declared_settings<'a>(banks: &'a Banks, key: Either<&Id, &SceneId>) -> Option<&'a Settings> {
+    match key {
+        Either::Left(key) => banks.declared_settings(key),
+        Either::Right(key) => banks.declared_settings(key),
+    }
+}
+
+impl<B: Backend> Engine<B> {
+    /// Decide whether sound may play, and whether it needs a [ControlId].
+    ///
+    /// Nothing is recorded yet, see [Engine::played].
+    pub fn prepare(
+        &mut self,
+        key: Either<&Id, &SceneId>,
+        origin: Origin,
+        track: Option<DuckSource>,
+        control_id: Option<ControlId>,
+    ) -> Option<Prepared> {
+        let settings = declared_settings(&self.banks, key);
+        let cues = match key {
+            Either::Left(key) => self.banks.cues(key).is_some(),
+            Either::Right(key) => self.banks.cues(key).is_some(),
+        };
+        // scene dialogs are paced by scenes themselves
+        let (retrigger, limit) = match key {
+            Either::Left(_) => (
+                settings.and_then(Settings::retrigger),
+                settings.and_then(Settings::instance_limit),
+            ),
+            Either::Right(_) => (None, None),
+        };
+        let event_name = origin.event_name;
+        let delay = match retrigger.map(|x| {
+            self.tracks
+                .last_plays
+                .retrigger(event_name, x, origin.entity_id, origin.tag_name)
+        }) {
+            Some(Retriggered::Dropped) => {
+                lifecycle!("retriggered too soon, dropped {event_name}");
+                return None;
+            }
+            Some(Retriggered::Delayed(delay)) => Some(delay),
+            _ => None,
+        };
+        let duck = ducks(settings, track, &self.banks.ducking);
+        let control_id = control_id.or_else(|| {
+            (cues || has_markers(settings) || limit.is_some() || duck).then(next_control_id)
+        });
+        let instance = limit.zip(control_id).map(|(limit, control_id)| {
+            let instance = Instance::new(control_id, origin.entity_id, origin.tag_name, delay);
+            let admission = self.instances.admission(
+                event_name,
+                limit,
+                &instance,
+                &self.tracks,
+                self.scene.as_ref(),
+            );
+            (instance, admission)
+        });
+        if let Some((_, Admission::Reject)) = instance {
+            return None;
+        }
+        Some(Prepared {
+            control_id,
+            delay,
+            retrigger,
+            instance,
+            track,
+            cues,
+        })
+    }
+    /// Record sound once stored: retrigger window, instance, ducking,
+    /// then watch its markers and propagate its subtitles to `speaker` if any.
+    pub fn played(
+        &mut self,
+        key: Either<&Id, &SceneId>,
+        origin: Origin,
+        prepared: Prepared,
+        emitter_name: Option<CName>,
+        speaker: Option<Speaker>,
+    ) {
+        let Prepared {
+            control_id,
+            delay,
+            retrigger,
+            instance,
+            track,
+            cues,
+        } = prepared;
+        let settings = declared_settings(&self.banks, key);
+        if let Some(retrigger) = retrigger {
+            self.tracks.last_plays.played(
+                origin.event_name,
+                retrigger,
+                origin.entity_id,
+                origin.tag_name,
+                delay,
+            );
+        }
+        if let Some((instance, admission)) = instance {
+            self.instances.admitted(
+                origin.event_name,
+                instance,
+                admission,
+                declared_fade_out(settings),
+                &mut self.tracks,
+                self.scene.as_mut(),
+            );
+        }
+        if let Some(ducker) = control_id
+            .and_then(|control_id| Ducker::new(control_id, settings, track, &self.banks.ducking))
+        {
+            self.duckers
+                .duck(ducker, delay, &mut self.modulators.ducking);
+        }
+        PendingMarkers::watch(
+            &mut self.pending_markers,
+            control_id,
+            origin.event_name,
+            origin.entity_id,
+            emitter_name,
+            settings,
+        );
+        let Some(Speaker {
+            entity_id,
+            emitter_name,
+            line_type,
+            duration,
+        }) = speaker
+        else {
+            return;
+        };
+        match (control_id.filter(|_| cues), duration, delay) {
+            (Some(control_id), ..) => self.pending_cues.push(
+                PendingCues::new(
+                    control_id,
+                    entity_id,
+                    emitter_name,
+                    key.map_either(Id::clone, SceneId::clone),
+                )
+                .delayed(delay),
+            ),
+            (None, Some(duration), Some(delay)) => {
+                self.delayed_subtitles.push(DelayedSubtitle::new(
+                    origin.event_name,
+                    entity_id,
+                    emitter_name,
+                    line_type,
+                    duration,
+                    delay,
+                ))
+            }
+            (None, Some(duration), None) => propagate_subtitles(
+                origin.event_name,
+                entity_id,
+                emitter_name,
+                line_type,
+                duration,
+            ),
+            // red engine propagates regular subtitles on its own
+            (None, None, _) => {}
+        }
+    }
+}
@@ -22,6 +22,7 @@ use crate::{
         DilationUpdate,
         callbacks::Dispatch,
         traits::{
+            effect::{EffectParameter, SetControlledEffect},
             panning::SetControlledPanning,
             pause::PauseControlled,
             playback::SetControlledPlaybackRate,
@@ -94,6 +95,11 @@ static DYNAMIC_SOUNDS: OnceLock<RwLock<Option<Sender<DynamicSound>>>> = OnceLock
 static DYNAMIC_EMITTERS: OnceLock<RwLock<Option<Sender<DynamicEmitter>>>> = OnceLock::new();
 
 fn load() -> Result<(Engine<CpalBackend>, usize), Error> {
+    // best-effort, only used to report mismatching audio on load
+    if let Ok(config) = default_device_and_config() {
+        audioware_bank::OUTPUT_SAMPLE_RATE
+            .store(config.sample_rate, std::sync::atomic::Ordering::Relaxed);
+    }
     let buffer_size = BufferSize::read_ini();
     let mut backend_settings = CpalBackendSettings::default();
     if buffer_size != BufferSize::Auto {
@@ -102,10 +108,15 @@ fn load() -> Result<(Engine<CpalBackend>, usize), Error> {
             ..default_device_and_config()?
         });
     }
-    let manager_settings = AudioManagerSettings::<CpalBackend> {
+    let mut manager_settings = AudioManagerSettings::<CpalBackend> {
         backend_settings,
         ..Default::default()
     };
+    // each emitter with a reverb send curve owns a modulator
+    manager_settings.capacities.modulator_capacity = manager_settings
+        .capacities
+        .modulator_capacity
+        .max(manager_settings.capacities.sub_track_capacity);
     let capacity = manager_settings.capacities.sub_track_capacity;
     Ok((Engine::try_new(manager_settings)?, capacity))
 }
@@ -222,6 +233,7 @@ pub fn run(
                     tag_name,
                     emitter_name,
                     emitter_settings,
+                    anchor,
                     sender,
                 } => {
                     let registered = engine.register_emitter(
@@ -229,6 +241,7 @@ pub fn run(
                         *tag_name,
                         emitter_name,
                         emitter_settings.as_deref(),
+                        anchor,
                     );
                     let _ = sender.try_send(registered);
                 }
@@ -240,6 +253,29 @@ pub fn run(
                     let unregistered = engine.unregister_emitter(*entity_id, *tag_name);
                     let _ = sender.try_send(unregistered);
                 }
+                Lifecycle::RegisterPositionalEmitter {
+                    id,
+                    position,
+                    emitter_settings,
+                    lifetime,
+                    sender,
+                } => {
+                    let registered = engine.register_positional_emitter(
+                        id,
+                        position,
+                        emitter_settings.as_deref(),
+                        lifetime,
+                        false,
+                    );
+                    let _ = sender.try_send(registered);
+                }
+                Lifecycle::UnregisterPositionalEmitter { id, sender } => {
+                    let unregistered = engine.unregister_positional_emitter(id);
+                    let _ = sender.try_send(unregistered);
+                }
+                Lifecycle::SetPositionalEmitterPosition { id, position } => {
+                    engine.set_positional_emitter_position(id, position)
+                }
                 Lifecycle::OnEmitterDies { entity_id } => engine.on_emitter_dies(entity_id),
                 Lifecycle::OnEmitterIncapacitated { entity_id } => {
                     engine.on_emitter_incapacitated(entity_id)
@@ -264,7 +300,11 @@ pub fn run(
                     if state.contains(Flags::IN_GAME) {
                         state.set(Flags::IN_GAME, false);
                         engine.scene = None;
+                        engine.released_scenes.clear();
                         engine.tracks.clear();
+                        engine.pending_cues.clear();
+                        engine.delayed_subtitles.clear();
+                        engine.pending_markers.clear();
                         engine.reset_callbacks();
                     }
                 }
@@ -315,6 +355,7 @@ pub fn run(
             }
         }
         engine.update_mutes();
+        engine.update_ducking();
         if state.should_sync()
             && (engine.any_emitter() || engine.any_actor())
             && synchronization.try_recv().is_ok()
@@ -378,6 +419,29 @@ pub fn run(
                 } => {
                     engine.play_on_emitter(event_name, *entity_id, *tag_name, ext, Some(control_id))
                 }
+                Command::PlayOnPositionalEmitter {
+                    event_name,
+                    id,
+                    ext,
+                } => {
+                    engine.play_on_positional_emitter(event_name, id, ext, None);
+                }
+                Command::EnqueueAndPlayOnPositionalEmitter {
+                    event_name,
+                    id,
+                    ext,
+                    control_id,
+                } => {
+                    engine.play_on_positional_emitter(event_name, id, ext, Some(control_id));
+                }
+                Command::PlayAtPosition {
+                    event_name,
+                    position,
+                    ext,
+                    emitter_settings,
+                } => {
+                    engine.play_at_position(event_name, position, ext, emitter_settings.as_deref())
+                }
                 Command::PlayOverThePhone {
                     event_name,
                     emitter_name,
@@ -409,6 +473,15 @@ pub fn run(
                     tag_name,
                     tween,
                 } => engine.stop_on_emitter(event_name, *entity_id, *tag_name, tween),
+                Command::StopOnPositionalEmitter {
+                    event_name,
+                    id,
+                    tween,
+                } => engine.stop_on_positional_emitter(event_name, id, tween),
+                Command::ActivateSnapshot { name, tween } => engine.activate_snapshot(name, tween),
+                Command::DeactivateSnapshot { name, tween } => {
+                    engine.deactivate_snapshot(name, tween)
+                }
                 Command::StopVanilla {
                     event_name,
                     entity_id,
@@ -472,6 +545,51 @@ pub fn run(
                         .tracks
                         .set_controlled_panning(id, value, tween.unwrap_or(IMMEDIATELY));
                 }
+                DynamicSound::SetFilterCutoff {
+                    id,
+                    filter,
+                    value,
+                    tween,
+                } => {
+                    engine.tracks.set_controlled_effect(
+                        id,
+                        EffectParameter::FilterCutoff(filter, value),
+                        tween.unwrap_or(IMMEDIATELY),
+                    );
+                }
+                DynamicSound::SetFilterResonance {
+                    id,
+                    filter,
+                    value,
+                    tween,
+                } => {
+                    engine.tracks.set_controlled_effect(
+                        id,
+                        EffectParameter::FilterResonance(filter, value),
+                        tween.unwrap_or(IMMEDIATELY),
+                    );
+                }
+                DynamicSound::SetDistortionDrive { id, value, tween } => {
+                    engine.tracks.set_controlled_effect(
+                        id,
+                        EffectParameter::DistortionDrive(value),
+                        tween.unwrap_or(IMMEDIATELY),
+                    );
+                }
+                DynamicSound::SetDelayFeedback { id, value, tween } => {
+                    engine.tracks.set_controlled_effect(
+                        id,
+                        EffectParameter::DelayFeedback(value),
+                        tween.unwrap_or(IMMEDIATELY),
+                    );
+                }
+                DynamicSound::SetReverbMix { id, value, tween } => {
+                    engine.tracks.set_controlled_effect(
+                        id,
+                        EffectParameter::ReverbMix(value),
+                        tween.unwrap_or(IMMEDIATELY),
+                    );
+                }
                 DynamicSound::Pause { id, tween } => {
                     engine
                         .tracks
@@ -513,6 +631,51 @@ pub fn run(
                     DynamicEmitter::SetPlaybackRate { id, value, tween } => {
                         scene.set_controlled_playback_rate(id, value, tween.unwrap_or(IMMEDIATELY));
                     }
+                    DynamicEmitter::SetFilterCutoff {
+                        id,
+                        filter,
+                        value,
+                        tween,
+                    } => {
+                        scene.set_controlled_effect(
+                            id,
+                            EffectParameter::FilterCutoff(filter, value),
+                            tween.unwrap_or(IMMEDIATELY),
+                        );
+                    }
+                    DynamicEmitter::SetFilterResonance {
+                        id,
+                        filter,
+                        value,
+                        tween,
+                    } => {
+                        scene.set_controlled_effect(
+                            id,
+                            EffectParameter::FilterResonance(filter, value),
+                            tween.unwrap_or(IMMEDIATELY),
+                        );
+                    }
+                    DynamicEmitter::SetDistortionDrive { id, value, tween } => {
+                        scene.set_controlled_effect(
+                            id,
+                            EffectParameter::DistortionDrive(value),
+                            tween.unwrap_or(IMMEDIATELY),
+                        );
+                    }
+                    DynamicEmitter::SetDelayFeedback { id, value, tween } => {
+                        scene.set_controlled_effect(
+                            id,
+                            EffectParameter::DelayFeedback(value),
+                            tween.unwrap_or(IMMEDIATELY),
+                        );
+                    }
+                    DynamicEmitter::SetReverbMix { id, value, tween } => {
+                        scene.set_controlled_effect(
+                            id,
+                            EffectParameter::ReverbMix(value),
+                            tween.unwrap_or(IMMEDIATELY),
+                        );
+                    }
                     DynamicEmitter::Pause { id, tween } => {
                         scene.pause_controlled(id, tween.unwrap_or(IMMEDIATELY));
                     }
@@ -545,6 +708,8 @@ pub fn run(
             }
         }
         engine.update_callbacks();
+        engine.update_cues();
+        engine.update_markers();
     }
     let _ = LIFECYCLE
         .get()
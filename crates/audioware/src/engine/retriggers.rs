@@ -0,0 +1,114 @@
+//! Per-sound cooldown and retrigger protection.
+
+use std::{
+    collections::HashMap,
+    time::{Duration, Instant},
+};
+
+use audioware_manifest::{InstanceScope, Retrigger};
+use either::Either;
+use kira::{
+    StartTime,
+    sound::{FromFileError, static_sound::StaticSoundData, streaming::StreamingSoundData},
+};
+use red4ext_rs::types::{CName, EntityId};
+
+/// Whether a play may happen, see [LastPlays::retrigger].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Retriggered {
+    Now,
+    /// Once previous window ends.
+    Delayed(Duration),
+    Dropped,
+}
+
+/// Last time sounds declared with `cooldown` or `min_retrigger_interval` played,
+/// along with their window.
+#[derive(Debug, Default)]
+pub struct LastPlays(HashMap<(CName, Option<EntityId>, Option<CName>), (Instant, Duration)>);
+
+impl LastPlays {
+    /// Decide whether sound may play now, see [LastPlays::played].
+    pub fn retrigger(
+        &self,
+        event_name: CName,
+        retrigger: Retrigger,
+        entity_id: Option<EntityId>,
+        tag_name: Option<CName>,
+    ) -> Retriggered {
+        let key = scoped(event_name, retrigger.scope, entity_id, tag_name);
+        let now = Instant::now();
+        let Some((last, _)) = self.0.get(&key).copied() else {
+            return Retriggered::Now;
+        };
+        // another play is already waiting
+        if last > now {
+            return Retriggered::Dropped;
+        }
+        let elapsed = now.duration_since(last);
+        if elapsed >= retrigger.window {
+            return Retriggered::Now;
+        }
+        if !retrigger.queue {
+            return Retriggered::Dropped;
+        }
+        Retriggered::Delayed(retrigger.window - elapsed)
+    }
+    /// Remember sound played, starting after `delay` if any.
+    pub fn played(
+        &mut self,
+        event_name: CName,
+        retrigger: Retrigger,
+        entity_id: Option<EntityId>,
+        tag_name: Option<CName>,
+        delay: Option<Duration>,
+    ) {
+        let key = scoped(event_name, retrigger.scope, entity_id, tag_name);
+        self.0.insert(
+            key,
+            (Instant::now() + delay.unwrap_or_default(), retrigger.window),
+        );
+    }
+    /// Forget plays whose window already ended.
+    pub fn reclaim(&mut self) {
+        let now = Instant::now();
+        self.0
+            .retain(|_, (last, window)| now.saturating_duration_since(*last) < *window);
+    }
+    pub fn clear(&mut self) {
+        self.0.clear();
+    }
+}
+
+fn scoped(
+    event_name: CName,
+    scope: InstanceScope,
+    entity_id: Option<EntityId>,
+    tag_name: Option<CName>,
+) -> (CName, Option<EntityId>, Option<CName>) {
+    match scope {
+        InstanceScope::Global => (event_name, None, None),
+        InstanceScope::PerEntity => (event_name, entity_id, None),
+        InstanceScope::PerEmitter => (event_name, entity_id, tag_name),
+    }
+}
+
+/// Postpone start time by `delay`.
+pub fn delayed(start_time: StartTime, delay: Duration) -> StartTime {
+    match start_time {
+        StartTime::Immediate => StartTime::Delayed(delay),
+        StartTime::Delayed(x) => StartTime::Delayed(x + delay),
+        x => x,
+    }
+}
+
+/// Postpone start time of sound data by `delay`.
+pub fn delay_start(
+    data: &mut Either<StaticSoundData, StreamingSoundData<FromFileError>>,
+    delay: Duration,
+) {
+    match data {
+        Either::Left(x) => x.settings.start_time = delayed(x.settings.start_time, delay),
+        Either::Right(x) => x.settings.start_time = delayed(x.settings.start_time, delay),
+    }
+}
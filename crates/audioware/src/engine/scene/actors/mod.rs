@@ -1,4 +1,4 @@
-use std::{collections::HashSet, sync::LazyLock};
+use std::{collections::HashSet, sync::LazyLock, time::Instant};
 
 use dashmap::DashMap;
 use kira::sound::FromFileError;
@@ -6,10 +6,12 @@ use parking_lot::RwLock;
 use red4ext_rs::types::{Cruid, EntityId};
 
 use crate::{
+    ControlId,
     engine::{
-        scene::{actors::slot::ActorSlot, emitters::Emitter},
+        scene::{actors::slot::ActorSlot, emitters::Emitter, listener::Listener},
         traits::{
-            DualHandles, clear::Clear, pause::Pause, reclaim::Reclaim, resume::Resume, stop::Stop,
+            DualHandles, clear::Clear, pause::Pause, position::PlayingPosition, reclaim::Reclaim,
+            release::Release, resume::Resume, stop::Stop,
         },
     },
     error::Error,
@@ -39,13 +41,22 @@ impl Actors {
     pub fn is_empty(&self) -> bool {
         self.emitters.is_empty()
     }
+    pub fn any_lingering_handle(&self) -> bool {
+        self.v.any_lingering_handle()
+            || self.holocall.any_lingering_handle()
+            || self
+                .emitters
+                .iter()
+                .any(|x| x.handles.any_lingering_handle())
+    }
     pub fn exists(&self, entity_id: &EntityId) -> bool {
         self.emitters.contains_key(entity_id)
     }
-    pub fn sync_emitters(&mut self) -> Result<(), Error> {
+    pub fn sync_emitters(&mut self, listener: &Listener) -> Result<(), Error> {
         if self.emitters.is_empty() {
             return Ok(());
         }
+        let now = Instant::now();
         self.emitters.retain(|k, v| {
             if !v.any_playing_handle() && !Emitter::is_in_scene(k) {
                 return false;
@@ -53,14 +64,29 @@ impl Actors {
             let Ok(position) = Emitter::position(*k) else {
                 return false;
             };
-            v.last_known_position = position;
+            v.motion.update(position.into(), now);
             v.set_emitter_position(position);
+            v.handle.attenuate(position.distance(&listener.position));
+            v.set_emitter_doppler(listener);
             true
         });
         Ok(())
     }
 }
 
+impl PlayingPosition for Actors {
+    fn playing_position(&self, id: ControlId) -> Option<f64> {
+        self.v
+            .playing_position(id)
+            .or_else(|| self.holocall.playing_position(id))
+            .or_else(|| {
+                self.emitters
+                    .iter()
+                    .find_map(|x| x.handles.playing_position(id))
+            })
+    }
+}
+
 impl Stop for Actors {
     fn stop(&mut self, tween: kira::Tween) {
         self.v.stop(tween);
@@ -69,6 +95,16 @@ impl Stop for Actors {
     }
 }
 
+impl Release for Actors {
+    fn release(&mut self, fallback: kira::Tween) {
+        self.v.release(fallback);
+        self.emitters
+            .iter_mut()
+            .for_each(|mut x| x.release(fallback));
+        self.holocall.release(fallback);
+    }
+}
+
 impl Pause for Actors {
     fn pause(&mut self, tween: kira::Tween) {
         self.v.pause(tween);
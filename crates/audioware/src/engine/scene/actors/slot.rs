@@ -1,18 +1,22 @@
-use kira::{sound::FromFileError, track::SpatialTrackHandle};
+use audioware_core::{Motion, doppler_shift};
+use kira::sound::FromFileError;
 use red4ext_rs::types::Cruid;
 
 use crate::{
     Vector4,
     engine::{
+        scene::listener::Listener,
         tracks::Spatial,
         traits::{
             DualHandles,
+            doppler::SyncDoppler,
             pause::Pause,
             reclaim::Reclaim,
+            release::Release,
             resume::Resume,
             stop::{Stop, StopBy},
         },
-        tweens::IMMEDIATELY,
+        tweens::{IMMEDIATELY, SHIFTED},
     },
 };
 
@@ -20,6 +24,7 @@ use crate::{
 pub struct ActorSlot {
     pub handle: Spatial,
     pub last_known_position: Vector4,
+    pub motion: Motion,
     pub handles: DualHandles<Cruid, (), FromFileError>,
 }
 
@@ -42,9 +47,10 @@ impl ActorSlot {
             handle,
             handles: Default::default(),
             last_known_position,
+            motion: Motion::default(),
         }
     }
-    pub fn track_mut(&mut self) -> &mut SpatialTrackHandle {
+    pub fn track_mut(&mut self) -> &mut Spatial {
         &mut self.handle
     }
     pub fn set_emitter_position(&mut self, position: Vector4) {
@@ -54,6 +60,24 @@ impl ActorSlot {
                 .set_position(self.last_known_position, IMMEDIATELY);
         }
     }
+    /// Follow Doppler shift, if enabled.
+    pub fn set_emitter_doppler(&mut self, listener: &Listener) {
+        let Some(factor) = self.handle.doppler_factor() else {
+            return;
+        };
+        let doppler = doppler_shift(
+            self.last_known_position.into(),
+            self.motion.velocity,
+            listener.position.into(),
+            listener.motion.velocity,
+            factor,
+        );
+        if (doppler - self.handle.doppler()).abs() < f64::EPSILON {
+            return;
+        }
+        self.handle.set_doppler(doppler);
+        self.handles.sync_doppler(1., doppler, SHIFTED);
+    }
 }
 
 impl Stop for ActorSlot {
@@ -62,6 +86,12 @@ impl Stop for ActorSlot {
     }
 }
 
+impl Release for ActorSlot {
+    fn release(&mut self, fallback: kira::Tween) {
+        self.handles.release(fallback);
+    }
+}
+
 impl StopBy<Cruid> for ActorSlot {
     fn stop_by(&mut self, key: &Cruid, tween: kira::Tween) {
         self.handles.stop_by(key, tween);
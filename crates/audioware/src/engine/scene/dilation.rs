@@ -1,4 +1,4 @@
-use audioware_manifest::Settings;
+use audioware_manifest::{Settings, TimeDilation};
 use kira::Tween;
 use red4ext_rs::types::CName;
 
@@ -86,19 +86,31 @@ impl PartialEq for DilationUpdate {
 }
 
 pub trait AffectedByTimeDilation {
-    fn affected_by_time_dilation(&self) -> bool;
+    fn time_dilation(&self) -> TimeDilation;
+    #[inline(always)]
+    fn affected_by_time_dilation(&self) -> bool {
+        self.time_dilation().is_affected()
+    }
 }
 
 impl AffectedByTimeDilation for Settings {
     #[inline(always)]
-    fn affected_by_time_dilation(&self) -> bool {
-        self.affected_by_time_dilation.unwrap_or(true)
+    fn time_dilation(&self) -> TimeDilation {
+        self.affected_by_time_dilation.unwrap_or_default()
+    }
+}
+
+/// Actors are only synced with listener.
+impl AffectedByTimeDilation for () {
+    #[inline(always)]
+    fn time_dilation(&self) -> TimeDilation {
+        TimeDilation::Unaffected
     }
 }
 
 impl AffectedByTimeDilation for Tween {
     #[inline(always)]
-    fn affected_by_time_dilation(&self) -> bool {
-        true
+    fn time_dilation(&self) -> TimeDilation {
+        TimeDilation::Pitch
     }
 }
@@ -1,5 +1,6 @@
+use glam::{Quat, Vec3};
 use kira::track::SpatialTrackDistances;
-use red4ext_rs::types::{EntityId, GameInstance};
+use red4ext_rs::types::{CName, EntityId, GameInstance};
 
 use crate::{
     AIActionHelper, AsEntity, AsGameInstance, AsSceneSystem, AsSceneSystemInterface,
@@ -12,6 +13,15 @@ use super::super::AsEntityExt;
 
 pub struct Emitter;
 
+/// Where sounds come from on emitter, other than its origin.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Anchor {
+    /// Name of entity slot, e.g. a cannon or an exhaust.
+    pub slot: Option<CName>,
+    /// In slot (or entity) local space.
+    pub offset: Vector4,
+}
+
 impl Emitter {
     pub fn infos(entity_id: EntityId) -> Result<(Vector4, bool), Error> {
         let entity = resolve_any_entity(entity_id);
@@ -29,6 +39,15 @@ impl Emitter {
         Ok((position, busy))
     }
 
+    /// Direction emitter faces, if still around.
+    pub fn forward(entity_id: EntityId) -> Option<Vector4> {
+        let entity = resolve_any_entity(entity_id);
+        if entity.is_null() {
+            return None;
+        }
+        Some(entity.get_world_forward())
+    }
+
     #[allow(clippy::type_complexity)]
     pub fn full_infos(
         entity_id: EntityId,
@@ -76,6 +95,27 @@ impl Emitter {
         let position = entity.get_world_position();
         Ok(position)
     }
+    /// World position and forward direction of anchor on emitter, if still around.
+    ///
+    /// Slot is resolved from its world transform, so it follows whatever it is bound to
+    /// (parent component, bone, etc), falling back to entity origin whenever it cannot be found.
+    pub fn anchored(entity_id: EntityId, anchor: &Anchor) -> Option<(Vector4, Vector4)> {
+        let entity = resolve_any_entity(entity_id);
+        if entity.is_null() {
+            return None;
+        }
+        let transform = match anchor.slot {
+            Some(slot) => entity.get_slot_transform(slot),
+            None => entity.get_world_transform(),
+        };
+        let origin = Vec3::from(transform.position);
+        let orientation = Quat::from(transform.orientation);
+        let offset = Vec3::from(anchor.offset);
+        Some((
+            (origin + orientation * offset).into(),
+            (orientation * Vec3::Y).into(),
+        ))
+    }
     pub fn is_in_scene(entity_id: &EntityId) -> bool {
         let interface = GameInstance::get_scene_system().get_script_interface();
         interface.is_entity_in_scene(*entity_id)
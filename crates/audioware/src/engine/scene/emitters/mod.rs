@@ -1,35 +1,46 @@
-use audioware_bank::{BankData, Banks, Id};
-use audioware_core::With;
-use audioware_manifest::ValidateFor;
+use std::{
+    borrow::Borrow,
+    cmp::Ordering,
+    time::{Duration, Instant},
+};
+
+use audioware_bank::{BankData, BankDeclaredSettings, Banks, Id};
+use audioware_core::{Audibility, OcclusionProvider, SpatialTrackSettings, With};
+use audioware_manifest::{Settings, ValidateFor};
 use dashmap::{
     DashMap,
     mapref::{multiple::RefMutMulti, one::RefMut},
 };
 use either::Either;
 use kira::{
-    Tween,
+    Decibels, Tween,
     sound::{FromFileError, static_sound::StaticSoundData, streaming::StreamingSoundData},
 };
 use red4ext_rs::types::{CName, EntityId};
-use slot::EmitterSlot;
-use slots::EmitterSlots;
 
 use crate::{
     ControlId, Vector4,
     cache::cache,
     engine::{
-        scene::emitters::cache::{publish_entries, reclaim_entries, with_entries},
+        instances::Placement,
+        retriggers::delay_start,
+        scene::{
+            emitters::cache::{publish_entries, reclaim_entries, with_entries},
+            listener::Listener,
+        },
         tracks::Spatial,
         traits::{
             clear::Clear,
+            effect::{EffectParameter, SetControlledEffect},
             pause::{Pause, PauseControlled},
             playback::SetControlledPlaybackRate,
-            position::PositionControlled,
+            position::{PlayingPosition, PositionControlled},
             reclaim::Reclaim,
+            release::Release,
             resume::{Resume, ResumeControlled, ResumeControlledAt},
             seek::{SeekControlledBy, SeekControlledTo},
             stop::{Stop, StopControlled},
-            volume::SetControlledVolume,
+            volume::{PlayingVolume, SetControlledVolume},
         },
         tweens::IMMEDIATELY,
     },
@@ -38,10 +49,16 @@ use crate::{
 };
 
 mod emitter;
+mod occlusion;
 mod slot;
 mod slots;
+mod virtuals;
 
-pub use emitter::Emitter;
+pub use emitter::{Anchor, Emitter};
+use occlusion::ReportedOcclusion;
+pub use slot::{EmitterSlot, Replay};
+pub use slots::EmitterSlots;
+pub use virtuals::{VirtualSlot, VirtualVoice, start_position};
 
 use super::AffectedByTimeDilation;
 
@@ -52,7 +69,8 @@ cache!(
 
 pub struct Emitters {
     entries: DashMap<EntityId, EmitterSlots>,
-    pending_occlusions: Vec<(EntityId, f32)>,
+    /// Feeds occlusion of emitters with occlusion enabled.
+    occlusion: Box<dyn OcclusionProvider<EntityId> + Send>,
 }
 
 impl Emitters {
@@ -60,7 +78,7 @@ impl Emitters {
         publish_entries(Vec::with_capacity(capacity));
         Self {
             entries: Default::default(),
-            pending_occlusions: Vec::with_capacity(capacity),
+            occlusion: Box::new(ReportedOcclusion::default()),
         }
     }
     pub fn exists_tag(&self, entity_id: &EntityId, tag_name: &CName) -> bool {
@@ -72,7 +90,8 @@ impl Emitters {
     #[allow(clippy::too_many_arguments)]
     pub fn add_emitter(
         &mut self,
-        handle: Spatial,
+        handle: Option<Spatial>,
+        settings: SpatialTrackSettings,
         entity_id: EntityId,
         tag_name: CName,
         emitter_name: Option<CName>,
@@ -80,7 +99,7 @@ impl Emitters {
         occlusion: Option<f32>,
         last_known_position: Vector4,
         busy: bool,
-        persist_until_sounds_finish: bool,
+        anchor: Option<Anchor>,
     ) -> Result<(), Error> {
         if self.exists_tag(&entity_id, &tag_name) {
             warns!(
@@ -89,15 +108,23 @@ impl Emitters {
             );
             return Ok(());
         }
-        let slot = EmitterSlot::new(handle, tag_name, emitter_name, persist_until_sounds_finish);
-        if let Some(mut slots) = self.entries.get_mut(&entity_id) {
-            slots.value_mut().insert(slot);
-        } else {
-            self.entries.insert(
-                entity_id,
-                EmitterSlots::new(slot, dilation, busy, last_known_position),
-            );
+        let mut slots = self
+            .entries
+            .entry(entity_id)
+            .or_insert_with(|| EmitterSlots::new(dilation, busy, last_known_position));
+        match handle {
+            Some(handle) => slots.insert(EmitterSlot::new(
+                handle,
+                tag_name,
+                emitter_name,
+                settings,
+                anchor,
+            )),
+            None => {
+                slots.insert_virtual(VirtualSlot::new(tag_name, emitter_name, anchor, settings))
+            }
         }
+        drop(slots);
         let mut next = vec![];
         with_entries(|x| {
             next = x.to_vec();
@@ -110,10 +137,11 @@ impl Emitters {
         );
         Ok(())
     }
-    pub fn sync_emitters(&mut self) -> Result<(), Error> {
+    pub fn sync_emitters(&mut self, listener: &Listener) -> Result<(), Error> {
         if self.entries.is_empty() {
             return Ok(());
         }
+        let now = Instant::now();
         let mut next = vec![];
         with_entries(|x| {
             next = x.to_vec();
@@ -121,19 +149,27 @@ impl Emitters {
         self.entries.retain(|k, v| {
             if v.marked_for_death && !v.any_playing_handle() {
                 next.retain(|(id, _)| id != k);
+                self.occlusion.forget(k);
                 return false;
             }
             let Ok((position, busy)) = Emitter::infos(*k) else {
                 next.retain(|(id, _)| id != k);
+                self.occlusion.forget(k);
                 return false;
             };
             v.busy = busy;
             v.last_known_position = position;
             // weirdly enough if emitter is not updated, sound(s) won't update as expected.
             // e.g. when listener moves but emitter stands still.
-            v.set_emitter_position(position);
-            if let Some((_, (_, Some(occlusion)))) = next.iter().find(|x| x.0 == *k) {
-                v.set_emitter_occlusion(*occlusion);
+            v.set_emitter_position(*k, position, listener.position, now);
+            if v.directional()
+                && let Some(forward) = Emitter::forward(*k)
+            {
+                v.set_emitter_orientation(forward, listener.position);
+            }
+            v.set_emitter_doppler(listener);
+            if v.any_occluded() {
+                v.set_emitter_occlusion(k, &mut *self.occlusion, listener.position, now);
             }
             true
         });
@@ -149,6 +185,7 @@ impl Emitters {
         }
         if removed && last {
             self.entries.remove(entity_id);
+            self.occlusion.forget(entity_id);
         }
         if removed {
             let mut next = vec![];
@@ -169,10 +206,16 @@ impl Emitters {
                         retain = true;
                         true
                     } else {
-                        x.stop(IMMEDIATELY);
-                        false
+                        x.release(IMMEDIATELY);
+                        // let declared fade-out or ending play out
+                        let lingers = x.handles.any_lingering_handle();
+                        retain |= lingers;
+                        lingers
                     }
                 });
+                // nothing lingers on virtual slots
+                v.virtuals.retain(|x| x.persist_until_sounds_finish);
+                retain |= !v.virtuals.is_empty();
                 if retain {
                     v.marked_for_death = true;
                 }
@@ -199,6 +242,11 @@ impl Emitters {
                     slot.stop(tween);
                 }
             }
+            for slot in slots.virtuals.iter_mut() {
+                if !slot.persist_until_sounds_finish {
+                    slot.voices.clear();
+                }
+            }
         }
     }
     pub fn stop_on_emitter(
@@ -206,7 +254,7 @@ impl Emitters {
         event_name: CName,
         entity_id: EntityId,
         tag_name: CName,
-        tween: Tween,
+        tween: Option<Tween>,
     ) {
         if let Some(mut slots) = self.entries.get_mut(&entity_id) {
             slots
@@ -214,6 +262,37 @@ impl Emitters {
                 .stop_on_emitter(event_name, tag_name, tween);
         }
     }
+    /// Distance from listener and attenuation gain of emitter, if registered.
+    pub fn placement(
+        &self,
+        entity_id: &EntityId,
+        tag_name: Option<&CName>,
+        listener: &Listener,
+    ) -> Option<Placement> {
+        let slots = self.entries.get(entity_id)?;
+        let Some(tag_name) = tag_name else {
+            return Some(Placement {
+                distance: slots.last_known_position.distance(&listener.position),
+                gain: Decibels::IDENTITY,
+            });
+        };
+        let (distance, gain) = slots
+            .slots
+            .iter()
+            .find(|x| x.tag_name.as_ref() == Some(tag_name))
+            .map(|x| (x.position.distance(&listener.position), x.handle.gain()))
+            .or_else(|| {
+                slots
+                    .virtuals
+                    .iter()
+                    .find(|x| x.tag_name == *tag_name)
+                    .map(|x| {
+                        let distance = x.position.distance(&listener.position);
+                        (distance, x.settings.gain(distance))
+                    })
+            })?;
+        Some(Placement { distance, gain })
+    }
     #[allow(clippy::too_many_arguments)]
     pub fn play_on_emitter<T>(
         &mut self,
@@ -224,36 +303,159 @@ impl Emitters {
         tag_name: CName,
         ext: Option<T>,
         control_id: Option<ControlId>,
+        delay: Option<Duration>,
     ) -> Result<(f32, Option<CName>), Error>
     where
         Either<StaticSoundData, StreamingSoundData<FromFileError>>: With<Option<T>>,
         T: AffectedByTimeDilation
-            + ValidateFor<Either<StaticSoundData, StreamingSoundData<FromFileError>>>,
+            + ValidateFor<Either<StaticSoundData, StreamingSoundData<FromFileError>>>
+            + Borrow<Settings>,
     {
         let Some(mut slots) = self.get_mut(&entity_id) else {
             return Err(SceneError::MissingEmitter { entity_id }.into());
         };
-        let Some(slot) = slots.get_mut(&tag_name) else {
+        if !slots.exists_tag(&tag_name) {
             return Err(SceneError::MissingEmitter { entity_id }.into());
-        };
+        }
         let data = banks.data(key);
         if let Some(Err(e)) = ext.as_ref().map(|x| x.validate_for(&data)) {
             return Err(Error::Validation { errors: e });
         }
-        let dilatable = ext
+        let settings = banks.declared_settings(key);
+        let time_dilation = ext
             .as_ref()
-            .map(AffectedByTimeDilation::affected_by_time_dilation)
-            .unwrap_or(true);
-        slot.play_and_store(event_name, dilatable, data.with(ext), control_id)
-            .map_err(|e| match e {
-                Either::Left(e) => Error::Engine {
-                    source: EngineError::Sound { source: e },
-                },
-                Either::Right(e) => Error::Engine {
-                    source: EngineError::FromFile { source: e },
-                },
+            .map(AffectedByTimeDilation::time_dilation)
+            .or_else(|| settings.map(AffectedByTimeDilation::time_dilation))
+            .unwrap_or_default();
+        let replay_ext = ext.as_ref().map(|x| x.borrow().replayed());
+        let mut data = data.with(ext);
+        if let Some(delay) = delay {
+            delay_start(&mut data, delay);
+        }
+        let replay = Replay::new(key.clone(), replay_ext, &data);
+        let Some(slot) = slots.get_mut(&tag_name) else {
+            // keeps track of sound until emitter gets a track back
+            let slot = slots.get_virtual_mut(&tag_name).expect("checked above");
+            let duration = replay.duration as f32;
+            let now = Instant::now();
+            let mut voice = VirtualVoice::new(
+                event_name,
+                replay,
+                time_dilation,
+                control_id,
+                start_position(&data),
+                Some(now + delay.unwrap_or_default()),
+            );
+            voice.dilate(slot.dilation, now);
+            slot.voices.push(voice);
+            return Ok((duration, slot.emitter_name));
+        };
+        slot.play_and_store(
+            event_name,
+            time_dilation,
+            data,
+            control_id,
+            settings,
+            Some(replay),
+        )
+        .map_err(|e| match e {
+            Either::Left(e) => Error::Engine {
+                source: EngineError::Sound { source: e },
+            },
+            Either::Right(e) => Error::Engine {
+                source: EngineError::FromFile { source: e },
+            },
+        })
+    }
+    /// Virtual slots with sounds to play right away, along with how audible they'd be.
+    pub fn candidates(
+        &mut self,
+        listener: &Listener,
+        now: Instant,
+    ) -> Vec<(EntityId, CName, Audibility)> {
+        let mut candidates = vec![];
+        for mut slots in self.entries.iter_mut() {
+            let entity_id = *slots.key();
+            for slot in slots.virtuals.iter_mut() {
+                slot.prune(now);
+                let audibility = slot.audibility(slot.position.distance(&listener.position));
+                if slot.wants_track() && audibility.audible() {
+                    candidates.push((entity_id, slot.tag_name, audibility));
+                }
+            }
+        }
+        candidates
+    }
+    /// Where given virtual slot stands, and settings to get its track back with.
+    pub fn virtual_placement(
+        &self,
+        entity_id: &EntityId,
+        tag_name: &CName,
+    ) -> Option<(Vector4, SpatialTrackSettings)> {
+        self.entries.get(entity_id).and_then(|x| {
+            x.virtuals
+                .iter()
+                .find(|x| x.tag_name == *tag_name)
+                .map(|slot| (slot.position, slot.settings.clone()))
+        })
+    }
+    /// Get track back for given virtual slot, resuming its sounds.
+    pub fn realize(
+        &mut self,
+        entity_id: &EntityId,
+        tag_name: &CName,
+        handle: Spatial,
+        banks: &Banks,
+        now: Instant,
+    ) -> bool {
+        let Some(mut slots) = self.entries.get_mut(entity_id) else {
+            return false;
+        };
+        slots.realize(tag_name, handle, banks, now);
+        lifecycle!(
+            "emitter got its track back {entity_id} with tag name {}",
+            tag_name.as_str()
+        );
+        true
+    }
+    /// Least audible slot still holding a track, if any,
+    /// preferring slots with nothing playing, along with whether it plays anything.
+    pub fn least_audible(&self) -> Option<(EntityId, CName, bool, Audibility)> {
+        self.entries
+            .iter()
+            .flat_map(|x| {
+                let entity_id = *x.key();
+                x.slots
+                    .iter()
+                    .filter_map(|x| {
+                        Some((
+                            entity_id,
+                            x.tag_name?,
+                            x.any_playing_handle(),
+                            x.audibility(),
+                        ))
+                    })
+                    .collect::<Vec<_>>()
+            })
+            .min_by(|a, b| {
+                a.2.cmp(&b.2)
+                    .then(a.3.partial_cmp(&b.3).unwrap_or(Ordering::Equal))
             })
     }
+    /// Give up track of given slot, remembering its sounds.
+    pub fn virtualize(&mut self, entity_id: &EntityId, tag_name: &CName, now: Instant) -> bool {
+        let virtualized = self
+            .entries
+            .get_mut(entity_id)
+            .is_some_and(|mut x| x.virtualize(tag_name, now));
+        if virtualized {
+            lifecycle!(
+                "emitter gave up its track {entity_id} with tag name {}",
+                tag_name.as_str()
+            );
+        }
+        virtualized
+    }
     pub fn get_mut(&mut self, entity_id: &EntityId) -> Option<RefMut<'_, EntityId, EmitterSlots>> {
         self.entries.get_mut(entity_id)
     }
@@ -263,6 +465,11 @@ impl Emitters {
     pub fn is_empty(&self) -> bool {
         self.entries.is_empty()
     }
+    pub fn any_lingering_handle(&self) -> bool {
+        self.entries
+            .iter()
+            .any(|x| x.slots.iter().any(|x| x.handles.any_lingering_handle()))
+    }
     pub fn is_registered_emitter(entity_id: EntityId, tag_name: Option<CName>) -> bool {
         let mut registered = false;
         with_entries(|x| {
@@ -288,13 +495,7 @@ impl Emitters {
         {
             return;
         }
-        for x in self.pending_occlusions.iter_mut() {
-            if x.0 == entity_id {
-                x.1 = factor;
-                return;
-            }
-        }
-        self.pending_occlusions.push((entity_id, factor));
+        self.occlusion.report(&entity_id, factor.clamp(0., 1.));
     }
 }
 
@@ -306,6 +507,14 @@ impl Stop for Emitters {
     }
 }
 
+impl Release for Emitters {
+    fn release(&mut self, fallback: Tween) {
+        self.entries.iter_mut().for_each(|mut x| {
+            x.release(fallback);
+        });
+    }
+}
+
 impl Pause for Emitters {
     fn pause(&mut self, tween: Tween) {
         self.entries.iter_mut().for_each(|mut x| {
@@ -324,6 +533,9 @@ impl Resume for Emitters {
 
 impl Clear for Emitters {
     fn clear(&mut self) {
+        for entity_id in self.entries.iter().map(|x| *x.key()) {
+            self.occlusion.forget(&entity_id);
+        }
         self.entries.clear();
         let mut next = vec![];
         with_entries(|x| {
@@ -336,9 +548,7 @@ impl Clear for Emitters {
 
 impl Reclaim for Emitters {
     fn reclaim(&mut self) {
-        self.entries
-            .iter_mut()
-            .for_each(|mut x| x.slots.iter_mut().for_each(|x| x.handles.reclaim()));
+        self.entries.iter_mut().for_each(|mut x| x.reclaim());
         reclaim_entries();
     }
 }
@@ -364,6 +574,14 @@ impl SetControlledPlaybackRate for Emitters {
     }
 }
 
+impl SetControlledEffect for Emitters {
+    fn set_controlled_effect(&mut self, id: ControlId, parameter: EffectParameter, tween: Tween) {
+        self.entries.iter_mut().for_each(|mut x| {
+            x.set_controlled_effect(id, parameter, tween);
+        })
+    }
+}
+
 impl PositionControlled for Emitters {
     fn position_controlled(&mut self, id: ControlId, sender: crossbeam::channel::Sender<f32>) {
         self.entries.iter_mut().for_each(|mut x| {
@@ -372,6 +590,18 @@ impl PositionControlled for Emitters {
     }
 }
 
+impl PlayingPosition for Emitters {
+    fn playing_position(&self, id: ControlId) -> Option<f64> {
+        self.entries.iter().find_map(|x| x.playing_position(id))
+    }
+}
+
+impl PlayingVolume for Emitters {
+    fn playing_volume(&self, id: ControlId) -> Option<Decibels> {
+        self.entries.iter().find_map(|x| x.playing_volume(id))
+    }
+}
+
 impl StopControlled for Emitters {
     fn stop_controlled(&mut self, id: ControlId, tween: Tween) {
         self.entries.iter_mut().for_each(|mut x| {
@@ -0,0 +1,21 @@
+use std::collections::HashMap;
+
+use audioware_core::OcclusionProvider;
+use red4ext_rs::types::EntityId;
+
+/// Occlusion as last reported by game audio engine,
+/// whose own raycasts and room graph already account for geometry.
+#[derive(Debug, Default)]
+pub struct ReportedOcclusion(HashMap<EntityId, f32>);
+
+impl OcclusionProvider<EntityId> for ReportedOcclusion {
+    fn occlusion(&mut self, emitter: &EntityId, _: [f32; 3], _: [f32; 3]) -> Option<f32> {
+        self.0.get(emitter).copied()
+    }
+    fn report(&mut self, emitter: &EntityId, occlusion: f32) {
+        self.0.insert(*emitter, occlusion);
+    }
+    fn forget(&mut self, emitter: &EntityId) {
+        self.0.remove(emitter);
+    }
+}
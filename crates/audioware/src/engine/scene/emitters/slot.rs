@@ -1,25 +1,112 @@
+use std::time::Instant;
+
 use either::Either;
 use kira::{
-    PlaySoundError, Tween,
-    sound::{FromFileError, static_sound::StaticSoundData, streaming::StreamingSoundData},
+    Decibels, PlaySoundError, PlaybackRate, Tween,
+    sound::{
+        EndPosition, FromFileError, SoundData, static_sound::StaticSoundData,
+        streaming::StreamingSoundData,
+    },
+    track::TrackHandle,
 };
 use red4ext_rs::types::CName;
 
-use crate::engine::traits::{dilation::SyncDilation, pause::Pause};
+use audioware_bank::{BankData, BankDeclaredSettings, Banks, Id};
+use audioware_core::{
+    Audibility, Motion, SpatialTrackSettings, With, base_playback_rate, base_volume,
+};
+use audioware_manifest::{Settings, TimeDilation};
+
+use crate::engine::traits::{
+    dilation::SyncDilation,
+    doppler::SyncDoppler,
+    pause::Pause,
+    playback::SetPlaybackRate,
+    release::{Release, ReleasePolicy},
+};
 use crate::engine::{AffectedByTimeDilation, traits::resume::Resume};
 use crate::engine::{
-    tracks::Spatial,
+    tracks::{Inserts, Spatial},
     traits::{DualHandles, Handle, store::Store},
 };
-use crate::{ControlId, engine::traits::stop::Stop};
+
+use crate::{
+    ControlId, Vector4,
+    engine::{
+        scene::emitters::{
+            Anchor,
+            virtuals::{VirtualSlot, VirtualVoice, seconds},
+        },
+        traits::{effect::SetControlledEffect, stop::Stop, volume::SetControlledVolume},
+        tweens::{IMMEDIATELY, SHIFTED},
+    },
+    utils::warns,
+};
 
 pub struct EmitterEntryOptions {
-    pub affected_by_time_dilation: bool,
+    pub time_dilation: TimeDilation,
+    /// To play sound again once emitter gets its track back, if it can be virtualized.
+    pub replay: Option<Replay>,
+}
+
+/// What it takes to play sound again from the start.
+#[derive(Debug, Clone)]
+pub struct Replay {
+    pub key: Id,
+    /// Only settings applied to sound data, see [Settings::replayed].
+    pub ext: Option<Settings>,
+    /// In seconds.
+    pub duration: f64,
+    /// Loop start and end (in seconds), if looping.
+    pub loop_region: Option<(f64, f64)>,
+    /// Own playback rate, as sampled on play.
+    pub rate: f64,
+}
+
+impl Replay {
+    pub fn new(
+        key: Id,
+        ext: Option<Settings>,
+        data: &Either<StaticSoundData, StreamingSoundData<FromFileError>>,
+    ) -> Self {
+        let (duration, loop_region, playback_rate, sample_rate) = match data {
+            Either::Left(x) => (
+                x.duration(),
+                x.settings.loop_region,
+                x.settings.playback_rate,
+                Some(x.sample_rate),
+            ),
+            Either::Right(x) => (
+                x.duration(),
+                x.settings.loop_region,
+                x.settings.playback_rate,
+                None,
+            ),
+        };
+        let duration = duration.as_secs_f64();
+        let loop_region = loop_region.map(|x| {
+            let end = match x.end {
+                EndPosition::EndOfAudio => None,
+                EndPosition::Custom(end) => seconds(&end, sample_rate),
+            };
+            (
+                seconds(&x.start, sample_rate).unwrap_or_default(),
+                end.unwrap_or(duration),
+            )
+        });
+        Self {
+            key,
+            ext,
+            duration,
+            loop_region,
+            rate: base_playback_rate(playback_rate),
+        }
+    }
 }
 
 impl AffectedByTimeDilation for EmitterEntryOptions {
-    fn affected_by_time_dilation(&self) -> bool {
-        self.affected_by_time_dilation
+    fn time_dilation(&self) -> TimeDilation {
+        self.time_dilation
     }
 }
 
@@ -30,6 +117,15 @@ pub struct EmitterSlot {
     pub tag_name: Option<CName>,
     pub emitter_name: Option<CName>,
     pub persist_until_sounds_finish: bool,
+    /// Where sounds come from, entity origin if none.
+    pub anchor: Option<Anchor>,
+    /// World position as of last sync, at anchor if any.
+    pub position: Vector4,
+    /// Anchor forward direction as of last sync, entity own if none.
+    pub forward: Option<Vector4>,
+    pub motion: Motion,
+    /// Handle was created with.
+    pub settings: SpatialTrackSettings,
     pub handles: DualHandles<CName, EmitterEntryOptions, FromFileError>,
 }
 
@@ -44,55 +140,217 @@ impl EmitterSlot {
         handle: Spatial,
         tag_name: CName,
         emitter_name: Option<CName>,
-        persist_until_sounds_finish: bool,
+        settings: SpatialTrackSettings,
+        anchor: Option<Anchor>,
     ) -> Self {
         Self {
             handle,
             tag_name: Some(tag_name),
             emitter_name,
-            persist_until_sounds_finish,
+            persist_until_sounds_finish: settings.persist_until_sounds_finish,
+            anchor,
+            position: Vector4::default(),
+            forward: None,
+            motion: Motion::default(),
+            settings,
             handles: DualHandles::default(),
         }
     }
+    /// How much it deserves to keep its track, at last synced distance.
+    pub fn audibility(&self) -> Audibility {
+        Audibility {
+            priority: self.settings.priority,
+            gain: self.handle.gain(),
+        }
+    }
+    /// Give up track, remembering ongoing sounds to play them again later.
+    pub fn virtualize(self, now: Instant) -> Option<VirtualSlot> {
+        let mut slot = VirtualSlot::new(
+            self.tag_name?,
+            self.emitter_name,
+            self.anchor,
+            self.settings.clone(),
+        );
+        slot.persist_until_sounds_finish = self.persist_until_sounds_finish;
+        slot.position = self.position;
+        slot.dilation = self.handle.rate();
+        slot.voices = self
+            .handles
+            .ongoing()
+            .filter_map(|x| {
+                let replay = x.options.replay.clone()?;
+                let mut voice = VirtualVoice::new(
+                    *x.key,
+                    replay,
+                    x.options.time_dilation,
+                    x.control_id,
+                    x.position,
+                    (!x.paused).then_some(now),
+                );
+                voice.set_rate(x.rate, now);
+                voice.set_volume(x.volume.into());
+                voice.dilate(slot.dilation, now);
+                Some(voice)
+            })
+            .collect();
+        Some(slot)
+    }
+    /// Resume sound of virtual emitter where it would be by now,
+    /// with whatever scripts set meanwhile.
+    pub fn replay(&mut self, voice: VirtualVoice, banks: &Banks, now: Instant) {
+        let Some(position) = voice.position(now) else {
+            return;
+        };
+        let rate = PlaybackRate(voice.rate());
+        let data = banks
+            .data(&voice.replay.key)
+            .with(voice.replay.ext.clone())
+            .map_either(
+                |x| x.start_position(position).playback_rate(rate),
+                |x| x.start_position(position).playback_rate(rate),
+            );
+        if let Err(e) = self.play_and_store(
+            voice.event_name,
+            voice.time_dilation,
+            data,
+            voice.control_id,
+            banks.declared_settings(&voice.replay.key),
+            Some(voice.replay.clone()),
+        ) {
+            warns!("unable to replay sound on emitter: {e}");
+            return;
+        }
+        let Some(id) = voice.control_id else {
+            return;
+        };
+        if let Some(volume) = voice.volume() {
+            self.handles.set_controlled_volume(id, volume, IMMEDIATELY);
+        }
+        for parameter in voice.effects() {
+            self.handles
+                .set_controlled_effect(id, *parameter, IMMEDIATELY);
+        }
+    }
     pub fn play_and_store(
         &mut self,
         event_name: CName,
-        affected_by_time_dilation: bool,
+        time_dilation: TimeDilation,
         data: Either<StaticSoundData, StreamingSoundData<FromFileError>>,
         control_id: Option<ControlId>,
+        settings: Option<&Settings>,
+        replay: Option<Replay>,
     ) -> PlayResult {
+        let (duration, playback_rate, volume) = match &data {
+            Either::Left(x) => (x.duration(), x.settings.playback_rate, x.settings.volume),
+            Either::Right(x) => (x.duration(), x.settings.playback_rate, x.settings.volume),
+        };
+        let release = ReleasePolicy::new(settings, playback_rate);
+        let rate = base_playback_rate(playback_rate);
+        let volume = base_volume(volume);
+        let options = EmitterEntryOptions {
+            time_dilation,
+            replay,
+        };
         match data {
-            Either::Left(data) => {
-                let duration = data.duration().as_secs_f32();
-                let handle = self.handle.play(data).map_err(Either::Left)?;
-                self.handles.store(Handle::new(
-                    event_name,
-                    handle,
-                    EmitterEntryOptions {
-                        affected_by_time_dilation,
-                    },
-                    control_id,
-                ));
-                Ok((duration, self.emitter_name))
+            Either::Left(data) => self
+                .play_on(
+                    event_name, data, options, control_id, release, rate, volume, settings,
+                )
+                .map_err(Either::Left)?,
+            Either::Right(data) => self
+                .play_on(
+                    event_name, data, options, control_id, release, rate, volume, settings,
+                )
+                .map_err(Either::Right)?,
+        };
+        Ok((duration.as_secs_f32(), self.emitter_name))
+    }
+    /// Play on track matching time dilation, through sound own inserts if any.
+    #[allow(clippy::too_many_arguments)]
+    fn play_on<D>(
+        &mut self,
+        event_name: CName,
+        data: D,
+        options: EmitterEntryOptions,
+        control_id: Option<ControlId>,
+        release: ReleasePolicy,
+        rate: f64,
+        volume: Decibels,
+        settings: Option<&Settings>,
+    ) -> Result<(), PlaySoundError<D::Error>>
+    where
+        D: SoundData,
+        D::Handle: SetPlaybackRate,
+        DualHandles<CName, EmitterEntryOptions, FromFileError>:
+            Store<Handle<CName, D::Handle, EmitterEntryOptions>>,
+    {
+        let (handle, mut inserts) = match self.stretched(options.time_dilation) {
+            Some(track) => {
+                let mut inserts = track.inserts(settings);
+                let handle = inserts.as_deref_mut().unwrap_or(track).play(data);
+                (handle, inserts)
             }
-            Either::Right(data) => {
-                let duration = data.duration().as_secs_f32();
-                let handle = self.handle.play(data).map_err(Either::Right)?;
-                self.handles.store(Handle::new(
-                    event_name,
-                    handle,
-                    EmitterEntryOptions {
-                        affected_by_time_dilation,
-                    },
-                    control_id,
-                ));
-                Ok((duration, self.emitter_name))
+            None => {
+                let mut inserts = self.handle.inserts(settings);
+                let handle = match inserts.as_deref_mut() {
+                    Some(track) => track.play(data),
+                    None => self.handle.play(data),
+                };
+                (handle, inserts)
+            }
+        };
+        let handle = handle?;
+        if let Some(inserts) = inserts.as_mut() {
+            inserts.attenuate(&self.handle);
+        }
+        let mut handle = Handle::new(
+            event_name, handle, options, control_id, release, rate, volume,
+        )
+        .with_inserts(inserts);
+        // sound own rate, shifted by current time dilation and Doppler effect
+        let (dilation, doppler) = (self.handle.rate(), self.handle.doppler());
+        if dilation != 1. || doppler != 1. {
+            handle.sync_doppler(dilation, doppler, IMMEDIATELY);
+        }
+        self.handles.store(handle);
+        Ok(())
+    }
+    /// Move to given world position, following attenuation curves as seen from listener.
+    pub fn place(&mut self, position: Vector4, listener: Vector4, now: Instant) {
+        self.position = position;
+        self.motion.update(position.into(), now);
+        self.handle.set_position(position, IMMEDIATELY);
+        self.attenuate(position.distance(&listener));
+    }
+    /// Follow attenuation curves, if any, at given distance from listener.
+    pub fn attenuate(&mut self, distance: f32) {
+        self.handle.attenuate(distance);
+        let emitter = &self.handle;
+        self.handles
+            .inserts_mut()
+            .for_each(|x| x.attenuate(emitter));
+    }
+    /// Track to play on when time dilation should preserve pitch, if any.
+    ///
+    /// Sounds played on it are already synced with current time dilation.
+    fn stretched(&mut self, time_dilation: TimeDilation) -> Option<&mut TrackHandle> {
+        if time_dilation != TimeDilation::Stretch {
+            return None;
+        }
+        match self.handle.stretch() {
+            Ok(track) => Some(track),
+            Err(e) => {
+                warns!("unable to preserve pitch on time dilation, falling back to pitch: {e}");
+                None
             }
         }
     }
     pub fn stop(&mut self, tween: Tween) {
         self.handles.stop(tween);
     }
+    pub fn release(&mut self, fallback: Tween) {
+        self.handles.release(fallback);
+    }
     pub fn pause(&mut self, tween: Tween) {
         self.handles.pause(tween);
     }
@@ -100,7 +358,22 @@ impl EmitterSlot {
         self.handles.resume(tween);
     }
     pub fn sync_dilation(&mut self, rate: f64, tween: Tween) {
-        self.handles.sync_dilation(rate, tween);
+        match self.handle.doppler_factor() {
+            Some(_) => self
+                .handles
+                .sync_doppler(rate, self.handle.doppler(), tween),
+            None => self.handles.sync_dilation(rate, tween),
+        }
+        self.handle.sync_pitch(rate, tween);
+    }
+    /// Follow Doppler shift, on top of time dilation.
+    pub fn shift(&mut self, doppler: f64) {
+        if (doppler - self.handle.doppler()).abs() < f64::EPSILON {
+            return;
+        }
+        self.handle.set_doppler(doppler);
+        self.handles
+            .sync_doppler(self.handle.rate(), doppler, SHIFTED);
     }
     pub fn occluded(&self) -> bool {
         self.handle.occluded()
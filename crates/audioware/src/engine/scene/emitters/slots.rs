@@ -1,30 +1,42 @@
-use kira::Tween;
-use red4ext_rs::types::CName;
+use std::time::Instant;
+
+use audioware_bank::Banks;
+use audioware_core::{OcclusionProvider, angle_towards, doppler_shift};
+use kira::{Decibels, Tween};
+use red4ext_rs::types::{CName, EntityId};
 
 use crate::{
     ControlId, Vector4,
     engine::{
-        scene::dilation::Dilation,
+        scene::{dilation::Dilation, listener::Listener},
+        tracks::Spatial,
         traits::{
+            effect::{EffectParameter, SetControlledEffect},
             pause::PauseControlled,
             playback::SetControlledPlaybackRate,
-            position::PositionControlled,
+            position::{PlayingPosition, PositionControlled},
             reclaim::Reclaim,
+            release::ReleaseBy,
             resume::{ResumeControlled, ResumeControlledAt},
             seek::{SeekControlledBy, SeekControlledTo},
             stop::{StopBy, StopControlled},
-            volume::SetControlledVolume,
+            volume::{PlayingVolume, SetControlledVolume},
         },
-        tweens::IMMEDIATELY,
     },
 };
 
-use super::slot::EmitterSlot;
+use super::{
+    Emitter,
+    slot::EmitterSlot,
+    virtuals::{VirtualSlot, VirtualVoice},
+};
 
 /// Identify active [EmitterSlot] handles.
 /// These handles can be shared by multiple mods.
 pub struct EmitterSlots {
     pub slots: Vec<EmitterSlot>,
+    /// Slots without a track, once there are none left.
+    pub virtuals: Vec<VirtualSlot>,
     pub marked_for_death: bool,
     pub busy: bool,
     pub last_known_position: Vector4,
@@ -32,73 +44,214 @@ pub struct EmitterSlots {
 }
 
 impl EmitterSlots {
-    pub fn new(
-        slot: EmitterSlot,
-        dilation: Option<f32>,
-        busy: bool,
-        last_known_position: Vector4,
-    ) -> Self {
+    pub fn new(dilation: Option<f32>, busy: bool, last_known_position: Vector4) -> Self {
         Self {
-            slots: vec![slot],
+            slots: vec![],
+            virtuals: vec![],
             marked_for_death: false,
             busy,
             last_known_position,
             dilation: Dilation::new(dilation.unwrap_or(1.0)),
         }
     }
-    pub fn insert(&mut self, slot: EmitterSlot) {
+    /// Placed at emitter last known position until next sync.
+    pub fn insert(&mut self, mut slot: EmitterSlot) {
+        slot.position = self.last_known_position;
         self.slots.push(slot);
     }
+    pub fn insert_virtual(&mut self, mut slot: VirtualSlot) {
+        slot.position = self.last_known_position;
+        self.virtuals.push(slot);
+    }
     pub fn exists_tag(&self, tag_name: &CName) -> bool {
         self.slots.iter().any(|x| x.tag_name == Some(*tag_name))
+            || self.virtuals.iter().any(|x| x.tag_name == *tag_name)
     }
     pub fn any_playing_handle(&self) -> bool {
         self.slots.iter().any(|x| x.any_playing_handle())
+            || self.virtuals.iter().any(|x| x.any_playing_voice())
     }
-    pub fn set_emitter_position(&mut self, position: Vector4) {
-        self.slots.iter_mut().for_each(|x| {
-            x.handle.set_position(position, IMMEDIATELY);
-        });
+    /// Give up track of given slot, if still around.
+    pub fn virtualize(&mut self, tag_name: &CName, now: Instant) -> bool {
+        let Some(index) = self
+            .slots
+            .iter()
+            .position(|x| x.tag_name == Some(*tag_name))
+        else {
+            return false;
+        };
+        match self.slots.remove(index).virtualize(now) {
+            Some(slot) => {
+                self.virtuals.push(slot);
+                true
+            }
+            None => false,
+        }
     }
-    pub fn set_emitter_occlusion(&mut self, factor: f32) {
+    /// Get track back for given virtual slot, resuming its sounds.
+    pub fn realize(&mut self, tag_name: &CName, handle: Spatial, banks: &Banks, now: Instant) {
+        let Some(index) = self.virtuals.iter().position(|x| x.tag_name == *tag_name) else {
+            return;
+        };
+        let VirtualSlot {
+            tag_name,
+            emitter_name,
+            persist_until_sounds_finish,
+            anchor,
+            position,
+            settings,
+            voices,
+        } = self.virtuals.remove(index);
+        let mut slot = EmitterSlot::new(handle, tag_name, emitter_name, settings, anchor);
+        slot.persist_until_sounds_finish = persist_until_sounds_finish;
+        slot.position = position;
+        for voice in voices {
+            slot.replay(voice, banks, now);
+        }
+        self.slots.push(slot);
+    }
+    pub fn get_virtual_mut<'a>(&'a mut self, tag_name: &CName) -> Option<&'a mut VirtualSlot> {
+        self.virtuals.iter_mut().find(|x| x.tag_name == *tag_name)
+    }
+    fn voices_mut(&mut self) -> impl Iterator<Item = &mut VirtualVoice> {
+        self.virtuals.iter_mut().flat_map(|x| x.voices.iter_mut())
+    }
+    /// Move slots along entity, at their anchor if any.
+    pub fn set_emitter_position(
+        &mut self,
+        entity_id: EntityId,
+        position: Vector4,
+        listener: Vector4,
+        now: Instant,
+    ) {
         self.slots.iter_mut().for_each(|x| {
-            x.handle.set_occlusion(factor);
+            let anchored = x
+                .anchor
+                .as_ref()
+                .and_then(|anchor| Emitter::anchored(entity_id, anchor));
+            x.forward = anchored.map(|(_, forward)| forward);
+            x.place(
+                anchored.map(|(position, _)| position).unwrap_or(position),
+                listener,
+                now,
+            );
+        });
+        self.virtuals.iter_mut().for_each(|x| {
+            x.position = x
+                .anchor
+                .as_ref()
+                .and_then(|anchor| Emitter::anchored(entity_id, anchor))
+                .map(|(position, _)| position)
+                .unwrap_or(position);
         });
     }
+    /// Whether any slot emits in a cone.
+    pub fn directional(&self) -> bool {
+        self.slots.iter().any(|x| x.handle.directional())
+    }
+    /// Orient cones from where each slot stands, facing its anchor forward if any.
+    pub fn set_emitter_orientation(&mut self, forward: Vector4, listener: Vector4) {
+        self.slots
+            .iter_mut()
+            .filter(|x| x.handle.directional())
+            .for_each(|x| {
+                let angle = angle_towards(
+                    x.forward.unwrap_or(forward).into(),
+                    x.position.into(),
+                    listener.into(),
+                );
+                x.handle.orient(angle);
+            });
+    }
+    /// Shift pitch of slots with Doppler effect enabled, if any.
+    pub fn set_emitter_doppler(&mut self, listener: &Listener) {
+        for slot in self.slots.iter_mut() {
+            let Some(factor) = slot.handle.doppler_factor() else {
+                continue;
+            };
+            slot.shift(doppler_shift(
+                slot.position.into(),
+                slot.motion.velocity,
+                listener.position.into(),
+                listener.motion.velocity,
+                factor,
+            ));
+        }
+    }
+    /// Occlude slots with occlusion enabled, from where each stands.
+    pub fn set_emitter_occlusion(
+        &mut self,
+        entity_id: &EntityId,
+        provider: &mut dyn OcclusionProvider<EntityId>,
+        listener: Vector4,
+        now: Instant,
+    ) {
+        self.slots
+            .iter_mut()
+            .filter(|x| x.occluded())
+            .for_each(|x| {
+                if let Some(occlusion) =
+                    provider.occlusion(entity_id, x.position.into(), listener.into())
+                {
+                    x.handle.occlude(occlusion, now);
+                }
+            });
+    }
     pub fn is_empty(&self) -> bool {
-        self.slots.is_empty()
+        self.slots.is_empty() && self.virtuals.is_empty()
     }
 
-    pub fn stop_on_emitter(&mut self, event_name: CName, tag_name: CName, tween: Tween) {
+    /// Stop with given tween, or as declared in sound settings if none.
+    pub fn stop_on_emitter(&mut self, event_name: CName, tag_name: CName, tween: Option<Tween>) {
         self.slots.iter_mut().for_each(|x| {
             if x.tag_name == Some(tag_name) {
-                x.handles.stop_by(&event_name, tween);
+                match tween {
+                    Some(tween) => x.handles.stop_by(&event_name, tween),
+                    None => x.handles.release_by(&event_name, Default::default()),
+                }
             }
         });
+        self.virtuals
+            .iter_mut()
+            .filter(|x| x.tag_name == tag_name)
+            .for_each(|x| x.voices.retain(|x| x.event_name != event_name));
     }
 
     pub fn stop(&mut self, tween: Tween) {
         self.slots.iter_mut().for_each(|x| {
             x.stop(tween);
         });
+        self.virtuals.iter_mut().for_each(|x| x.voices.clear());
+    }
+
+    pub fn release(&mut self, fallback: Tween) {
+        self.slots.iter_mut().for_each(|x| {
+            x.release(fallback);
+        });
+        self.virtuals.iter_mut().for_each(|x| x.voices.clear());
     }
 
     pub fn pause(&mut self, tween: Tween) {
         self.slots.iter_mut().for_each(|x| {
             x.pause(tween);
         });
+        let now = Instant::now();
+        self.voices_mut().for_each(|x| x.pause(now));
     }
 
     pub fn resume(&mut self, tween: Tween) {
         self.slots.iter_mut().for_each(|x| {
             x.resume(tween);
         });
+        let now = Instant::now();
+        self.voices_mut().for_each(|x| x.resume(now));
     }
 
     pub fn unregister_emitter(&mut self, tag_name: &CName) -> bool {
-        let before = self.slots.len();
+        let before = self.slots.len() + self.virtuals.len();
         self.slots.retain(|x| x.tag_name != Some(*tag_name));
-        before != self.slots.len()
+        self.virtuals.retain(|x| x.tag_name != *tag_name);
+        before != self.slots.len() + self.virtuals.len()
     }
     pub fn get_mut<'a>(&'a mut self, tag_name: &CName) -> Option<&'a mut EmitterSlot> {
         self.slots
@@ -109,6 +262,11 @@ impl EmitterSlots {
         self.slots.iter_mut().for_each(|x| {
             x.sync_dilation(rate, tween);
         });
+        let now = Instant::now();
+        self.virtuals.iter_mut().for_each(|x| {
+            x.dilation = rate;
+            x.voices.iter_mut().for_each(|x| x.dilate(rate, now));
+        });
     }
     pub fn any_occluded(&self) -> bool {
         self.slots.iter().any(|x| x.occluded())
@@ -118,6 +276,8 @@ impl EmitterSlots {
 impl Reclaim for EmitterSlots {
     fn reclaim(&mut self) {
         self.slots.iter_mut().for_each(|x| x.handles.reclaim());
+        let now = Instant::now();
+        self.virtuals.iter_mut().for_each(|x| x.prune(now));
     }
 }
 
@@ -131,6 +291,9 @@ impl SetControlledVolume for EmitterSlots {
         self.slots
             .iter_mut()
             .for_each(|x| x.handles.set_controlled_volume(id, amplitude, tween));
+        self.voices_mut()
+            .filter(|x| x.control_id == Some(id))
+            .for_each(|x| x.set_volume(amplitude));
     }
 }
 
@@ -138,7 +301,22 @@ impl SetControlledPlaybackRate for EmitterSlots {
     fn set_controlled_playback_rate(&mut self, id: ControlId, rate: f64, tween: Tween) {
         self.slots.iter_mut().for_each(|x| {
             x.handles.set_controlled_playback_rate(id, rate, tween);
-        })
+        });
+        let now = Instant::now();
+        self.voices_mut()
+            .filter(|x| x.control_id == Some(id))
+            .for_each(|x| x.set_rate(rate, now));
+    }
+}
+
+impl SetControlledEffect for EmitterSlots {
+    fn set_controlled_effect(&mut self, id: ControlId, parameter: EffectParameter, tween: Tween) {
+        self.slots.iter_mut().for_each(|x| {
+            x.handles.set_controlled_effect(id, parameter, tween);
+        });
+        self.voices_mut()
+            .filter(|x| x.control_id == Some(id))
+            .for_each(|x| x.set_effect(parameter));
     }
 }
 
@@ -150,11 +328,46 @@ impl PositionControlled for EmitterSlots {
     }
 }
 
+impl PlayingPosition for EmitterSlots {
+    fn playing_position(&self, id: ControlId) -> Option<f64> {
+        let now = Instant::now();
+        self.slots
+            .iter()
+            .find_map(|x| x.handles.playing_position(id))
+            .or_else(|| {
+                self.virtuals
+                    .iter()
+                    .flat_map(|x| x.voices.iter())
+                    .filter(|x| x.control_id == Some(id))
+                    .find_map(|x| x.position(now))
+            })
+    }
+}
+
+impl PlayingVolume for EmitterSlots {
+    fn playing_volume(&self, id: ControlId) -> Option<Decibels> {
+        self.slots
+            .iter()
+            .find_map(|x| x.handles.playing_volume(id))
+            .or_else(|| {
+                self.virtuals
+                    .iter()
+                    .flat_map(|x| x.voices.iter())
+                    .filter(|x| x.control_id == Some(id))
+                    .find_map(|x| x.volume())
+                    .map(Into::into)
+            })
+    }
+}
+
 impl StopControlled for EmitterSlots {
     fn stop_controlled(&mut self, id: ControlId, tween: Tween) {
         self.slots.iter_mut().for_each(|x| {
             x.handles.stop_controlled(id, tween);
-        })
+        });
+        self.virtuals
+            .iter_mut()
+            .for_each(|x| x.voices.retain(|x| x.control_id != Some(id)));
     }
 }
 
@@ -162,7 +375,11 @@ impl PauseControlled for EmitterSlots {
     fn pause_controlled(&mut self, id: ControlId, tween: Tween) {
         self.slots.iter_mut().for_each(|x| {
             x.handles.pause_controlled(id, tween);
-        })
+        });
+        let now = Instant::now();
+        self.voices_mut()
+            .filter(|x| x.control_id == Some(id))
+            .for_each(|x| x.pause(now));
     }
 }
 
@@ -170,7 +387,11 @@ impl ResumeControlled for EmitterSlots {
     fn resume_controlled(&mut self, id: ControlId, tween: Tween) {
         self.slots.iter_mut().for_each(|x| {
             x.handles.resume_controlled(id, tween);
-        })
+        });
+        let now = Instant::now();
+        self.voices_mut()
+            .filter(|x| x.control_id == Some(id))
+            .for_each(|x| x.resume(now));
     }
 }
 
@@ -178,7 +399,12 @@ impl ResumeControlledAt for EmitterSlots {
     fn resume_controlled_at(&mut self, id: ControlId, delay: f64, tween: Tween) {
         self.slots.iter_mut().for_each(|x| {
             x.handles.resume_controlled_at(id, delay, tween);
-        })
+        });
+        // resumes right away rather than late, while virtual
+        let now = Instant::now();
+        self.voices_mut()
+            .filter(|x| x.control_id == Some(id))
+            .for_each(|x| x.resume(now));
     }
 }
 
@@ -186,7 +412,11 @@ impl SeekControlledTo for EmitterSlots {
     fn seek_controlled_to(&mut self, id: ControlId, position: f64) {
         self.slots.iter_mut().for_each(|x| {
             x.handles.seek_controlled_to(id, position);
-        })
+        });
+        let now = Instant::now();
+        self.voices_mut()
+            .filter(|x| x.control_id == Some(id))
+            .for_each(|x| x.seek_to(position, now));
     }
 }
 
@@ -194,6 +424,10 @@ impl SeekControlledBy for EmitterSlots {
     fn seek_controlled_by(&mut self, id: ControlId, amount: f64) {
         self.slots.iter_mut().for_each(|x| {
             x.handles.seek_controlled_by(id, amount);
-        })
+        });
+        let now = Instant::now();
+        self.voices_mut()
+            .filter(|x| x.control_id == Some(id))
+            .for_each(|x| x.seek_by(amount, now));
     }
 }
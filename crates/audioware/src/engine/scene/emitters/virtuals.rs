@@ -0,0 +1,217 @@
+//! Emitters without a track, once there are none left.
+
+use std::{mem, time::Instant};
+
+use audioware_core::{Amplitude, Audibility, SpatialTrackSettings, advance};
+use audioware_manifest::TimeDilation;
+use either::Either;
+use kira::sound::{
+    FromFileError, PlaybackPosition, static_sound::StaticSoundData, streaming::StreamingSoundData,
+};
+use red4ext_rs::types::CName;
+
+use crate::{
+    ControlId, Vector4,
+    engine::{scene::emitters::Anchor, traits::effect::EffectParameter},
+};
+
+use super::slot::Replay;
+
+/// Sound on a virtual emitter, whose position keeps advancing.
+pub struct VirtualVoice {
+    pub event_name: CName,
+    pub replay: Replay,
+    pub time_dilation: TimeDilation,
+    pub control_id: Option<ControlId>,
+    /// Logical position (in seconds) as of `since`.
+    position: f64,
+    /// Paused if none.
+    since: Option<Instant>,
+    /// Own playback rate, as last set from scripts if any.
+    rate: f64,
+    /// Time dilation, if affected.
+    dilation: f64,
+    /// Volume as last set from scripts, if any.
+    volume: Option<Amplitude>,
+    /// Effect parameters as last set from scripts, if any.
+    effects: Vec<EffectParameter>,
+}
+
+impl VirtualVoice {
+    pub fn new(
+        event_name: CName,
+        replay: Replay,
+        time_dilation: TimeDilation,
+        control_id: Option<ControlId>,
+        position: f64,
+        since: Option<Instant>,
+    ) -> Self {
+        Self {
+            event_name,
+            rate: replay.rate,
+            replay,
+            time_dilation,
+            control_id,
+            position,
+            since,
+            dilation: 1.,
+            volume: None,
+            effects: vec![],
+        }
+    }
+    /// Logical position (in seconds), if still playing.
+    pub fn position(&self, now: Instant) -> Option<f64> {
+        let elapsed = self
+            .since
+            .map(|x| now.saturating_duration_since(x).as_secs_f64())
+            .unwrap_or_default();
+        advance(
+            self.position,
+            elapsed,
+            self.rate * self.dilation,
+            self.replay.duration,
+            self.replay.loop_region,
+        )
+    }
+    /// Settle position so far, before rate changes.
+    fn settle(&mut self, now: Instant) {
+        if self.since.is_some_and(|x| x < now) {
+            self.position = self.position(now).unwrap_or(self.replay.duration);
+            self.since = Some(now);
+        }
+    }
+    pub fn paused(&self) -> bool {
+        self.since.is_none()
+    }
+    pub fn pause(&mut self, now: Instant) {
+        if let Some(position) = self.position(now) {
+            self.position = position;
+        }
+        self.since = None;
+    }
+    pub fn resume(&mut self, now: Instant) {
+        if self.since.is_none() {
+            self.since = Some(now);
+        }
+    }
+    pub fn seek_to(&mut self, position: f64, now: Instant) {
+        self.position = position.max(0.);
+        if self.since.is_some() {
+            self.since = Some(now);
+        }
+    }
+    pub fn seek_by(&mut self, amount: f64, now: Instant) {
+        if let Some(position) = self.position(now) {
+            self.seek_to(position + amount, now);
+        }
+    }
+    pub fn rate(&self) -> f64 {
+        self.rate
+    }
+    pub fn set_rate(&mut self, rate: f64, now: Instant) {
+        self.settle(now);
+        self.rate = rate;
+    }
+    pub fn dilate(&mut self, dilation: f64, now: Instant) {
+        if self.time_dilation.is_affected() {
+            self.settle(now);
+            self.dilation = dilation;
+        }
+    }
+    pub fn volume(&self) -> Option<Amplitude> {
+        self.volume
+    }
+    pub fn set_volume(&mut self, volume: Amplitude) {
+        self.volume = Some(volume);
+    }
+    pub fn effects(&self) -> &[EffectParameter] {
+        &self.effects
+    }
+    /// Only last value of each parameter is kept.
+    pub fn set_effect(&mut self, parameter: EffectParameter) {
+        self.effects.retain(|x| !same_target(x, &parameter));
+        self.effects.push(parameter);
+    }
+}
+
+/// Whether both parameters set the same thing, e.g. cutoff of same filter.
+fn same_target(lhs: &EffectParameter, rhs: &EffectParameter) -> bool {
+    match (lhs, rhs) {
+        (EffectParameter::FilterCutoff(lhs, _), EffectParameter::FilterCutoff(rhs, _))
+        | (EffectParameter::FilterResonance(lhs, _), EffectParameter::FilterResonance(rhs, _)) => {
+            lhs == rhs
+        }
+        _ => mem::discriminant(lhs) == mem::discriminant(rhs),
+    }
+}
+
+/// Emitter slot which gave up its track, see [EmitterSlot](super::EmitterSlot).
+pub struct VirtualSlot {
+    pub tag_name: CName,
+    pub emitter_name: Option<CName>,
+    pub persist_until_sounds_finish: bool,
+    pub anchor: Option<Anchor>,
+    /// World position as of last sync, at anchor if any.
+    pub position: Vector4,
+    /// Time dilation as of last sync.
+    pub dilation: f64,
+    /// To get a track back with.
+    pub settings: SpatialTrackSettings,
+    pub voices: Vec<VirtualVoice>,
+}
+
+impl VirtualSlot {
+    pub fn new(
+        tag_name: CName,
+        emitter_name: Option<CName>,
+        anchor: Option<Anchor>,
+        settings: SpatialTrackSettings,
+    ) -> Self {
+        Self {
+            tag_name,
+            emitter_name,
+            persist_until_sounds_finish: settings.persist_until_sounds_finish,
+            anchor,
+            position: Vector4::default(),
+            dilation: 1.,
+            settings,
+            voices: vec![],
+        }
+    }
+    pub fn audibility(&self, distance: f32) -> Audibility {
+        Audibility {
+            priority: self.settings.priority,
+            gain: self.settings.gain(distance),
+        }
+    }
+    pub fn any_playing_voice(&self) -> bool {
+        let now = Instant::now();
+        self.voices.iter().any(|x| x.position(now).is_some())
+    }
+    /// Only worth a track when it has sounds to play right away.
+    pub fn wants_track(&self) -> bool {
+        !self.voices.is_empty() && self.voices.iter().all(|x| !x.paused())
+    }
+    /// Forget sounds which would have finished by now.
+    pub fn prune(&mut self, now: Instant) {
+        self.voices.retain(|x| x.position(now).is_some());
+    }
+}
+
+/// Starting position (in seconds) of sound data.
+pub fn start_position(data: &Either<StaticSoundData, StreamingSoundData<FromFileError>>) -> f64 {
+    match data {
+        Either::Left(x) => seconds(&x.settings.start_position, Some(x.sample_rate)),
+        Either::Right(x) => seconds(&x.settings.start_position, None),
+    }
+    .unwrap_or_default()
+}
+
+/// Position in seconds, unless in samples of unknown sample rate.
+pub fn seconds(position: &PlaybackPosition, sample_rate: Option<u32>) -> Option<f64> {
+    match (position, sample_rate) {
+        (PlaybackPosition::Seconds(x), _) => Some(*x),
+        (PlaybackPosition::Samples(x), Some(rate)) if rate > 0 => Some(*x as f64 / rate as f64),
+        _ => None,
+    }
+}
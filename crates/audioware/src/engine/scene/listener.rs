@@ -1,8 +1,9 @@
+use audioware_core::Motion;
 use debug_ignore::DebugIgnore;
 use kira::listener::ListenerHandle;
 use red4ext_rs::types::{EntityId, WeakRef};
 
-use crate::CameraComponent;
+use crate::{CameraComponent, Vector4};
 
 use super::dilation::Dilation;
 
@@ -10,6 +11,9 @@ use super::dilation::Dilation;
 pub struct Listener {
     pub id: EntityId,
     pub handle: ListenerHandle,
+    /// Last synced position.
+    pub position: Vector4,
+    pub motion: Motion,
     pub dilation: Dilation,
     pub overriden: Option<DebugIgnore<WeakRef<CameraComponent>>>,
 }
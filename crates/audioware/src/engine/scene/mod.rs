@@ -1,35 +1,45 @@
-use std::num::NonZero;
+use std::{
+    cmp::Ordering,
+    num::NonZero,
+    time::{Duration, Instant},
+};
 
-use audioware_core::SpatialTrackSettings;
+use audioware_bank::Banks;
+use audioware_core::{Audibility, Motion, SpatialTrackSettings};
 use audioware_manifest::PlayerGender;
 use debug_ignore::DebugIgnore;
 use dilation::Dilation;
+pub use emitters::Anchor;
 use emitters::{Emitter, Emitters};
-use kira::{AudioManager, Easing, Tween, backend::Backend, track::SpatialTrackDistances};
+use kira::{AudioManager, Decibels, Easing, Tween, backend::Backend, track::SpatialTrackDistances};
 use listener::Listener;
+use positionals::Positionals;
 use red4ext_rs::types::{CName, EntityId, GameInstance, Ref, WeakRef};
 
 use crate::{
     AsEntity, AsIComponent, AsScriptedPuppet, AsTimeDilatable, AvObject, BikeObject,
     CameraComponent, CarObject, ControlId, Device, Entity, GamedataNpcType, IComponent,
-    ScriptedPuppet, TankObject, TimeDilatable, VehicleObject,
+    PositionalId, ScriptedPuppet, TankObject, TimeDilatable, Vector4, VehicleObject,
     engine::{
+        instances::Placement,
         scene::actors::{Actors, slot::ActorSlot},
-        tracks::Spatial,
+        tracks::{RESERVED_FOR_INSERTS, Spatial},
         traits::{
-            clear::Clear,
+            effect::{EffectParameter, SetControlledEffect},
             pause::{Pause, PauseControlled},
             playback::SetControlledPlaybackRate,
-            position::PositionControlled,
+            position::{PlayingPosition, PositionControlled},
             reclaim::Reclaim,
+            release::Release,
             resume::{Resume, ResumeControlled, ResumeControlledAt},
             seek::{SeekControlledBy, SeekControlledTo},
-            stop::{Stop, StopControlled},
-            volume::SetControlledVolume,
+            stop::StopControlled,
+            volume::{PlayingVolume, SetControlledVolume},
         },
     },
-    error::{Error, SceneError},
+    error::{EngineError, Error, SceneError},
     get_player, resolve_any_entity,
+    utils::warns,
 };
 
 use super::{lifecycle, tracks::ambience::Ambience, tweens::IMMEDIATELY};
@@ -38,14 +48,29 @@ mod actors;
 mod dilation;
 mod emitters;
 mod listener;
+mod positionals;
 
 pub use dilation::{AffectedByTimeDilation, DilationUpdate};
 
+/// Minimum delay between two emitters giving up their track,
+/// since freed tracks are only reclaimed on next audio update.
+const TAKEOVER_COOLDOWN: Duration = Duration::from_millis(250);
+
+/// Emitter slot competing for a track, see [Scene::rebalance].
+#[derive(Debug, Clone, Copy)]
+enum Contender {
+    Emitter(EntityId, CName),
+    Positional(PositionalId),
+}
+
 /// Audio spatial scene.
 pub struct Scene {
     pub emitters: Emitters,
     pub actors: Actors,
+    pub positionals: Positionals,
     pub v: Listener,
+    /// Last time an emitter gave up its track for a virtual one.
+    last_takeover: Option<Instant>,
 }
 
 impl Scene {
@@ -69,11 +94,17 @@ impl Scene {
             v: Listener {
                 id,
                 handle,
+                position,
+                motion: Motion::default(),
                 dilation: Dilation::new(dilation),
                 overriden: None,
             },
-            emitters: Emitters::with_capacity(capacity - RESERVED_FOR_ACTORS),
+            emitters: Emitters::with_capacity(
+                capacity - RESERVED_FOR_ACTORS - RESERVED_FOR_INSERTS,
+            ),
             actors: Actors::with_capacity(RESERVED_FOR_ACTORS),
+            positionals: Positionals::default(),
+            last_takeover: None,
         })
     }
 
@@ -85,6 +116,7 @@ impl Scene {
         tag_name: CName,
         emitter_name: Option<CName>,
         settings: Option<&(SpatialTrackSettings, NonZero<u64>)>,
+        anchor: Option<Anchor>,
         ambience: &Ambience,
     ) -> Result<(), Error> {
         if entity_id == self.v.id {
@@ -111,9 +143,27 @@ impl Scene {
             },
         };
         lifecycle!("emitter settings after {:?} [{entity_id}]", mapped);
-        let handle = Spatial::try_new(manager, self.v.handle.id(), position, mapped, ambience)?;
+        let handle = match Spatial::try_new(
+            manager,
+            self.v.handle.id(),
+            position,
+            position.distance(&self.v.position),
+            mapped.clone(),
+            ambience,
+        ) {
+            Ok(handle) => Some(handle),
+            // virtual until it gets a track back
+            Err(Error::Engine {
+                source: EngineError::Limit { .. },
+            }) => {
+                lifecycle!("no track left, emitter is virtual [{entity_id}]");
+                None
+            }
+            Err(e) => return Err(e),
+        };
         self.emitters.add_emitter(
             handle,
+            mapped,
             entity_id,
             tag_name,
             emitter_name,
@@ -121,12 +171,56 @@ impl Scene {
             Some(0.),
             position,
             busy,
-            settings
-                .map(|x| x.0.persist_until_sounds_finish)
-                .unwrap_or(false),
+            anchor,
         )
     }
 
+    /// Emitter at given position, without entity.
+    #[allow(clippy::too_many_arguments)]
+    pub fn add_positional<B: Backend>(
+        &mut self,
+        manager: &mut AudioManager<B>,
+        id: PositionalId,
+        position: Vector4,
+        settings: Option<&(SpatialTrackSettings, NonZero<u64>)>,
+        lifetime: Option<Duration>,
+        one_shot: bool,
+        ambience: &Ambience,
+    ) -> Result<(), Error> {
+        let settings = settings.map(|x| x.0.clone()).unwrap_or_default();
+        let persist_until_sounds_finish = settings.persist_until_sounds_finish;
+        lifecycle!("positional emitter settings {:?} [{id}]", settings);
+        // shares emitters budget, virtual until it gets a track back
+        let handle = match Spatial::try_new(
+            manager,
+            self.v.handle.id(),
+            position,
+            position.distance(&self.v.position),
+            settings.clone(),
+            ambience,
+        ) {
+            Ok(handle) => Some(handle),
+            Err(Error::Engine {
+                source: EngineError::Limit { .. },
+            }) => {
+                lifecycle!("no track left, positional emitter is virtual [{id}]");
+                None
+            }
+            Err(e) => return Err(e),
+        };
+        self.positionals.add(
+            id,
+            handle,
+            settings,
+            position,
+            lifetime,
+            // one-shots always let their sound play out
+            persist_until_sounds_finish || one_shot,
+            one_shot,
+        );
+        Ok(())
+    }
+
     pub fn exists_actor(&self, entity_id: &EntityId) -> bool {
         self.actors.exists(entity_id)
     }
@@ -157,6 +251,7 @@ impl Scene {
             manager,
             self.v.handle.id(),
             position,
+            position.distance(&self.v.position),
             settings.clone(),
             ambience,
         )?;
@@ -174,15 +269,16 @@ impl Scene {
         event_name: CName,
         entity_id: EntityId,
         tag_name: CName,
-        tween: Tween,
+        tween: Option<Tween>,
     ) {
         self.emitters
             .stop_on_emitter(event_name, entity_id, tag_name, tween);
     }
 
-    pub fn stop_emitters_and_actors(&mut self, tween: Tween) {
-        self.emitters.stop(tween);
-        self.actors.stop(tween);
+    pub fn release_emitters_and_actors(&mut self, fallback: Tween) {
+        self.emitters.release(fallback);
+        self.actors.release(fallback);
+        self.positionals.release(fallback);
     }
 
     fn sync_listener(&mut self) -> Result<(), Error> {
@@ -210,6 +306,8 @@ impl Scene {
         };
         self.v.handle.set_position(position, IMMEDIATELY);
         self.v.handle.set_orientation(orientation, IMMEDIATELY);
+        self.v.position = position;
+        self.v.motion.update(position.into(), Instant::now());
         Ok(())
     }
 
@@ -218,19 +316,144 @@ impl Scene {
     }
 
     fn sync_emitters(&mut self) -> Result<(), Error> {
-        self.emitters.sync_emitters()?;
+        self.emitters.sync_emitters(&self.v)?;
         Ok(())
     }
 
     fn sync_actors(&mut self) -> Result<(), Error> {
-        self.actors.sync_emitters()?;
+        self.actors.sync_emitters(&self.v)?;
         Ok(())
     }
 
-    pub fn sync(&mut self) -> Result<(), Error> {
+    fn sync_positionals(&mut self) {
+        self.positionals.sync_emitters(&self.v);
+    }
+
+    /// Hand tracks over to virtual emitters (positional ones included),
+    /// from most to least audible, taking them from less audible ones
+    /// once there are none left.
+    fn rebalance<B: Backend>(
+        &mut self,
+        manager: &mut AudioManager<B>,
+        banks: &Banks,
+        ambience: &Ambience,
+    ) {
+        let now = Instant::now();
+        let mut candidates = self
+            .emitters
+            .candidates(&self.v, now)
+            .into_iter()
+            .map(|(entity_id, tag_name, audibility)| {
+                (Contender::Emitter(entity_id, tag_name), audibility)
+            })
+            .chain(
+                self.positionals
+                    .candidates(&self.v, now)
+                    .into_iter()
+                    .map(|(id, audibility)| (Contender::Positional(id), audibility)),
+            )
+            .collect::<Vec<_>>();
+        if candidates.is_empty() {
+            return;
+        }
+        candidates.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap_or(Ordering::Equal));
+        let mut realized = false;
+        for (contender, audibility) in candidates {
+            let Some((position, settings)) = (match contender {
+                Contender::Emitter(entity_id, tag_name) => {
+                    self.emitters.virtual_placement(&entity_id, &tag_name)
+                }
+                Contender::Positional(id) => self.positionals.virtual_placement(&id),
+            }) else {
+                continue;
+            };
+            match Spatial::try_new(
+                manager,
+                self.v.handle.id(),
+                position,
+                position.distance(&self.v.position),
+                settings,
+                ambience,
+            ) {
+                Ok(handle) => {
+                    realized |= match contender {
+                        Contender::Emitter(entity_id, tag_name) => self
+                            .emitters
+                            .realize(&entity_id, &tag_name, handle, banks, now),
+                        Contender::Positional(id) => {
+                            self.positionals.realize(&id, handle, banks, now)
+                        }
+                    };
+                }
+                Err(Error::Engine {
+                    source: EngineError::Limit { .. },
+                }) => {
+                    // track freed for it only becomes available later on
+                    self.take_over(&audibility, now);
+                    break;
+                }
+                Err(e) => {
+                    warns!("unable to get track back for emitter: {e}");
+                }
+            }
+        }
+        if realized {
+            self.sync_dilation();
+        }
+    }
+
+    /// Virtualize least audible emitter, if outranked by given one,
+    /// preferring emitters with nothing playing, which always give way.
+    fn take_over(&mut self, audibility: &Audibility, now: Instant) {
+        if self
+            .last_takeover
+            .is_some_and(|x| now.duration_since(x) < TAKEOVER_COOLDOWN)
+        {
+            return;
+        }
+        let emitter =
+            self.emitters
+                .least_audible()
+                .map(|(entity_id, tag_name, playing, audibility)| {
+                    (Contender::Emitter(entity_id, tag_name), playing, audibility)
+                });
+        let positional = self
+            .positionals
+            .least_audible()
+            .map(|(id, playing, audibility)| (Contender::Positional(id), playing, audibility));
+        let Some((contender, ..)) = emitter
+            .into_iter()
+            .chain(positional)
+            .min_by(|a, b| {
+                a.1.cmp(&b.1)
+                    .then(a.2.partial_cmp(&b.2).unwrap_or(Ordering::Equal))
+            })
+            .filter(|x| !x.1 || audibility.outranks(&x.2))
+        else {
+            return;
+        };
+        let virtualized = match contender {
+            Contender::Emitter(entity_id, tag_name) => {
+                self.emitters.virtualize(&entity_id, &tag_name, now)
+            }
+            Contender::Positional(id) => self.positionals.virtualize(&id, now),
+        };
+        if virtualized {
+            self.last_takeover = Some(now);
+        }
+    }
+
+    pub fn sync<B: Backend>(
+        &mut self,
+        manager: &mut AudioManager<B>,
+        banks: &Banks,
+        ambience: &Ambience,
+    ) -> Result<(), Error> {
         self.sync_listener()?;
         self.sync_emitters()?;
+        self.rebalance(manager, banks, ambience);
         self.sync_actors()?;
+        self.sync_positionals();
         Ok(())
     }
 
@@ -251,32 +474,43 @@ impl Scene {
     }
 
     pub fn any_emitter(&self) -> bool {
-        !self.emitters.is_empty()
+        !self.emitters.is_empty() || !self.positionals.is_empty()
     }
 
     pub fn any_actor(&self) -> bool {
         !self.actors.is_empty()
     }
 
-    pub fn clear(&mut self) {
-        self.stop_emitters_and_actors(IMMEDIATELY);
-        self.emitters.clear();
-        self.actors.clear();
+    /// Release emitters and actors as declared in their settings,
+    /// keeping scene around only as long as any of their sounds lingers.
+    pub fn release(mut self) -> Option<Self> {
+        self.release_emitters_and_actors(IMMEDIATELY);
+        self.any_lingering_handle().then_some(self)
+    }
+
+    /// Whether any sound still plays out its declared release.
+    pub fn any_lingering_handle(&self) -> bool {
+        self.emitters.any_lingering_handle()
+            || self.actors.any_lingering_handle()
+            || self.positionals.any_lingering_handle()
     }
 
     pub fn pause(&mut self, tween: Tween) {
         self.emitters.pause(tween);
         self.actors.pause(tween);
+        self.positionals.pause(tween);
     }
 
     pub fn resume(&mut self, tween: Tween) {
         self.emitters.resume(tween);
         self.actors.resume(tween);
+        self.positionals.resume(tween);
     }
 
     pub fn reclaim(&mut self) {
         self.emitters.reclaim();
         self.actors.reclaim();
+        self.positionals.reclaim();
     }
 
     pub fn set_listener_dilation(&mut self, dilation: &DilationUpdate) -> bool {
@@ -350,12 +584,20 @@ impl Scene {
             lifecycle!("sync emitter handle dilation: {rate} {tween:?}");
             slots.sync_dilation(rate, tween.unwrap_or(IMMEDIATELY));
         }
+        // positional emitters have no time dilation of their own
+        self.positionals
+            .sync_dilation(listener, tween.unwrap_or(IMMEDIATELY));
     }
 
     pub fn listener_id(&self) -> EntityId {
         self.v.id
     }
 
+    /// Where sound played on entity (and emitter, if tagged) stands, if registered.
+    pub fn placement(&self, entity_id: &EntityId, tag_name: Option<&CName>) -> Option<Placement> {
+        self.emitters.placement(entity_id, tag_name, &self.v)
+    }
+
     pub fn emitters_count() -> i32 {
         Emitters::emitters_count()
     }
@@ -458,53 +700,87 @@ impl SetControlledVolume for Scene {
         tween: Tween,
     ) {
         self.emitters.set_controlled_volume(id, amplitude, tween);
+        self.positionals.set_controlled_volume(id, amplitude, tween);
     }
 }
 
 impl SetControlledPlaybackRate for Scene {
     fn set_controlled_playback_rate(&mut self, id: ControlId, rate: f64, tween: Tween) {
         self.emitters.set_controlled_playback_rate(id, rate, tween);
+        self.positionals
+            .set_controlled_playback_rate(id, rate, tween);
+    }
+}
+
+impl SetControlledEffect for Scene {
+    fn set_controlled_effect(&mut self, id: ControlId, parameter: EffectParameter, tween: Tween) {
+        self.emitters.set_controlled_effect(id, parameter, tween);
+        self.positionals.set_controlled_effect(id, parameter, tween);
+    }
+}
+
+impl PlayingPosition for Scene {
+    fn playing_position(&self, id: ControlId) -> Option<f64> {
+        self.emitters
+            .playing_position(id)
+            .or_else(|| self.actors.playing_position(id))
+            .or_else(|| self.positionals.playing_position(id))
+    }
+}
+
+impl PlayingVolume for Scene {
+    fn playing_volume(&self, id: ControlId) -> Option<Decibels> {
+        self.emitters
+            .playing_volume(id)
+            .or_else(|| self.positionals.playing_volume(id))
     }
 }
 
 impl PositionControlled for Scene {
     fn position_controlled(&mut self, id: ControlId, sender: crossbeam::channel::Sender<f32>) {
-        self.emitters.position_controlled(id, sender);
+        self.emitters.position_controlled(id, sender.clone());
+        self.positionals.position_controlled(id, sender);
     }
 }
 
 impl StopControlled for Scene {
     fn stop_controlled(&mut self, id: ControlId, tween: Tween) {
         self.emitters.stop_controlled(id, tween);
+        self.positionals.stop_controlled(id, tween);
     }
 }
 
 impl PauseControlled for Scene {
     fn pause_controlled(&mut self, id: ControlId, tween: Tween) {
         self.emitters.pause_controlled(id, tween);
+        self.positionals.pause_controlled(id, tween);
     }
 }
 
 impl ResumeControlled for Scene {
     fn resume_controlled(&mut self, id: ControlId, tween: Tween) {
         self.emitters.resume_controlled(id, tween);
+        self.positionals.resume_controlled(id, tween);
     }
 }
 
 impl ResumeControlledAt for Scene {
     fn resume_controlled_at(&mut self, id: ControlId, delay: f64, tween: Tween) {
         self.emitters.resume_controlled_at(id, delay, tween);
+        self.positionals.resume_controlled_at(id, delay, tween);
     }
 }
 
 impl SeekControlledTo for Scene {
     fn seek_controlled_to(&mut self, id: ControlId, position: f64) {
         self.emitters.seek_controlled_to(id, position);
+        self.positionals.seek_controlled_to(id, position);
     }
 }
 
 impl SeekControlledBy for Scene {
     fn seek_controlled_by(&mut self, id: ControlId, amount: f64) {
         self.emitters.seek_controlled_by(id, amount);
+        self.positionals.seek_controlled_by(id, amount);
     }
 }
@@ -0,0 +1,461 @@
+//! Emitters at world positions, without entity.
+
+use std::{
+    borrow::Borrow,
+    cmp::Ordering,
+    time::{Duration, Instant},
+};
+
+use audioware_bank::{BankData, BankDeclaredSettings, Banks, Id};
+use audioware_core::{Audibility, SpatialTrackSettings, With};
+use audioware_manifest::{Settings, ValidateFor};
+use dashmap::DashMap;
+use either::Either;
+use kira::{
+    Decibels, Tween,
+    sound::{FromFileError, static_sound::StaticSoundData, streaming::StreamingSoundData},
+};
+use red4ext_rs::types::CName;
+
+use crate::{
+    ControlId, PositionalId, Vector4,
+    engine::{
+        retriggers::delay_start,
+        scene::{
+            AffectedByTimeDilation,
+            emitters::{
+                EmitterSlot, EmitterSlots, Replay, VirtualSlot, VirtualVoice, start_position,
+            },
+            listener::Listener,
+        },
+        tracks::Spatial,
+        traits::{
+            effect::{EffectParameter, SetControlledEffect},
+            pause::PauseControlled,
+            playback::SetControlledPlaybackRate,
+            position::{PlayingPosition, PositionControlled},
+            reclaim::Reclaim,
+            resume::{ResumeControlled, ResumeControlledAt},
+            seek::{SeekControlledBy, SeekControlledTo},
+            stop::StopControlled,
+            volume::{PlayingVolume, SetControlledVolume},
+        },
+        tweens::IMMEDIATELY,
+    },
+    error::{EngineError, Error, SceneError},
+    utils::lifecycle,
+};
+
+/// Emitter at a fixed position, or moved by scripts.
+///
+/// Holds a single slot without tag name, virtual once there are no tracks left.
+pub struct Positional {
+    slots: EmitterSlots,
+    /// Beyond which sounds are released.
+    expires: Option<Instant>,
+    /// Played once at position, also removed once out of earshot.
+    one_shot: bool,
+}
+
+impl Positional {
+    /// How much it deserves a track, at given distance from listener.
+    fn audibility(&self, distance: f32) -> Option<Audibility> {
+        self.slots
+            .slots
+            .first()
+            .map(|x| x.audibility())
+            .or_else(|| self.slots.virtuals.first().map(|x| x.audibility(distance)))
+    }
+}
+
+#[derive(Default)]
+pub struct Positionals(DashMap<PositionalId, Positional>);
+
+impl Positionals {
+    #[allow(clippy::too_many_arguments)]
+    pub fn add(
+        &mut self,
+        id: PositionalId,
+        handle: Option<Spatial>,
+        settings: SpatialTrackSettings,
+        position: Vector4,
+        lifetime: Option<Duration>,
+        persist_until_sounds_finish: bool,
+        one_shot: bool,
+    ) {
+        let mut slots = EmitterSlots::new(None, false, position);
+        slots.marked_for_death = one_shot;
+        match handle {
+            Some(handle) => {
+                let mut slot = EmitterSlot::new(handle, CName::undefined(), None, settings, None);
+                slot.persist_until_sounds_finish = persist_until_sounds_finish;
+                slots.insert(slot);
+            }
+            None => {
+                let mut slot = VirtualSlot::new(CName::undefined(), None, None, settings);
+                slot.persist_until_sounds_finish = persist_until_sounds_finish;
+                slots.insert_virtual(slot);
+            }
+        }
+        self.0.insert(
+            id,
+            Positional {
+                slots,
+                expires: lifetime.map(|x| Instant::now() + x),
+                one_shot,
+            },
+        );
+        lifecycle!("added positional emitter {id} at {position}");
+    }
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+    /// Removed right away, unless its sounds should persist until they finish.
+    pub fn unregister(&mut self, id: &PositionalId) -> bool {
+        let Some(mut positional) = self.0.get_mut(id) else {
+            return false;
+        };
+        let persist = positional
+            .slots
+            .slots
+            .iter()
+            .map(|x| x.persist_until_sounds_finish)
+            .chain(
+                positional
+                    .slots
+                    .virtuals
+                    .iter()
+                    .map(|x| x.persist_until_sounds_finish),
+            )
+            .any(|x| x);
+        if persist {
+            positional.slots.marked_for_death = true;
+            return true;
+        }
+        drop(positional);
+        self.0.remove(id).is_some()
+    }
+    pub fn set_position(&mut self, id: &PositionalId, position: Vector4) -> bool {
+        match self.0.get_mut(id) {
+            Some(mut positional) => {
+                positional.slots.last_known_position = position;
+                true
+            }
+            None => false,
+        }
+    }
+    #[allow(clippy::too_many_arguments)]
+    pub fn play<T>(
+        &mut self,
+        id: PositionalId,
+        key: &Id,
+        banks: &Banks,
+        event_name: CName,
+        ext: Option<T>,
+        control_id: Option<ControlId>,
+        delay: Option<Duration>,
+    ) -> Result<f32, Error>
+    where
+        Either<StaticSoundData, StreamingSoundData<FromFileError>>: With<Option<T>>,
+        T: AffectedByTimeDilation
+            + ValidateFor<Either<StaticSoundData, StreamingSoundData<FromFileError>>>
+            + Borrow<Settings>,
+    {
+        let Some(mut positional) = self.0.get_mut(&id) else {
+            return Err(SceneError::MissingPositionalEmitter { id }.into());
+        };
+        let data = banks.data(key);
+        if let Some(Err(e)) = ext.as_ref().map(|x| x.validate_for(&data)) {
+            return Err(Error::Validation { errors: e });
+        }
+        let settings = banks.declared_settings(key);
+        let time_dilation = ext
+            .as_ref()
+            .map(AffectedByTimeDilation::time_dilation)
+            .or_else(|| settings.map(AffectedByTimeDilation::time_dilation))
+            .unwrap_or_default();
+        let replay_ext = ext.as_ref().map(|x| x.borrow().replayed());
+        let mut data = data.with(ext);
+        if let Some(delay) = delay {
+            delay_start(&mut data, delay);
+        }
+        let replay = Replay::new(key.clone(), replay_ext, &data);
+        let Some(slot) = positional.slots.get_mut(&CName::undefined()) else {
+            // keeps track of sound until emitter gets a track back
+            let duration = replay.duration as f32;
+            let Some(slot) = positional.slots.get_virtual_mut(&CName::undefined()) else {
+                return Err(SceneError::MissingPositionalEmitter { id }.into());
+            };
+            let now = Instant::now();
+            let mut voice = VirtualVoice::new(
+                event_name,
+                replay,
+                time_dilation,
+                control_id,
+                start_position(&data),
+                Some(now + delay.unwrap_or_default()),
+            );
+            voice.dilate(slot.dilation, now);
+            slot.voices.push(voice);
+            return Ok(duration);
+        };
+        slot.play_and_store(
+            event_name,
+            time_dilation,
+            data,
+            control_id,
+            settings,
+            Some(replay),
+        )
+        .map(|(duration, _)| duration)
+        .map_err(|e| match e {
+            Either::Left(e) => Error::Engine {
+                source: EngineError::Sound { source: e },
+            },
+            Either::Right(e) => Error::Engine {
+                source: EngineError::FromFile { source: e },
+            },
+        })
+    }
+    /// Stop with given tween, or as declared in sound settings if none.
+    pub fn stop_on_emitter(&mut self, event_name: CName, id: &PositionalId, tween: Option<Tween>) {
+        if let Some(mut positional) = self.0.get_mut(id) {
+            positional
+                .slots
+                .stop_on_emitter(event_name, CName::undefined(), tween);
+        }
+    }
+    pub fn sync_emitters(&mut self, listener: &Listener) {
+        if self.0.is_empty() {
+            return;
+        }
+        let now = Instant::now();
+        self.0.retain(|id, v| {
+            if v.expires.is_some_and(|x| now >= x) {
+                lifecycle!("positional emitter lifetime elapsed [{id}]");
+                v.expires = None;
+                v.slots.marked_for_death = true;
+                v.slots.release(IMMEDIATELY);
+            }
+            if v.slots.marked_for_death && !v.slots.any_playing_handle() {
+                return false;
+            }
+            let position = v.slots.last_known_position;
+            let distance = position.distance(&listener.position);
+            v.slots
+                .slots
+                .iter_mut()
+                .for_each(|x| x.place(position, listener.position, now));
+            if v.one_shot && v.audibility(distance).is_none_or(|x| !x.audible()) {
+                lifecycle!("positional emitter out of earshot [{id}]");
+                v.slots.stop(IMMEDIATELY);
+                return false;
+            }
+            v.slots.set_emitter_doppler(listener);
+            true
+        });
+    }
+    /// Virtual positional emitters with sounds to play right away,
+    /// along with how audible they'd be.
+    pub fn candidates(
+        &mut self,
+        listener: &Listener,
+        now: Instant,
+    ) -> Vec<(PositionalId, Audibility)> {
+        let mut candidates = vec![];
+        for mut positional in self.0.iter_mut() {
+            let id = *positional.key();
+            let distance = positional
+                .slots
+                .last_known_position
+                .distance(&listener.position);
+            for slot in positional.slots.virtuals.iter_mut() {
+                slot.prune(now);
+                let audibility = slot.audibility(distance);
+                if slot.wants_track() && audibility.audible() {
+                    candidates.push((id, audibility));
+                }
+            }
+        }
+        candidates
+    }
+    /// Where given virtual positional emitter stands,
+    /// and settings to get its track back with.
+    pub fn virtual_placement(&self, id: &PositionalId) -> Option<(Vector4, SpatialTrackSettings)> {
+        self.0.get(id).and_then(|x| {
+            x.slots
+                .virtuals
+                .first()
+                .map(|slot| (x.slots.last_known_position, slot.settings.clone()))
+        })
+    }
+    /// Get track back for given virtual positional emitter, resuming its sounds.
+    pub fn realize(
+        &mut self,
+        id: &PositionalId,
+        handle: Spatial,
+        banks: &Banks,
+        now: Instant,
+    ) -> bool {
+        let Some(mut positional) = self.0.get_mut(id) else {
+            return false;
+        };
+        positional
+            .slots
+            .realize(&CName::undefined(), handle, banks, now);
+        lifecycle!("positional emitter got its track back [{id}]");
+        true
+    }
+    /// Least audible positional emitter still holding a track, if any,
+    /// preferring ones with nothing playing, along with whether it plays anything.
+    pub fn least_audible(&self) -> Option<(PositionalId, bool, Audibility)> {
+        self.0
+            .iter()
+            .filter_map(|x| {
+                let slot = x.slots.slots.first()?;
+                Some((*x.key(), slot.any_playing_handle(), slot.audibility()))
+            })
+            .min_by(|a, b| {
+                a.1.cmp(&b.1)
+                    .then(a.2.partial_cmp(&b.2).unwrap_or(Ordering::Equal))
+            })
+    }
+    /// Give up track of given positional emitter, remembering its sounds.
+    pub fn virtualize(&mut self, id: &PositionalId, now: Instant) -> bool {
+        let virtualized = self
+            .0
+            .get_mut(id)
+            .is_some_and(|mut x| x.slots.virtualize(&CName::undefined(), now));
+        if virtualized {
+            lifecycle!("positional emitter gave up its track [{id}]");
+        }
+        virtualized
+    }
+    pub fn sync_dilation(&mut self, rate: f64, tween: Tween) {
+        self.0
+            .iter_mut()
+            .for_each(|mut x| x.slots.sync_dilation(rate, tween));
+    }
+    pub fn release(&mut self, fallback: Tween) {
+        self.0
+            .iter_mut()
+            .for_each(|mut x| x.slots.release(fallback));
+    }
+    pub fn pause(&mut self, tween: Tween) {
+        self.0.iter_mut().for_each(|mut x| x.slots.pause(tween));
+    }
+    pub fn resume(&mut self, tween: Tween) {
+        self.0.iter_mut().for_each(|mut x| x.slots.resume(tween));
+    }
+    pub fn any_lingering_handle(&self) -> bool {
+        self.0.iter().any(|x| {
+            x.slots
+                .slots
+                .iter()
+                .any(|x| x.handles.any_lingering_handle())
+        })
+    }
+}
+
+impl Reclaim for Positionals {
+    fn reclaim(&mut self) {
+        self.0.iter_mut().for_each(|mut x| x.slots.reclaim());
+    }
+}
+
+impl SetControlledVolume for Positionals {
+    fn set_controlled_volume(
+        &mut self,
+        id: ControlId,
+        amplitude: audioware_core::Amplitude,
+        tween: Tween,
+    ) {
+        self.0.iter_mut().for_each(|mut x| {
+            x.slots.set_controlled_volume(id, amplitude, tween);
+        })
+    }
+}
+
+impl SetControlledPlaybackRate for Positionals {
+    fn set_controlled_playback_rate(&mut self, id: ControlId, rate: f64, tween: Tween) {
+        self.0.iter_mut().for_each(|mut x| {
+            x.slots.set_controlled_playback_rate(id, rate, tween);
+        })
+    }
+}
+
+impl SetControlledEffect for Positionals {
+    fn set_controlled_effect(&mut self, id: ControlId, parameter: EffectParameter, tween: Tween) {
+        self.0.iter_mut().for_each(|mut x| {
+            x.slots.set_controlled_effect(id, parameter, tween);
+        })
+    }
+}
+
+impl PositionControlled for Positionals {
+    fn position_controlled(&mut self, id: ControlId, sender: crossbeam::channel::Sender<f32>) {
+        self.0.iter_mut().for_each(|mut x| {
+            x.slots.position_controlled(id, sender.clone());
+        })
+    }
+}
+
+impl PlayingPosition for Positionals {
+    fn playing_position(&self, id: ControlId) -> Option<f64> {
+        self.0.iter().find_map(|x| x.slots.playing_position(id))
+    }
+}
+
+impl PlayingVolume for Positionals {
+    fn playing_volume(&self, id: ControlId) -> Option<Decibels> {
+        self.0.iter().find_map(|x| x.slots.playing_volume(id))
+    }
+}
+
+impl StopControlled for Positionals {
+    fn stop_controlled(&mut self, id: ControlId, tween: Tween) {
+        self.0.iter_mut().for_each(|mut x| {
+            x.slots.stop_controlled(id, tween);
+        })
+    }
+}
+
+impl PauseControlled for Positionals {
+    fn pause_controlled(&mut self, id: ControlId, tween: Tween) {
+        self.0.iter_mut().for_each(|mut x| {
+            x.slots.pause_controlled(id, tween);
+        })
+    }
+}
+
+impl ResumeControlled for Positionals {
+    fn resume_controlled(&mut self, id: ControlId, tween: Tween) {
+        self.0.iter_mut().for_each(|mut x| {
+            x.slots.resume_controlled(id, tween);
+        })
+    }
+}
+
+impl ResumeControlledAt for Positionals {
+    fn resume_controlled_at(&mut self, id: ControlId, delay: f64, tween: Tween) {
+        self.0.iter_mut().for_each(|mut x| {
+            x.slots.resume_controlled_at(id, delay, tween);
+        })
+    }
+}
+
+impl SeekControlledTo for Positionals {
+    fn seek_controlled_to(&mut self, id: ControlId, position: f64) {
+        self.0.iter_mut().for_each(|mut x| {
+            x.slots.seek_controlled_to(id, position);
+        })
+    }
+}
+
+impl SeekControlledBy for Positionals {
+    fn seek_controlled_by(&mut self, id: ControlId, amount: f64) {
+        self.0.iter_mut().for_each(|mut x| {
+            x.slots.seek_controlled_by(id, amount);
+        })
+    }
+}
@@ -0,0 +1,130 @@
+//! Named mixes stacked by priority.
+
+use std::collections::HashMap;
+
+use audioware_manifest::{MixTrack, Snapshot};
+use kira::{Decibels, Tween};
+use red4ext_rs::types::CName;
+
+use crate::engine::{
+    eq::Cutoffs,
+    modulators::{Modulators, Parameter},
+    tracks::Tracks,
+};
+
+/// Values resolved from active snapshots, unset when none defines them.
+#[derive(Debug, Clone, Default, PartialEq)]
+struct Mix {
+    volumes: HashMap<MixTrack, Decibels>,
+    cutoffs: Cutoffs,
+    track_cutoffs: HashMap<MixTrack, Cutoffs>,
+    reverb_mix: Option<f32>,
+}
+
+/// Snapshots currently active, along with reverb mix set by player's surroundings.
+#[derive(Debug, Default)]
+pub struct Snapshots {
+    /// In activation order.
+    active: Vec<(CName, Snapshot)>,
+    mix: Mix,
+    reverb_mix: f32,
+}
+
+impl Snapshots {
+    /// Activate snapshot, or bring it back on top of same priority ones if already active.
+    pub fn activate(
+        &mut self,
+        name: CName,
+        snapshot: Snapshot,
+        tween: Tween,
+        modulators: &mut Modulators,
+        tracks: &mut Tracks,
+    ) {
+        self.active.retain(|(x, _)| *x != name);
+        self.active.push((name, snapshot));
+        self.apply(tween, modulators, tracks);
+    }
+    /// Returns whether snapshot was active.
+    pub fn deactivate(
+        &mut self,
+        name: CName,
+        tween: Tween,
+        modulators: &mut Modulators,
+        tracks: &mut Tracks,
+    ) -> bool {
+        let count = self.active.len();
+        self.active.retain(|(x, _)| *x != name);
+        if self.active.len() == count {
+            return false;
+        }
+        self.apply(tween, modulators, tracks);
+        true
+    }
+    /// Deactivate all snapshots at once.
+    pub fn clear(&mut self, tween: Tween, modulators: &mut Modulators, tracks: &mut Tracks) {
+        self.active.clear();
+        self.apply(tween, modulators, tracks);
+    }
+    /// Reverb mix from player's surroundings, unless a snapshot overrides it.
+    pub fn set_reverb_mix(&mut self, value: f32, tween: Tween, modulators: &mut Modulators) {
+        self.reverb_mix = value;
+        if self.mix.reverb_mix.is_none() {
+            modulators.reverb_mix.update(value, tween);
+        }
+    }
+    fn apply(&mut self, tween: Tween, modulators: &mut Modulators, tracks: &mut Tracks) {
+        let mut stack = self.active.iter().map(|(_, x)| x).collect::<Vec<_>>();
+        // stable sort: latest activated still wins ties
+        stack.sort_by_key(|x| x.priority);
+        let mut mix = Mix::default();
+        for snapshot in stack {
+            for (track, volume) in snapshot.volumes.iter().flatten() {
+                mix.volumes.insert(*track, volume.as_decibels());
+            }
+            if snapshot.low_pass.is_some() {
+                mix.cutoffs.low_pass = snapshot.low_pass;
+            }
+            if snapshot.high_pass.is_some() {
+                mix.cutoffs.high_pass = snapshot.high_pass;
+            }
+            for (track, cutoffs) in snapshot.cutoffs.iter().flatten() {
+                let current = mix.track_cutoffs.entry(*track).or_default();
+                if cutoffs.low_pass.is_some() {
+                    current.low_pass = cutoffs.low_pass;
+                }
+                if cutoffs.high_pass.is_some() {
+                    current.high_pass = cutoffs.high_pass;
+                }
+            }
+            if snapshot.reverb_mix.is_some() {
+                mix.reverb_mix = snapshot.reverb_mix;
+            }
+        }
+        for track in MixTrack::ALL {
+            let volume = mix.volumes.get(&track).copied();
+            if volume != self.mix.volumes.get(&track).copied() {
+                modulators
+                    .mix
+                    .level_mut(track)
+                    .update(volume.unwrap_or(Decibels::IDENTITY), tween);
+            }
+        }
+        for track in MixTrack::ALL {
+            let cutoffs = mix.track_cutoffs.get(&track).copied();
+            if cutoffs != self.mix.track_cutoffs.get(&track).copied() {
+                tracks
+                    .equalizer(track)
+                    .set_cutoffs(cutoffs.unwrap_or_default(), tween);
+            }
+        }
+        if mix.cutoffs != self.mix.cutoffs {
+            tracks.ambience.equalizer().set_cutoffs(mix.cutoffs, tween);
+        }
+        if mix.reverb_mix != self.mix.reverb_mix {
+            modulators
+                .reverb_mix
+                .update(mix.reverb_mix.unwrap_or(self.reverb_mix), tween);
+        }
+        self.mix = mix;
+    }
+}
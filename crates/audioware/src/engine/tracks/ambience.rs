@@ -7,7 +7,7 @@ use kira::{
 
 use crate::{
     engine::{
-        eq::{EQ, HighPass, LowPass},
+        eq::{Cutoffs, EQ, HighPass, LowPass, Preset},
         modulators::{Modulators, Parameter},
     },
     error::Error,
@@ -46,6 +46,8 @@ impl Ambience {
             eq: EQ {
                 lowpass: LowPass(low),
                 highpass: HighPass(high),
+                preset: Preset::None,
+                cutoffs: Cutoffs::default(),
             },
         })
     }
@@ -0,0 +1,159 @@
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use audioware_core::AttenuationCurve;
+use audioware_manifest::{EffectHandle, Settings, track_with_effects};
+use kira::{
+    Mix, ResourceLimitReached, Tween,
+    track::{TrackBuilder, TrackHandle},
+};
+
+use crate::{
+    engine::{traits::effect::EffectParameter, tweens::ATTENUATED},
+    utils::warns,
+};
+
+use super::Spatial;
+
+/// Sub-tracks set aside for sounds insert effects,
+/// out of the same budget as emitters.
+pub const RESERVED_FOR_INSERTS: usize = 16;
+
+/// Sub-tracks currently in use for insert effects.
+static INSERTS: AtomicUsize = AtomicUsize::new(0);
+
+/// Sub-track with sound own insert effects and attenuation curve.
+pub struct InsertsHandle {
+    track: TrackHandle,
+    effects: Vec<EffectHandle>,
+    attenuation: Option<AttenuationCurve>,
+}
+
+impl InsertsHandle {
+    /// Follow sound own attenuation curve, if any,
+    /// in place of the one of the emitter it plays on.
+    pub fn attenuate(&mut self, emitter: &Spatial) {
+        if let Some(curve) = self.attenuation.as_ref() {
+            let gain = curve.gain(emitter.distance(), emitter.distances());
+            self.track.set_volume(gain, ATTENUATED);
+        }
+    }
+    /// Update parameter on every matching effect, if any,
+    /// or only on the requested one for filters.
+    pub fn set_effect(&mut self, parameter: EffectParameter, tween: Tween) {
+        let mut filters = 0;
+        for effect in self.effects.iter_mut() {
+            match (effect, parameter) {
+                (EffectHandle::Filter(x), EffectParameter::FilterCutoff(filter, value)) => {
+                    if filters == filter {
+                        x.set_cutoff(value, tween);
+                    }
+                    filters += 1;
+                }
+                (EffectHandle::Filter(x), EffectParameter::FilterResonance(filter, value)) => {
+                    if filters == filter {
+                        x.set_resonance(value, tween);
+                    }
+                    filters += 1;
+                }
+                (EffectHandle::Distortion(x), EffectParameter::DistortionDrive(value)) => {
+                    x.set_drive(value, tween);
+                }
+                (EffectHandle::Delay(x), EffectParameter::DelayFeedback(value)) => {
+                    x.set_feedback(value, tween);
+                }
+                (EffectHandle::Reverb(x), EffectParameter::ReverbMix(value)) => {
+                    x.set_mix(Mix(value), tween);
+                }
+                _ => {}
+            }
+        }
+    }
+}
+
+impl Drop for InsertsHandle {
+    fn drop(&mut self) {
+        INSERTS.fetch_sub(1, Ordering::Relaxed);
+    }
+}
+
+impl std::ops::Deref for InsertsHandle {
+    type Target = TrackHandle;
+
+    fn deref(&self) -> &Self::Target {
+        &self.track
+    }
+}
+
+impl std::ops::DerefMut for InsertsHandle {
+    fn deref_mut(&mut self) -> &mut TrackHandle {
+        &mut self.track
+    }
+}
+
+/// Track which sounds with insert effects play through.
+pub trait Inserts {
+    /// Whether sounds playing through it are spatialized.
+    const SPATIAL: bool = false;
+    /// Add sub-track, bypassing emitter attenuation when sound has its own.
+    fn add_inserts(
+        &mut self,
+        builder: TrackBuilder,
+        own_attenuation: bool,
+    ) -> Result<TrackHandle, ResourceLimitReached>;
+    /// Sub-track with insert effects or attenuation curve declared in settings, if any.
+    fn inserts(&mut self, settings: Option<&Settings>) -> Option<InsertsHandle> {
+        let settings = settings?;
+        let effects = settings.effects.as_deref().unwrap_or_default();
+        let attenuation = settings
+            .spatial
+            .as_ref()
+            .and_then(|x| x.attenuation.clone())
+            .filter(|_| Self::SPATIAL);
+        if effects.is_empty() && attenuation.is_none() {
+            return None;
+        }
+        if INSERTS
+            .fetch_update(Ordering::Relaxed, Ordering::Relaxed, |x| {
+                (x < RESERVED_FOR_INSERTS).then_some(x + 1)
+            })
+            .is_err()
+        {
+            warns!("no track left to insert effects, playing without");
+            return None;
+        }
+        let (builder, effects) = track_with_effects(effects);
+        match self.add_inserts(builder, attenuation.is_some()) {
+            Ok(track) => Some(InsertsHandle {
+                track,
+                effects,
+                attenuation,
+            }),
+            Err(e) => {
+                INSERTS.fetch_sub(1, Ordering::Relaxed);
+                warns!("unable to insert effects, playing without: {e}");
+                None
+            }
+        }
+    }
+}
+
+impl Inserts for TrackHandle {
+    fn add_inserts(
+        &mut self,
+        builder: TrackBuilder,
+        _: bool,
+    ) -> Result<TrackHandle, ResourceLimitReached> {
+        self.add_sub_track(builder)
+    }
+}
+
+impl Inserts for Spatial {
+    const SPATIAL: bool = true;
+    fn add_inserts(
+        &mut self,
+        builder: TrackBuilder,
+        own_attenuation: bool,
+    ) -> Result<TrackHandle, ResourceLimitReached> {
+        self.add_sub_track(builder, own_attenuation)
+    }
+}
@@ -1,9 +1,11 @@
 use ambience::Ambience;
+use audioware_manifest::{MixTrack, TimeDilation};
 use car_radio::CarRadio;
 use dialogue::Dialogue;
 use holocall::Holocall;
+pub use inserts::{Inserts, InsertsHandle, RESERVED_FOR_INSERTS};
 use kira::{
-    Tween,
+    Decibels, Tween,
     sound::FromFileError,
     {AudioManager, backend::Backend},
 };
@@ -18,30 +20,34 @@ use crate::{
     ControlId,
     engine::{
         AffectedByTimeDilation,
+        eq::TrackEQ,
         traits::{
             DualHandles,
             clear::Clear,
             dilation::{Comparable, SyncDilationBy},
+            effect::{EffectParameter, SetControlledEffect},
             panning::SetControlledPanning,
             pause::{Pause, PauseControlled},
             playback::SetControlledPlaybackRate,
-            position::PositionControlled,
+            position::{PlayingPosition, PositionControlled},
             reclaim::Reclaim,
+            release::ReleaseBy,
             resume::{Resume, ResumeControlled, ResumeControlledAt},
             seek::{SeekControlledBy, SeekControlledTo},
             stop::{Stop, StopBy, StopControlled},
-            volume::SetControlledVolume,
+            volume::{PlayingVolume, SetControlledVolume},
         },
     },
     error::Error,
 };
 
-use super::{DilationUpdate, modulators::Modulators, tweens::IMMEDIATELY};
+use super::{DilationUpdate, modulators::Modulators, retriggers::LastPlays, tweens::IMMEDIATELY};
 
 pub mod ambience;
 mod car_radio;
 mod dialogue;
 mod holocall;
+mod inserts;
 mod music;
 mod radioport;
 mod sfx;
@@ -51,12 +57,13 @@ mod v;
 pub struct TrackEntryOptions {
     pub entity_id: Option<EntityId>,
     pub emitter_name: Option<CName>,
-    pub affected_by_time_dilation: bool,
+    /// Pitch-preserving time dilation is only supported on emitters.
+    pub time_dilation: TimeDilation,
 }
 
 impl AffectedByTimeDilation for TrackEntryOptions {
-    fn affected_by_time_dilation(&self) -> bool {
-        self.affected_by_time_dilation
+    fn time_dilation(&self) -> TimeDilation {
+        self.time_dilation
     }
 }
 
@@ -79,6 +86,8 @@ pub struct Tracks {
     // tracks affected by reverb mix + preset (e.g. underwater)
     pub ambience: Ambience,
     pub handles: DualHandles<CName, TrackEntryOptions, FromFileError>,
+    /// Last plays of sounds declared with `cooldown` or `min_retrigger_interval`.
+    pub last_plays: LastPlays,
 }
 
 impl Tracks {
@@ -89,11 +98,12 @@ impl Tracks {
         let ambience = Ambience::try_new(manager, modulators)?;
         let v = V::try_new(manager, &ambience)?;
         let holocall = Holocall::try_new(manager, &ambience)?;
-        let sfx = Sfx::try_new(manager, &ambience)?;
-        let radioport = Radioport::try_new(manager, &ambience)?;
-        let music = Music::try_new(manager, &ambience)?;
-        let dialogue = Dialogue::try_new(manager, &ambience)?;
-        let car_radio = CarRadio::try_new(manager, &ambience)?;
+        let Modulators { ducking, mix, .. } = modulators;
+        let sfx = Sfx::try_new(manager, &ambience, &ducking.sfx, &mix.sfx)?;
+        let radioport = Radioport::try_new(manager, &ambience, &ducking.radioport, &mix.radioport)?;
+        let music = Music::try_new(manager, &ambience, &ducking.music, &mix.music)?;
+        let dialogue = Dialogue::try_new(manager, &ambience, &mix.dialogue)?;
+        let car_radio = CarRadio::try_new(manager, &ambience, &ducking.car_radio, &mix.car_radio)?;
         Ok(Self {
             ambience,
             v,
@@ -104,6 +114,7 @@ impl Tracks {
             dialogue,
             car_radio,
             handles: Default::default(),
+            last_plays: Default::default(),
         })
     }
     pub fn pause(&mut self, tween: Tween) {
@@ -114,6 +125,7 @@ impl Tracks {
     }
     pub fn reclaim(&mut self) {
         self.handles.reclaim();
+        self.last_plays.reclaim();
     }
     pub fn any_handle(&self) -> bool {
         self.handles.any_handle()
@@ -131,12 +143,33 @@ impl Tracks {
         self.handles
             .stop_by(&(event_name, entity_id, emitter_name), tween);
     }
+    pub fn release_by(
+        &mut self,
+        event_name: CName,
+        entity_id: Option<EntityId>,
+        emitter_name: Option<CName>,
+        fallback: Tween,
+    ) {
+        self.handles
+            .release_by(&(event_name, entity_id, emitter_name), fallback);
+    }
     pub fn sync_dilation(&mut self, entity_id: EntityId, update: DilationUpdate) {
         self.handles.sync_dilation_by(&entity_id, &update);
     }
+    /// Filters of given track, set by mix snapshots.
+    pub fn equalizer(&mut self, track: MixTrack) -> &mut TrackEQ {
+        match track {
+            MixTrack::Music => self.music.equalizer(),
+            MixTrack::Sfx => self.sfx.equalizer(),
+            MixTrack::Dialogue => self.dialogue.equalizer(),
+            MixTrack::Radioport => self.radioport.equalizer(),
+            MixTrack::CarRadio => self.car_radio.equalizer(),
+        }
+    }
     pub fn clear(&mut self) {
         self.stop(IMMEDIATELY);
         self.handles.clear();
+        self.last_plays.clear();
     }
 }
 
@@ -163,6 +196,24 @@ impl SetControlledPanning for Tracks {
     }
 }
 
+impl SetControlledEffect for Tracks {
+    fn set_controlled_effect(&mut self, id: ControlId, parameter: EffectParameter, tween: Tween) {
+        self.handles.set_controlled_effect(id, parameter, tween);
+    }
+}
+
+impl PlayingPosition for Tracks {
+    fn playing_position(&self, id: ControlId) -> Option<f64> {
+        self.handles.playing_position(id)
+    }
+}
+
+impl PlayingVolume for Tracks {
+    fn playing_volume(&self, id: ControlId) -> Option<Decibels> {
+        self.handles.playing_volume(id)
+    }
+}
+
 impl PositionControlled for Tracks {
     fn position_controlled(&mut self, id: ControlId, sender: crossbeam::channel::Sender<f32>) {
         self.handles.position_controlled(id, sender);
@@ -4,23 +4,37 @@ use kira::{
     track::{TrackBuilder, TrackHandle},
 };
 
-use crate::error::Error;
+use crate::{
+    engine::{
+        eq::TrackEQ,
+        modulators::{Level, Parameter},
+    },
+    error::Error,
+};
 
 use super::ambience::Ambience;
 
-pub struct Radioport(TrackHandle);
+pub struct Radioport(TrackHandle, TrackEQ);
 
 impl Radioport {
     pub fn try_new<B: Backend>(
         manager: &mut AudioManager<B>,
         ambience: &Ambience,
+        duck: &Level,
+        mix: &Level,
     ) -> Result<Self, Error> {
-        let track = manager.add_sub_track(
-            TrackBuilder::new()
-                // reverb used to require to be set otherwise sound switched to mono, what now?
-                .with_send(ambience.reverb(), Decibels::SILENCE),
-        )?;
-        Ok(Self(track))
+        let mut builder = TrackBuilder::new()
+            // reverb used to require to be set otherwise sound switched to mono, what now?
+            .with_send(ambience.reverb(), Decibels::SILENCE)
+            .with_effect(duck.try_effect()?)
+            .with_effect(mix.try_effect()?);
+        let eq = TrackEQ::add(&mut builder);
+        let track = manager.add_sub_track(builder)?;
+        Ok(Self(track, eq))
+    }
+    /// Filters set by mix snapshots.
+    pub fn equalizer(&mut self) -> &mut TrackEQ {
+        &mut self.1
     }
 }
 
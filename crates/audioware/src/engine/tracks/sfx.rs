@@ -4,24 +4,38 @@ use kira::{
     {AudioManager, backend::Backend},
 };
 
-use crate::error::Error;
+use crate::{
+    engine::{
+        eq::TrackEQ,
+        modulators::{Level, Parameter},
+    },
+    error::Error,
+};
 
 use super::ambience::Ambience;
 
-pub struct Sfx(TrackHandle);
+pub struct Sfx(TrackHandle, TrackEQ);
 
 impl Sfx {
     pub fn try_new<B: Backend>(
         manager: &mut AudioManager<B>,
         ambience: &Ambience,
+        duck: &Level,
+        mix: &Level,
     ) -> Result<Self, Error> {
-        let track = manager.add_sub_track(
-            TrackBuilder::new()
-                // sum used to have to be 1.0 otherwise sounds crackled, what now?
-                .with_send(ambience.environmental(), amplitude!(0.5).as_decibels())
-                .with_send(ambience.reverb(), amplitude!(0.5).as_decibels()),
-        )?;
-        Ok(Self(track))
+        let mut builder = TrackBuilder::new()
+            // sum used to have to be 1.0 otherwise sounds crackled, what now?
+            .with_send(ambience.environmental(), amplitude!(0.5).as_decibels())
+            .with_send(ambience.reverb(), amplitude!(0.5).as_decibels())
+            .with_effect(duck.try_effect()?)
+            .with_effect(mix.try_effect()?);
+        let eq = TrackEQ::add(&mut builder);
+        let track = manager.add_sub_track(builder)?;
+        Ok(Self(track, eq))
+    }
+    /// Filters set by mix snapshots.
+    pub fn equalizer(&mut self) -> &mut TrackEQ {
+        &mut self.1
     }
 }
 
@@ -1,21 +1,89 @@
-use audioware_core::{Amplitude, SpatialTrackSettings, amplitude};
+use std::time::Instant;
+
+use audioware_core::{
+    Amplitude, AttenuationCurve, Cone, DistanceCurve, Occluded, OcclusionModel, PitchShiftBuilder,
+    PitchShiftHandle, SpatialTrackSettings, amplitude, eased_gain, factor_to_decibels,
+};
 use kira::{
-    AudioManager, Value,
+    AudioManager, Decibels, Easing, PlaySoundError, ResourceLimitReached, Tween, Value,
     backend::Backend,
-    effect::filter::{FilterBuilder, FilterHandle},
+    effect::{
+        filter::{FilterBuilder, FilterHandle},
+        volume_control::{VolumeControlBuilder, VolumeControlHandle},
+    },
     listener::ListenerId,
-    track::{SpatialTrackBuilder, SpatialTrackHandle},
+    modulator::tweener::{TweenerBuilder, TweenerHandle},
+    sound::SoundData,
+    track::{
+        SpatialTrackBuilder, SpatialTrackDistances, SpatialTrackHandle, TrackBuilder, TrackHandle,
+    },
 };
 
-use crate::{engine::tweens::OCCLUDED, error::Error};
-
-use super::ambience::Ambience;
+use crate::{
+    engine::{
+        modulators::ATTENUATION_MAPPING,
+        tweens::{ATTENUATED, OCCLUDED},
+    },
+    error::Error,
+};
 
-pub const DEFAULT_CUTOFF: f64 = 18_000.0;
+use super::{RESERVED_FOR_INSERTS, ambience::Ambience};
 
 pub struct Spatial {
     track: SpatialTrackHandle,
-    occlusion: Option<FilterHandle>,
+    /// Sub-track sounds play on, attenuated with distance from listener.
+    ///
+    /// Sounds with their own attenuation curve play next to it instead,
+    /// so that they can be heard past emitter max distance.
+    direct: TrackHandle,
+    occlusion: Option<Occlusion>,
+    /// Sub-track compensating pitch of sounds whose time dilation preserves pitch,
+    /// only created when first needed.
+    stretch: Option<(TrackHandle, PitchShiftHandle)>,
+    /// Last synced time dilation.
+    rate: f64,
+    distances: SpatialTrackDistances,
+    attenuation: Attenuation,
+    /// Last synced distance from listener.
+    distance: f32,
+    directional: Option<Directional>,
+    doppler_factor: Option<f32>,
+    /// Last synced Doppler shift.
+    doppler: f64,
+    air_absorption: Option<(DistanceCurve, FilterHandle)>,
+    reverb_send: Option<(DistanceCurve, TweenerHandle)>,
+}
+
+/// Low-pass and optional gain following occlusion between emitter and listener.
+struct Occlusion {
+    model: OcclusionModel,
+    state: Occluded,
+    volume: Option<VolumeControlHandle>,
+    filter: FilterHandle,
+}
+
+/// Gain and optional low-pass of directional emitter.
+struct Directional {
+    cone: Cone,
+    volume: VolumeControlHandle,
+    filter: Option<FilterHandle>,
+}
+
+/// How volume falls off with distance from listener.
+enum Attenuation {
+    /// Eased between min and max distances.
+    Eased(Easing),
+    /// Custom curve.
+    Curve(AttenuationCurve),
+}
+
+impl Attenuation {
+    fn gain(&self, distance: f32, distances: SpatialTrackDistances) -> Decibels {
+        match self {
+            Self::Eased(easing) => eased_gain(*easing, distance, distances),
+            Self::Curve(curve) => curve.gain(distance, distances),
+        }
+    }
 }
 
 impl Spatial {
@@ -23,47 +91,232 @@ impl Spatial {
         manager: &mut AudioManager<B>,
         listener: impl Into<ListenerId>,
         position: impl Into<Value<mint::Vector3<f32>>>,
+        distance: f32,
         settings: SpatialTrackSettings,
         ambience: &Ambience,
     ) -> Result<Self, Error> {
+        // leave room for sounds insert effects
+        if manager.num_sub_tracks() + RESERVED_FOR_INSERTS >= manager.sub_track_capacity() {
+            return Err(ResourceLimitReached.into());
+        }
         let SpatialTrackSettings {
             distances,
             persist_until_sounds_finish,
             attenuation_function,
+            attenuation_curve,
             spatialization_strength,
             affected_by_reverb_mix,
             affected_by_environmental_preset,
             enable_occlusion,
+            occlusion_model,
+            cone,
+            doppler_factor,
+            air_absorption,
+            reverb_send,
+            priority: _,
         } = settings;
         let mut builder = SpatialTrackBuilder::new()
             .distances(distances)
             .spatialization_strength(spatialization_strength)
-            .persist_until_sounds_finish(persist_until_sounds_finish)
-            // None: disable volume attenuation based on distance
-            .attenuation_function(attenuation_function.unwrap_or(kira::Easing::Linear));
+            .persist_until_sounds_finish(persist_until_sounds_finish);
+        // None: disable built-in volume attenuation based on distance,
+        // which would also apply to sounds with their own attenuation curve
+        builder = builder.attenuation_function(None);
+        let attenuation = match attenuation_curve {
+            Some(curve) => Attenuation::Curve(curve),
+            None => Attenuation::Eased(attenuation_function.unwrap_or(Easing::Linear)),
+        };
+        let mut distant = None;
         // sum used to have to be 1.0 otherwise sounds crackled, what now?
         if affected_by_reverb_mix {
-            builder = builder.with_send(ambience.reverb(), amplitude!(0.5).as_decibels());
+            match reverb_send {
+                Some(curve) => {
+                    let tweener = manager.add_modulator(TweenerBuilder {
+                        initial_value: send(&curve, f32::MAX).0 as f64,
+                    })?;
+                    builder = builder.with_send(
+                        ambience.reverb(),
+                        Value::from_modulator(&tweener, ATTENUATION_MAPPING),
+                    );
+                    distant = Some((curve, tweener));
+                }
+                None => {
+                    builder = builder.with_send(ambience.reverb(), amplitude!(0.5).as_decibels());
+                }
+            }
         }
         if affected_by_environmental_preset {
             builder = builder.with_send(ambience.environmental(), amplitude!(0.5).as_decibels());
         }
         let mut occlusion = None;
         if enable_occlusion {
-            occlusion = Some(builder.add_effect(FilterBuilder::new().cutoff(DEFAULT_CUTOFF)));
+            let state = Occluded::default();
+            occlusion = Some(Occlusion {
+                volume: (occlusion_model.volume.as_factor() < 1.).then(|| {
+                    builder.add_effect(VolumeControlBuilder::new(state.gain(&occlusion_model)))
+                }),
+                filter: builder
+                    .add_effect(FilterBuilder::new().cutoff(state.cutoff(&occlusion_model))),
+                model: occlusion_model,
+                state,
+            });
         }
-        let track = manager.add_spatial_sub_track(listener, position, builder)?;
-        Ok(Self { track, occlusion })
+        let air_absorption = air_absorption.map(|curve| {
+            let cutoff = curve.at(f32::MAX) as f64;
+            let filter = builder.add_effect(FilterBuilder::new().cutoff(cutoff));
+            (curve, filter)
+        });
+        // listener position is unknown yet: start from the back of the cone
+        let directional = cone.map(|cone| Directional {
+            cone,
+            volume: builder.add_effect(VolumeControlBuilder::new(cone.gain(180.))),
+            filter: cone
+                .cutoff(180.)
+                .map(|cutoff| builder.add_effect(FilterBuilder::new().cutoff(cutoff))),
+        });
+        let mut track = manager.add_spatial_sub_track(listener, position, builder)?;
+        let direct = track
+            .add_sub_track(TrackBuilder::new().volume(attenuation.gain(distance, distances)))?;
+        Ok(Self {
+            track,
+            direct,
+            occlusion,
+            stretch: None,
+            rate: 1.,
+            distances,
+            attenuation,
+            distance,
+            directional,
+            doppler_factor,
+            doppler: 1.,
+            air_absorption,
+            reverb_send: distant,
+        })
     }
-    pub fn set_occlusion(&mut self, factor: f32) {
-        let normalized = (DEFAULT_CUTOFF * (1. - factor as f64)).clamp(600.0, DEFAULT_CUTOFF);
-        if let Some(x) = self.occlusion.as_mut() {
-            x.set_cutoff(normalized, OCCLUDED);
+    /// Follow occlusion measured at given instant, smoothed over time.
+    pub fn occlude(&mut self, occlusion: f32, now: Instant) {
+        if let Some(Occlusion {
+            model,
+            state,
+            volume,
+            filter,
+        }) = self.occlusion.as_mut()
+        {
+            state.update(occlusion, now, model);
+            if let Some(volume) = volume.as_mut() {
+                volume.set_volume(state.gain(model), OCCLUDED);
+            }
+            filter.set_cutoff(state.cutoff(model), OCCLUDED);
         }
     }
     pub fn occluded(&self) -> bool {
         self.occlusion.is_some()
     }
+    /// Last synced time dilation.
+    pub fn rate(&self) -> f64 {
+        self.rate
+    }
+    /// Last synced distance from listener.
+    pub fn distance(&self) -> f32 {
+        self.distance
+    }
+    pub fn distances(&self) -> SpatialTrackDistances {
+        self.distances
+    }
+    /// Current attenuation gain, at last synced distance.
+    pub fn gain(&self) -> Decibels {
+        self.attenuation.gain(self.distance, self.distances)
+    }
+    /// Follow attenuation, and air absorption and reverb send if any.
+    pub fn attenuate(&mut self, distance: f32) {
+        self.distance = distance;
+        self.direct.set_volume(self.gain(), ATTENUATED);
+        if let Some((curve, filter)) = self.air_absorption.as_mut() {
+            filter.set_cutoff(curve.at(distance) as f64, ATTENUATED);
+        }
+        if let Some((curve, tweener)) = self.reverb_send.as_mut() {
+            tweener.set(send(curve, distance).0 as f64, ATTENUATED);
+        }
+    }
+    pub fn directional(&self) -> bool {
+        self.directional.is_some()
+    }
+    /// Follow cone, if any, given angle (in degrees) between emitter forward and listener.
+    pub fn orient(&mut self, angle: f32) {
+        if let Some(Directional {
+            cone,
+            volume,
+            filter,
+        }) = self.directional.as_mut()
+        {
+            volume.set_volume(cone.gain(angle), ATTENUATED);
+            if let Some((filter, cutoff)) = filter.as_mut().zip(cone.cutoff(angle)) {
+                filter.set_cutoff(cutoff, ATTENUATED);
+            }
+        }
+    }
+    /// Doppler shift intensity, if enabled.
+    pub fn doppler_factor(&self) -> Option<f32> {
+        self.doppler_factor
+    }
+    /// Last synced Doppler shift.
+    pub fn doppler(&self) -> f64 {
+        self.doppler
+    }
+    pub fn set_doppler(&mut self, doppler: f64) {
+        self.doppler = doppler;
+    }
+    /// Play sound attenuated with distance from listener.
+    pub fn play<D: SoundData>(&mut self, data: D) -> Result<D::Handle, PlaySoundError<D::Error>> {
+        self.direct.play(data)
+    }
+    /// Sub-track attenuated with distance from listener,
+    /// unless sound follows its own attenuation curve.
+    pub fn add_sub_track(
+        &mut self,
+        builder: TrackBuilder,
+        own_attenuation: bool,
+    ) -> Result<TrackHandle, ResourceLimitReached> {
+        match own_attenuation {
+            true => self.track.add_sub_track(builder),
+            false => self.direct.add_sub_track(builder),
+        }
+    }
+    /// Sub-track for sounds whose time dilation preserves pitch.
+    pub fn stretch(&mut self) -> Result<&mut TrackHandle, ResourceLimitReached> {
+        let stretch = match self.stretch.take() {
+            Some(x) => x,
+            None => {
+                let mut builder = TrackBuilder::new();
+                let pitch = builder.add_effect(PitchShiftBuilder {
+                    ratio: compensate(self.rate),
+                });
+                (self.direct.add_sub_track(builder)?, pitch)
+            }
+        };
+        Ok(&mut self.stretch.insert(stretch).0)
+    }
+    /// Shift pitch back in the opposite direction of time dilation.
+    pub fn sync_pitch(&mut self, rate: f64, tween: Tween) {
+        self.rate = rate;
+        if let Some((_, pitch)) = self.stretch.as_mut() {
+            pitch.set_ratio(compensate(rate), tween);
+        }
+    }
+}
+
+/// Reverb send level at given distance.
+fn send(curve: &DistanceCurve, distance: f32) -> Decibels {
+    Decibels(
+        factor_to_decibels(curve.at(distance))
+            .0
+            .min(Decibels::IDENTITY.0),
+    )
+}
+
+/// Pitch ratio which cancels out time dilation, within reasonable bounds.
+fn compensate(rate: f64) -> f64 {
+    (1. / rate).clamp(0.25, 4.)
 }
 
 impl std::ops::Deref for Spatial {
@@ -1,21 +1,28 @@
-use audioware_core::Amplitude;
+use audioware_core::{Amplitude, shifted_playback_rate};
 use kira::{
-    Panning, PlaybackRate, Tween,
+    Decibels, Panning, PlaybackRate, Tween,
     sound::{PlaybackState, static_sound::StaticSoundHandle, streaming::StreamingSoundHandle},
 };
 
 use crate::{
     ControlId,
-    engine::{traits::stop::Stop, tweens::IMMEDIATELY},
+    engine::{
+        tracks::InsertsHandle,
+        traits::{release::ReleasePolicy, stop::Stop},
+        tweens::IMMEDIATELY,
+    },
 };
 
 pub mod clear;
 pub mod dilation;
+pub mod doppler;
+pub mod effect;
 pub mod panning;
 pub mod pause;
 pub mod playback;
 pub mod position;
 pub mod reclaim;
+pub mod release;
 pub mod resume;
 pub mod seek;
 pub mod stop;
@@ -54,24 +61,66 @@ pub struct Handle<K, V, O> {
     handle: RawHandle<K, V>,
     options: O,
     control_id: Option<ControlId>,
+    release: ReleasePolicy,
+    /// Sub-track with sound own insert effects, kept alive as long as sound.
+    inserts: Option<InsertsHandle>,
+    /// Playback rate sound was played with, or last set from scripts.
+    rate: f64,
+    /// Last synced time dilation and Doppler shift, on top of rate.
+    shift: f64,
+    /// Volume sound was played with, or last set from scripts.
+    volume: Decibels,
+}
+
+impl<K, O, E> DualHandles<K, O, E> {
+    /// Sub-tracks of sounds with their own inserts, if any.
+    pub fn inserts_mut(&mut self) -> impl Iterator<Item = &mut InsertsHandle> {
+        self.statics
+            .0
+            .iter_mut()
+            .filter_map(|x| x.inserts.as_mut())
+            .chain(self.streams.0.iter_mut().filter_map(|x| x.inserts.as_mut()))
+    }
 }
 
 impl<K, V, O> Handle<K, V, O> {
-    pub fn new(key: K, value: V, options: O, control_id: Option<ControlId>) -> Self {
+    pub fn new(
+        key: K,
+        value: V,
+        options: O,
+        control_id: Option<ControlId>,
+        release: ReleasePolicy,
+        rate: f64,
+        volume: Decibels,
+    ) -> Self {
         Self {
             handle: RawHandle::new(key, value),
             options,
             control_id,
+            release,
+            inserts: None,
+            rate,
+            shift: 1.,
+            volume,
         }
     }
+    pub fn with_inserts(mut self, inserts: Option<InsertsHandle>) -> Self {
+        self.inserts = inserts;
+        self
+    }
 }
 
 impl<K, O> Handle<K, StaticSoundHandle, O> {
     pub fn set_volume(&mut self, value: Amplitude, tween: Tween) {
-        self.handle.value.set_volume(value.as_decibels(), tween);
+        self.volume = value.as_decibels();
+        self.handle.value.set_volume(self.volume, tween);
     }
+    /// Set own playback rate, keeping time dilation and Doppler shift on top.
     pub fn set_playback_rate(&mut self, value: PlaybackRate, tween: Tween) {
-        self.handle.value.set_playback_rate(value, tween);
+        self.rate = value.0;
+        self.handle
+            .value
+            .set_playback_rate(shifted_playback_rate(self.rate, self.shift, 1.), tween);
     }
     pub fn set_panning(&mut self, value: Panning, tween: Tween) {
         self.handle.value.set_panning(value, tween);
@@ -80,10 +129,15 @@ impl<K, O> Handle<K, StaticSoundHandle, O> {
 
 impl<K, O, E> Handle<K, StreamingSoundHandle<E>, O> {
     pub fn set_volume(&mut self, value: Amplitude, tween: Tween) {
-        self.handle.value.set_volume(value.as_decibels(), tween);
+        self.volume = value.as_decibels();
+        self.handle.value.set_volume(self.volume, tween);
     }
+    /// Set own playback rate, keeping time dilation and Doppler shift on top.
     pub fn set_playback_rate(&mut self, value: PlaybackRate, tween: Tween) {
-        self.handle.value.set_playback_rate(value, tween);
+        self.rate = value.0;
+        self.handle
+            .value
+            .set_playback_rate(shifted_playback_rate(self.rate, self.shift, 1.), tween);
     }
     pub fn set_panning(&mut self, value: Panning, tween: Tween) {
         self.handle.value.set_panning(value, tween);
@@ -116,6 +170,57 @@ impl<K, O, E> DualHandles<K, O, E> {
     }
 }
 
+/// Sound not stopped yet, see [DualHandles::ongoing].
+pub struct Ongoing<'a, K, O> {
+    pub key: &'a K,
+    pub options: &'a O,
+    pub control_id: Option<ControlId>,
+    /// In seconds.
+    pub position: f64,
+    pub paused: bool,
+    /// Own playback rate, without time dilation or Doppler shift.
+    pub rate: f64,
+    /// Volume sound was played with, or last set from scripts.
+    pub volume: Decibels,
+}
+
+impl<K, V, O> Handle<K, V, O> {
+    fn ongoing(&self, position: f64, state: PlaybackState) -> Option<Ongoing<'_, K, O>> {
+        let paused = match state {
+            PlaybackState::Playing | PlaybackState::WaitingToResume | PlaybackState::Resuming => {
+                false
+            }
+            PlaybackState::Pausing | PlaybackState::Paused => true,
+            PlaybackState::Stopping | PlaybackState::Stopped => return None,
+        };
+        Some(Ongoing {
+            key: &self.handle.key,
+            options: &self.options,
+            control_id: self.control_id,
+            position,
+            paused,
+            rate: self.rate,
+            volume: self.volume,
+        })
+    }
+}
+
+impl<K, O, E> DualHandles<K, O, E> {
+    /// Sounds not stopped yet.
+    pub fn ongoing(&self) -> impl Iterator<Item = Ongoing<'_, K, O>> {
+        self.statics
+            .0
+            .iter()
+            .filter_map(|x| x.ongoing(x.handle.value.position(), x.handle.value.state()))
+            .chain(
+                self.streams
+                    .0
+                    .iter()
+                    .filter_map(|x| x.ongoing(x.handle.value.position(), x.handle.value.state())),
+            )
+    }
+}
+
 impl<K, O, E> Drop for DualHandles<K, O, E> {
     fn drop(&mut self) {
         // bug in kira DecodeScheduler NextStep::Wait
@@ -1,5 +1,6 @@
 use std::hash::Hash;
 
+use audioware_core::shifted_playback_rate;
 use dashmap::mapref::multiple::RefMutMulti;
 use kira::Tween;
 
@@ -28,7 +29,10 @@ where
     #[inline]
     fn sync_dilation(&mut self, rate: f64, tween: Tween) {
         if self.options.affected_by_time_dilation() {
-            self.handle.value.set_playback_rate(rate, tween);
+            self.shift = rate;
+            self.handle
+                .value
+                .set_playback_rate(shifted_playback_rate(self.rate, rate, 1.), tween);
         }
     }
 }
@@ -42,9 +46,11 @@ where
     #[inline]
     fn sync_dilation_by(&mut self, key: &F, update: &DilationUpdate) {
         if self.options.compare(key) && self.options.affected_by_time_dilation() {
-            self.handle
-                .value
-                .set_playback_rate(update.dilation(), update.tween_curve());
+            self.shift = update.dilation();
+            self.handle.value.set_playback_rate(
+                shifted_playback_rate(self.rate, self.shift, 1.),
+                update.tween_curve(),
+            );
         }
     }
 }
@@ -0,0 +1,55 @@
+use audioware_core::shifted_playback_rate;
+use kira::Tween;
+
+use crate::engine::{
+    AffectedByTimeDilation,
+    traits::{DualHandles, Handle, Handles, playback::SetPlaybackRate},
+};
+
+/// Shift playback rate with Doppler effect, on top of time dilation.
+pub trait SyncDoppler {
+    fn sync_doppler(&mut self, dilation: f64, doppler: f64, tween: Tween);
+}
+
+impl<K, V, O> SyncDoppler for Handle<K, V, O>
+where
+    V: SetPlaybackRate,
+    O: AffectedByTimeDilation,
+{
+    #[inline]
+    fn sync_doppler(&mut self, dilation: f64, doppler: f64, tween: Tween) {
+        let dilation = if self.options.affected_by_time_dilation() {
+            dilation
+        } else {
+            1.
+        };
+        self.shift = dilation * doppler;
+        self.handle
+            .value
+            .set_playback_rate(shifted_playback_rate(self.rate, dilation, doppler), tween);
+    }
+}
+
+impl<K, V, O> SyncDoppler for Handles<K, V, O>
+where
+    V: SetPlaybackRate,
+    O: AffectedByTimeDilation,
+{
+    #[inline]
+    fn sync_doppler(&mut self, dilation: f64, doppler: f64, tween: Tween) {
+        self.0
+            .iter_mut()
+            .for_each(|x| x.sync_doppler(dilation, doppler, tween));
+    }
+}
+
+impl<K, O, E> SyncDoppler for DualHandles<K, O, E>
+where
+    O: AffectedByTimeDilation,
+{
+    #[inline]
+    fn sync_doppler(&mut self, dilation: f64, doppler: f64, tween: Tween) {
+        self.statics.sync_doppler(dilation, doppler, tween);
+        self.streams.sync_doppler(dilation, doppler, tween);
+    }
+}
@@ -0,0 +1,39 @@
+use kira::{Decibels, Tween};
+
+use crate::{
+    ControlId,
+    engine::traits::{DualHandles, Handles},
+};
+
+/// Runtime parameter of sound own insert effects.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum EffectParameter {
+    /// Index among filters, and cutoff.
+    FilterCutoff(usize, f64),
+    /// Index among filters, and resonance.
+    FilterResonance(usize, f64),
+    DistortionDrive(Decibels),
+    DelayFeedback(Decibels),
+    ReverbMix(f32),
+}
+
+pub trait SetControlledEffect {
+    fn set_controlled_effect(&mut self, id: ControlId, parameter: EffectParameter, tween: Tween);
+}
+
+impl<K, O, E> SetControlledEffect for DualHandles<K, O, E> {
+    fn set_controlled_effect(&mut self, id: ControlId, parameter: EffectParameter, tween: Tween) {
+        self.statics.set_controlled_effect(id, parameter, tween);
+        self.streams.set_controlled_effect(id, parameter, tween);
+    }
+}
+
+impl<K, V, O> SetControlledEffect for Handles<K, V, O> {
+    fn set_controlled_effect(&mut self, id: ControlId, parameter: EffectParameter, tween: Tween) {
+        self.0
+            .iter_mut()
+            .filter(|x| x.control_id.map(|x| x == id).unwrap_or(false))
+            .filter_map(|x| x.inserts.as_mut())
+            .for_each(|x| x.set_effect(parameter, tween));
+    }
+}
@@ -1,5 +1,7 @@
 use crossbeam::channel::Sender;
-use kira::sound::{static_sound::StaticSoundHandle, streaming::StreamingSoundHandle};
+use kira::sound::{
+    PlaybackState, static_sound::StaticSoundHandle, streaming::StreamingSoundHandle,
+};
 
 use crate::{
     ControlId,
@@ -11,6 +13,19 @@ pub trait PositionControlled {
     fn position_controlled(&mut self, id: ControlId, sender: Sender<f32>);
 }
 
+/// Position (in seconds) of a controlled sound, as long as it hasn't stopped.
+pub trait PlayingPosition {
+    fn playing_position(&self, id: ControlId) -> Option<f64>;
+}
+
+impl<K, O, E> PlayingPosition for DualHandles<K, O, E> {
+    fn playing_position(&self, id: ControlId) -> Option<f64> {
+        self.statics
+            .playing_position(id)
+            .or_else(|| self.streams.playing_position(id))
+    }
+}
+
 impl<K, O, E> PositionControlled for DualHandles<K, O, E> {
     fn position_controlled(&mut self, id: ControlId, sender: Sender<f32>) {
         if let Some(x) = self
@@ -41,3 +56,21 @@ impl<K, O, E> Handles<K, StreamingSoundHandle<E>, O> {
             .map(|x| x.handle.value.position() as f32)
     }
 }
+
+impl<K, O> Handles<K, StaticSoundHandle, O> {
+    fn playing_position(&self, id: ControlId) -> Option<f64> {
+        self.0
+            .iter()
+            .find(|x| x.control_id == Some(id) && x.handle.value.state() != PlaybackState::Stopped)
+            .map(|x| x.handle.value.position())
+    }
+}
+
+impl<K, O, E> Handles<K, StreamingSoundHandle<E>, O> {
+    fn playing_position(&self, id: ControlId) -> Option<f64> {
+        self.0
+            .iter()
+            .find(|x| x.control_id == Some(id) && x.handle.value.state() != PlaybackState::Stopped)
+            .map(|x| x.handle.value.position())
+    }
+}
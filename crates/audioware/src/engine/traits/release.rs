@@ -0,0 +1,190 @@
+use std::time::Duration;
+
+use audioware_core::base_playback_rate;
+use audioware_manifest::{OnStop, Settings};
+use kira::{
+    PlaybackRate, StartTime, Tween, Value,
+    sound::{PlaybackState, static_sound::StaticSoundHandle, streaming::StreamingSoundHandle},
+};
+use red4ext_rs::types::{CName, EntityId};
+
+use crate::engine::{
+    tracks::TrackEntryOptions,
+    traits::{DualHandles, Handle, Handles},
+    tweens::IMMEDIATELY,
+};
+
+/// Stop without explicit tween, as declared in sound settings.
+pub trait Release {
+    /// `fallback` is used whenever settings do not declare any tween.
+    fn release(&mut self, fallback: Tween);
+}
+
+pub trait ReleaseBy<K> {
+    fn release_by(&mut self, key: &K, fallback: Tween);
+}
+
+/// How a sound stops when released, see [OnStop].
+#[derive(Debug, Clone, Copy)]
+pub enum ReleasePolicy {
+    /// Stop with declared fade-out tween, if any.
+    Fade(Option<Tween>),
+    /// Stop looping, then stop when current loop iteration ends.
+    FinishLoopIteration {
+        /// End of loop region (in seconds), unless end of audio.
+        loop_ends: Option<f64>,
+        playback_rate: f64,
+    },
+    /// Stop looping, then play until the end.
+    PlayToEnd,
+}
+
+impl Default for ReleasePolicy {
+    fn default() -> Self {
+        Self::Fade(None)
+    }
+}
+
+impl ReleasePolicy {
+    pub fn new(settings: Option<&Settings>, playback_rate: Value<PlaybackRate>) -> Self {
+        let Some(settings) = settings else {
+            return Self::default();
+        };
+        // audio is already stretched on load
+        let speed = settings.time_stretch.unwrap_or(1.);
+        match settings.on_stop.unwrap_or_default() {
+            OnStop::Fade => Self::Fade(settings.fade_out_tween.clone().map(Tween::from)),
+            OnStop::FinishLoopIteration => Self::FinishLoopIteration {
+                loop_ends: settings
+                    .r#loop
+                    .unwrap_or(false)
+                    .then(|| settings.region.as_ref().and_then(|x| x.ends))
+                    .flatten()
+                    .map(|x| x.as_secs_f64() / speed),
+                playback_rate: base_playback_rate(playback_rate),
+            },
+            OnStop::PlayToEnd => Self::PlayToEnd,
+        }
+    }
+    /// Whether sound keeps on playing for a while once released.
+    pub fn lingers(&self) -> bool {
+        !matches!(self, Self::Fade(None))
+    }
+}
+
+macro_rules! impl_release {
+    ($handle:expr, $policy:expr, $fallback:expr) => {{
+        match $policy {
+            ReleasePolicy::Fade(tween) => $handle.stop(tween.unwrap_or($fallback)),
+            ReleasePolicy::FinishLoopIteration {
+                loop_ends,
+                playback_rate,
+            } => {
+                $handle.set_loop_region(None);
+                // otherwise sound naturally stops at the end of audio
+                if let Some(loop_ends) = loop_ends {
+                    let remaining = (loop_ends - $handle.position()).max(0.) / playback_rate;
+                    $handle.stop(Tween {
+                        start_time: StartTime::Delayed(Duration::from_secs_f64(remaining)),
+                        ..IMMEDIATELY
+                    });
+                }
+            }
+            ReleasePolicy::PlayToEnd => $handle.set_loop_region(None),
+        }
+    }};
+}
+
+impl<K, O> Release for Handle<K, StaticSoundHandle, O> {
+    fn release(&mut self, fallback: Tween) {
+        impl_release!(self.handle.value, self.release, fallback)
+    }
+}
+
+impl<K, O, E> Release for Handle<K, StreamingSoundHandle<E>, O> {
+    fn release(&mut self, fallback: Tween) {
+        impl_release!(self.handle.value, self.release, fallback)
+    }
+}
+
+impl<K, V, O> Release for Handles<K, V, O>
+where
+    Handle<K, V, O>: Release,
+{
+    #[inline]
+    fn release(&mut self, fallback: Tween) {
+        self.0.iter_mut().for_each(|x| x.release(fallback));
+    }
+}
+
+impl<K, V, O> ReleaseBy<K> for Handles<K, V, O>
+where
+    K: PartialEq,
+    Handle<K, V, O>: Release,
+{
+    #[inline]
+    fn release_by(&mut self, key: &K, fallback: Tween) {
+        self.0
+            .iter_mut()
+            .filter(|x| x.handle.key == *key)
+            .for_each(|x| x.release(fallback));
+    }
+}
+
+impl<V> ReleaseBy<(CName, Option<EntityId>, Option<CName>)> for Handles<CName, V, TrackEntryOptions>
+where
+    Handle<CName, V, TrackEntryOptions>: Release,
+{
+    #[inline]
+    fn release_by(&mut self, key: &(CName, Option<EntityId>, Option<CName>), fallback: Tween) {
+        self.0
+            .iter_mut()
+            .filter(|x| {
+                x.handle.key == key.0
+                    && x.options.entity_id == key.1
+                    && x.options.emitter_name == key.2
+            })
+            .for_each(|x| x.release(fallback));
+    }
+}
+
+impl<K, O, E> Release for DualHandles<K, O, E> {
+    #[inline]
+    fn release(&mut self, fallback: Tween) {
+        self.statics.release(fallback);
+        self.streams.release(fallback);
+    }
+}
+
+impl<O, E> ReleaseBy<CName> for DualHandles<CName, O, E> {
+    #[inline]
+    fn release_by(&mut self, key: &CName, fallback: Tween) {
+        self.statics.release_by(key, fallback);
+        self.streams.release_by(key, fallback);
+    }
+}
+
+impl<E> ReleaseBy<(CName, Option<EntityId>, Option<CName>)>
+    for DualHandles<CName, TrackEntryOptions, E>
+{
+    #[inline]
+    fn release_by(&mut self, key: &(CName, Option<EntityId>, Option<CName>), fallback: Tween) {
+        self.statics.release_by(key, fallback);
+        self.streams.release_by(key, fallback);
+    }
+}
+
+impl<K, O, E> DualHandles<K, O, E> {
+    /// Whether any sound still plays for a while once released.
+    pub fn any_lingering_handle(&self) -> bool {
+        self.statics
+            .0
+            .iter()
+            .any(|x| x.release.lingers() && x.handle.value.state() != PlaybackState::Stopped)
+            || self
+                .streams
+                .0
+                .iter()
+                .any(|x| x.release.lingers() && x.handle.value.state() != PlaybackState::Stopped)
+    }
+}
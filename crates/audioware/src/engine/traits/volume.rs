@@ -1,6 +1,6 @@
 use audioware_core::Amplitude;
 use kira::{
-    Tween,
+    Decibels, Tween,
     sound::{static_sound::StaticSoundHandle, streaming::StreamingSoundHandle},
 };
 
@@ -13,6 +13,28 @@ pub trait SetControlledVolume {
     fn set_controlled_volume(&mut self, id: ControlId, amplitude: Amplitude, tween: Tween);
 }
 
+/// Volume of a controlled sound, as played or last set from scripts, attenuation aside.
+pub trait PlayingVolume {
+    fn playing_volume(&self, id: ControlId) -> Option<Decibels>;
+}
+
+impl<K, O, E> PlayingVolume for DualHandles<K, O, E> {
+    fn playing_volume(&self, id: ControlId) -> Option<Decibels> {
+        self.statics
+            .playing_volume(id)
+            .or_else(|| self.streams.playing_volume(id))
+    }
+}
+
+impl<K, V, O> PlayingVolume for Handles<K, V, O> {
+    fn playing_volume(&self, id: ControlId) -> Option<Decibels> {
+        self.0
+            .iter()
+            .find(|x| x.control_id == Some(id))
+            .map(|x| x.volume)
+    }
+}
+
 impl<K, O, E> SetControlledVolume for DualHandles<K, O, E> {
     fn set_controlled_volume(&mut self, id: ControlId, amplitude: Amplitude, tween: Tween) {
         self.statics.set_controlled_volume(id, amplitude, tween);
@@ -19,12 +19,36 @@ pub const OCCLUDED: Tween = Tween {
     easing: Easing::Linear,
 };
 
+pub const ATTENUATED: Tween = Tween {
+    start_time: StartTime::Immediate,
+    duration: Duration::from_millis(10),
+    easing: Easing::Linear,
+};
+
+pub const SHIFTED: Tween = Tween {
+    start_time: StartTime::Immediate,
+    duration: Duration::from_millis(15),
+    easing: Easing::Linear,
+};
+
 pub const LAST_BREATH: Tween = Tween {
     start_time: StartTime::Immediate,
     duration: Duration::from_millis(2300),
     easing: Easing::InPowf(0.6),
 };
 
+pub const STOLEN: Tween = Tween {
+    start_time: StartTime::Immediate,
+    duration: Duration::from_millis(120),
+    easing: Easing::Linear,
+};
+
+pub const BLENDED: Tween = Tween {
+    start_time: StartTime::Immediate,
+    duration: Duration::from_millis(500),
+    easing: Easing::Linear,
+};
+
 pub const DEFAULT: Tween = Tween {
     start_time: StartTime::Immediate,
     duration: Duration::from_millis(10),
@@ -7,6 +7,8 @@ use kira::{PlaySoundError, ResourceLimitReached, sound::FromFileError};
 use red4ext_rs::types::{CName, EntityId};
 use snafu::Snafu;
 
+use crate::PositionalId;
+
 #[derive(Debug, Snafu)]
 #[snafu(visibility(pub))]
 pub enum Error {
@@ -67,6 +69,8 @@ pub enum SceneError {
     MissingEmitter { entity_id: EntityId },
     #[snafu(display("actor is null [{}]", entity_id))]
     MissingActor { entity_id: EntityId },
+    #[snafu(display("positional emitter is missing [{}]", id))]
+    MissingPositionalEmitter { id: PositionalId },
 }
 
 #[derive(Debug, Snafu)]
@@ -10,6 +10,8 @@ mod easing;
 pub use easing::*;
 mod event;
 pub use event::*;
+mod positional;
+pub use positional::*;
 mod settings;
 pub use settings::*;
 mod subtitles;
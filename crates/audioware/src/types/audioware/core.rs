@@ -115,6 +115,7 @@ pub enum EventHookType {
     AddContainerStreamingPrefetch = 11,
     RemoveContainerStreamingPrefetch = 12,
     SetGlobalParameter = 13,
+    Marker = 14,
 }
 
 unsafe impl NativeRepr for EventHookType {
@@ -9,11 +9,12 @@ use red4ext_rs::{
 use crate::{
     AudParam, AudSwitch, ESoundCurveType, EventName, TFlag, Vector4, WwiseId,
     abi::callback::{
-        FireAddContainerStreamingPrefetchCallback, FirePlayCallback, FirePlayExternalCallback,
-        FirePlayOneShotCallback, FireRemoveContainerStreamingPrefetchCallback,
-        FireSetAppearanceNameCallback, FireSetEntityNameCallback, FireSetGlobalParameterCallback,
-        FireSetParameterCallback, FireSetSwitchCallback, FireStopCallback, FireStopTaggedCallback,
-        FireTagCallback, FireUntagCallback,
+        FireAddContainerStreamingPrefetchCallback, FireMarkerCallback, FirePlayCallback,
+        FirePlayExternalCallback, FirePlayOneShotCallback,
+        FireRemoveContainerStreamingPrefetchCallback, FireSetAppearanceNameCallback,
+        FireSetEntityNameCallback, FireSetGlobalParameterCallback, FireSetParameterCallback,
+        FireSetSwitchCallback, FireStopCallback, FireStopTaggedCallback, FireTagCallback,
+        FireUntagCallback,
     },
 };
 
@@ -748,3 +749,47 @@ impl AsRef<IScriptable> for RemoveContainerStreamingPrefetchEvent {
         &self.base.base
     }
 }
+
+#[derive(Debug, Clone, Default)]
+#[repr(C)]
+pub struct MarkerEvent {
+    base: EngineSoundEvent,
+    event_name: Cell<EventName>,
+    marker_name: Cell<CName>,
+    entity_id: Cell<EntityId>,
+    emitter_name: Cell<CName>,
+    time: Cell<f32>,
+}
+
+impl MarkerEvent {
+    getter!(*cell event_name -> CName);
+    getter!(cell marker_name -> CName);
+    getter!(cell entity_id -> EntityId);
+    getter!(cell emitter_name -> CName);
+    getter!(cell time -> f32);
+
+    setter!(cell set_event_name event_name -> CName => FireMarkerCallback);
+    setter!(cell set_marker_name marker_name -> CName => FireMarkerCallback);
+    setter!(cell set_entity_id entity_id -> EntityId => FireMarkerCallback);
+    setter!(cell set_emitter_name emitter_name -> CName => FireMarkerCallback);
+    setter!(cell set_time time -> f32 => FireMarkerCallback);
+
+    pub fn hydrate(&mut self, other: &FireMarkerCallback) {
+        self.set_event_name(other);
+        self.set_marker_name(other);
+        self.set_entity_id(other);
+        self.set_emitter_name(other);
+        self.set_time(other);
+    }
+}
+
+unsafe impl ScriptClass for MarkerEvent {
+    type Kind = Native;
+    const NAME: &'static str = "Audioware.MarkerEvent";
+}
+
+impl AsRef<IScriptable> for MarkerEvent {
+    fn as_ref(&self) -> &IScriptable {
+        &self.base.base
+    }
+}
@@ -0,0 +1,32 @@
+use std::{
+    num::NonZero,
+    sync::atomic::{AtomicU64, Ordering},
+};
+
+static COUNTER: AtomicU64 = AtomicU64::new(1);
+
+/// Identifies emitter at a world position, without entity.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct PositionalId(NonZero<u64>);
+
+impl PositionalId {
+    pub(crate) fn next() -> Self {
+        Self(NonZero::new(COUNTER.fetch_add(1, Ordering::Relaxed)).expect("counter starts at 1"))
+    }
+    /// `0` is never attributed, as returned to scripts on failure.
+    pub fn try_new(value: u64) -> Option<Self> {
+        NonZero::new(value).map(Self)
+    }
+}
+
+impl From<PositionalId> for u64 {
+    fn from(value: PositionalId) -> Self {
+        value.0.get()
+    }
+}
+
+impl std::fmt::Display for PositionalId {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "posid:{}", self.0)
+    }
+}
@@ -3,7 +3,13 @@
 use core::fmt;
 use std::{hash::Hash, ops::Not};
 
-use red4ext_rs::{ScriptClass, class_kind::Scripted, types::Ref};
+use red4ext_rs::{
+    NativeRepr, ScriptClass,
+    class_kind::Scripted,
+    types::{CName, RedArray, Ref},
+};
+
+use crate::Vector4;
 
 use super::{ElasticTween, LinearTween, ToEasing, Tween};
 
@@ -17,6 +23,22 @@ pub struct EmitterSettings {
     pub affected_by_reverb_mix: bool,
     pub affected_by_environmental_preset: bool,
     pub enable_occlusion: bool,
+    /// Only applies when `enable_occlusion` is set.
+    pub occlusion: Ref<EmitterOcclusion>,
+    pub attenuation: Ref<EmitterAttenuation>,
+    pub cone: Ref<EmitterCone>,
+    /// Doppler shift intensity, disabled when `0.0`.
+    pub doppler_factor: f32,
+    /// Low-pass cutoff (in Hz) by distance.
+    pub air_absorption: Ref<EmitterCurve>,
+    /// Reverb send (as amplitude factor) by distance.
+    pub reverb_send: Ref<EmitterCurve>,
+    /// Placed component sounds come from, entity origin if `None`.
+    pub slot: CName,
+    /// Local offset from slot (or entity origin).
+    pub offset: Vector4,
+    /// Higher keeps its track first, when emitters exceed capacity.
+    pub priority: i32,
 }
 
 impl Default for EmitterSettings {
@@ -29,6 +51,15 @@ impl Default for EmitterSettings {
             affected_by_reverb_mix: true,
             affected_by_environmental_preset: false,
             enable_occlusion: false,
+            occlusion: Default::default(),
+            attenuation: Default::default(),
+            cone: Default::default(),
+            doppler_factor: 0.,
+            air_absorption: Default::default(),
+            reverb_send: Default::default(),
+            slot: Default::default(),
+            offset: Default::default(),
+            priority: 0,
         }
     }
 }
@@ -70,6 +101,17 @@ impl Hash for EmitterSettings {
 
         self.enable_spatialization.hash(state);
         self.persist_until_sounds_finish.hash(state);
+        unsafe { self.occlusion.fields() }.hash(state);
+        unsafe { self.attenuation.fields() }.hash(state);
+        unsafe { self.cone.fields() }.hash(state);
+        ((self.doppler_factor * 100.).clamp(0., u64::MAX as f32) as u64).hash(state);
+        unsafe { self.air_absorption.fields() }.hash(state);
+        unsafe { self.reverb_send.fields() }.hash(state);
+        self.slot.hash(state);
+        [self.offset.x, self.offset.y, self.offset.z]
+            .map(f32::to_bits)
+            .hash(state);
+        self.priority.hash(state);
     }
 }
 
@@ -116,6 +158,15 @@ impl Clone for EmitterSettings {
             affected_by_reverb_mix: self.affected_by_reverb_mix,
             affected_by_environmental_preset: self.affected_by_environmental_preset,
             enable_occlusion: self.enable_occlusion,
+            occlusion: self.occlusion.clone(),
+            attenuation: self.attenuation.clone(),
+            cone: self.cone.clone(),
+            doppler_factor: self.doppler_factor,
+            air_absorption: self.air_absorption.clone(),
+            reverb_send: self.reverb_send.clone(),
+            slot: self.slot,
+            offset: self.offset,
+            priority: self.priority,
         }
     }
 }
@@ -135,6 +186,15 @@ impl fmt::Debug for EmitterSettings {
                 "affected_by_environmental_preset",
                 &self.affected_by_environmental_preset,
             )
+            .field("occlusion", &self.occlusion.is_null())
+            .field("attenuation", &self.attenuation.is_null())
+            .field("cone", &self.cone.is_null())
+            .field("doppler_factor", &self.doppler_factor)
+            .field("air_absorption", &self.air_absorption.is_null())
+            .field("reverb_send", &self.reverb_send.is_null())
+            .field("slot", &self.slot)
+            .field("offset", &self.offset)
+            .field("priority", &self.priority)
             .finish()
     }
 }
@@ -158,3 +218,124 @@ impl Hash for EmitterDistances {
         ((self.max_distance * 100.).clamp(0., u64::MAX as f32) as u64).hash(state);
     }
 }
+
+/// Interop type for [AttenuationCurve](audioware_core::AttenuationCurve).
+#[derive(Default, Clone)]
+#[repr(C)]
+pub struct EmitterAttenuation {
+    pub preset: AttenuationPreset,
+    /// Distances from listener, in meters, sorted in increasing order.
+    pub distances: RedArray<f32>,
+    /// Gains as amplitude factors, one for each distance.
+    pub gains: RedArray<f32>,
+}
+
+unsafe impl ScriptClass for EmitterAttenuation {
+    type Kind = Scripted;
+    const NAME: &'static str = "Audioware.EmitterAttenuation";
+}
+
+impl Hash for EmitterAttenuation {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        self.preset.hash(state);
+        for distance in self.distances.iter() {
+            ((distance * 100.).clamp(0., u64::MAX as f32) as u64).hash(state);
+        }
+        for gain in self.gains.iter() {
+            ((gain * 100.).clamp(0., u64::MAX as f32) as u64).hash(state);
+        }
+    }
+}
+
+/// Interop type for [AttenuationPreset](audioware_core::AttenuationPreset).
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[repr(i64)]
+pub enum AttenuationPreset {
+    /// Use distances and gains instead.
+    #[default]
+    Custom = 0,
+    Logarithmic = 1,
+    InverseSquare = 2,
+}
+unsafe impl NativeRepr for AttenuationPreset {
+    const NAME: &'static str = "Audioware.AttenuationPreset";
+}
+
+/// Interop type for [Cone](audioware_core::Cone).
+#[derive(Debug, Default, Clone, PartialEq)]
+#[repr(C)]
+pub struct EmitterCone {
+    /// Full aperture, in degrees.
+    pub inner_angle: f32,
+    /// Full aperture, in degrees.
+    pub outer_angle: f32,
+    /// Amplitude factor.
+    pub outer_gain: f32,
+    /// Low-pass cutoff in Hz, none when `0.0`.
+    pub outer_cutoff: f32,
+}
+
+unsafe impl ScriptClass for EmitterCone {
+    type Kind = Scripted;
+    const NAME: &'static str = "Audioware.EmitterCone";
+}
+
+impl Hash for EmitterCone {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        ((self.inner_angle * 100.).clamp(0., u64::MAX as f32) as u64).hash(state);
+        ((self.outer_angle * 100.).clamp(0., u64::MAX as f32) as u64).hash(state);
+        ((self.outer_gain * 100.).clamp(0., u64::MAX as f32) as u64).hash(state);
+        ((self.outer_cutoff * 100.).clamp(0., u64::MAX as f32) as u64).hash(state);
+    }
+}
+
+/// Interop type for [OcclusionModel](audioware_core::OcclusionModel).
+#[derive(Debug, Default, Clone, PartialEq)]
+#[repr(C)]
+pub struct EmitterOcclusion {
+    /// Amplitude factor, once fully occluded.
+    pub volume: f32,
+    /// Low-pass cutoff in Hz, once fully occluded.
+    pub cutoff: f32,
+    /// Time constant, in seconds.
+    pub smoothing: f32,
+}
+
+unsafe impl ScriptClass for EmitterOcclusion {
+    type Kind = Scripted;
+    const NAME: &'static str = "Audioware.EmitterOcclusion";
+}
+
+impl Hash for EmitterOcclusion {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        ((self.volume * 100.).clamp(0., u64::MAX as f32) as u64).hash(state);
+        ((self.cutoff * 100.).clamp(0., u64::MAX as f32) as u64).hash(state);
+        ((self.smoothing * 100.).clamp(0., u64::MAX as f32) as u64).hash(state);
+    }
+}
+
+/// Interop type for [DistanceCurve](audioware_core::DistanceCurve).
+#[derive(Default, Clone)]
+#[repr(C)]
+pub struct EmitterCurve {
+    /// Distances from listener, in meters, sorted in increasing order.
+    pub distances: RedArray<f32>,
+    /// One for each distance.
+    pub values: RedArray<f32>,
+}
+
+unsafe impl ScriptClass for EmitterCurve {
+    type Kind = Scripted;
+    const NAME: &'static str = "Audioware.EmitterCurve";
+}
+
+impl Hash for EmitterCurve {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        for distance in self.distances.iter() {
+            ((distance * 100.).clamp(0., u64::MAX as f32) as u64).hash(state);
+        }
+        for value in self.values.iter() {
+            ((value * 100.).clamp(0., u64::MAX as f32) as u64).hash(state);
+        }
+    }
+}
@@ -93,7 +93,7 @@ impl AsRef<IScriptable> for IPlacedComponent {
 impl AsIPlacedComponent for Ref<IPlacedComponent> {
     fn get_local_orientation(&self) -> Quaternion {
         let rtti = RttiSystem::get();
-        let cls = rtti.get_class(CName::new(Entity::NAME)).unwrap();
+        let cls = rtti.get_class(CName::new(IPlacedComponent::NAME)).unwrap();
         let method: &Method = cls
             .get_method(CName::new("GetLocalOrientation"))
             .ok()
@@ -109,7 +109,7 @@ impl AsIPlacedComponent for Ref<IPlacedComponent> {
 
     fn get_local_position(&self) -> Vector4 {
         let rtti = RttiSystem::get();
-        let cls = rtti.get_class(CName::new(Entity::NAME)).unwrap();
+        let cls = rtti.get_class(CName::new(IPlacedComponent::NAME)).unwrap();
         let method: &Method = cls.get_method(CName::new("GetLocalPosition")).ok().unwrap();
         match unsafe { self.instance() } {
             Some(x) => method
@@ -86,6 +86,7 @@ pub trait AsEntity {
     fn get_world_forward(&self) -> Vector4;
     fn get_world_orientation(&self) -> Quaternion;
     fn get_world_transform(&self) -> WorldTransform;
+    fn get_slot_transform(&self, slot: CName) -> WorldTransform;
     fn is_in_workspot(&self) -> bool;
 }
 
@@ -154,6 +155,22 @@ impl AsEntity for Ref<Entity> {
         }
     }
 
+    /// `public func Audioware.SlotTransform(entity: ref<Entity>, slot: CName) -> WorldTransform`
+    fn get_slot_transform(&self, slot: CName) -> WorldTransform {
+        let rtti = RttiSystem::get();
+        let methods = rtti.get_global_functions();
+        let method = methods
+            .iter()
+            .find(|x| x.name() == CName::new("Audioware.SlotTransform;EntityCName"))
+            .unwrap();
+        match unsafe { self.instance() } {
+            Some(x) if x.status == EntityStatus::Attached => method
+                .execute::<_, WorldTransform>(None, (self.clone(), slot))
+                .unwrap(),
+            _ => WorldTransform::default(),
+        }
+    }
+
     fn is_in_workspot(&self) -> bool {
         self.clone()
             .cast::<GameObject>()
@@ -483,6 +483,7 @@ bitflags! {
         const ADD_CONTAINER_STREAMING_PREFETCH = 1 << 11;
         const REMOVE_CONTAINER_STREAMING_PREFETCH = 1 << 12;
         const SET_GLOBAL_PARAMETER = 1 << 13;
+        const MARKER = 1 << 14;
     }
 }
 
@@ -540,7 +541,7 @@ impl From<EventActionType> for EventHookTypes {
 
 impl From<EventHookType> for EventHookTypes {
     /// # Satefy
-    /// here we're sure that [EventHookType] variants from 0 to 13 always holds.
+    /// here we're sure that [EventHookType] variants from 0 to 14 always holds.
     fn from(value: EventHookType) -> Self {
         Self::from_bits_retain(1 << value as u32)
     }
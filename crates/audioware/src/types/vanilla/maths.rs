@@ -28,6 +28,19 @@ impl Add for Vector4 {
     }
 }
 
+impl Vector4 {
+    /// Euclidean distance, regardless of `w`.
+    pub fn distance(&self, rhs: &Self) -> f32 {
+        ((self.x - rhs.x).powi(2) + (self.y - rhs.y).powi(2) + (self.z - rhs.z).powi(2)).sqrt()
+    }
+}
+
+impl From<Vector4> for [f32; 3] {
+    fn from(value: Vector4) -> Self {
+        [value.x, value.y, value.z]
+    }
+}
+
 impl From<Vector4> for mint::Vector3<f32> {
     fn from(value: Vector4) -> Self {
         Self {
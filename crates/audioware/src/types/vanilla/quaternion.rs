@@ -26,6 +26,12 @@ impl From<glam::Quat> for Quaternion {
     }
 }
 
+impl From<Quaternion> for glam::Quat {
+    fn from(value: Quaternion) -> Self {
+        Self::from_xyzw(value.i, value.j, value.k, value.r)
+    }
+}
+
 impl From<mint::Quaternion<f32>> for Quaternion {
     fn from(value: mint::Quaternion<f32>) -> Self {
         Self {
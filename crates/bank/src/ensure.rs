@@ -7,14 +7,17 @@ use std::{
     path::{Path, PathBuf},
 };
 
-use audioware_core::With;
+use audioware_core::{Amplitude, Loudness, Probed, SampleRate, With, health::AudioIssue};
 use audioware_manifest::*;
 use either::Either;
-use kira::sound::{FromFileError, static_sound::StaticSoundData, streaming::StreamingSoundData};
+use kira::{
+    Decibels,
+    sound::{FromFileError, static_sound::StaticSoundData, streaming::StreamingSoundData},
+};
 use red4ext_rs::types::{CName, CNamePool, Cruid};
-use snafu::ensure;
+use snafu::{OptionExt, ensure};
 
-use crate::SceneKey;
+use crate::{SceneKey, Warning, check_audio_health, cue_key, health::region_frames, scene_cue_key};
 
 use super::{
     BothKey, Error, GenderKey, Id, Key, LocaleKey, SceneBothKey, SceneId, SceneLocaleKey,
@@ -215,6 +218,10 @@ pub fn ensure_located_in_depot(
     Ok(())
 }
 
+/// Headers of streaming audio, probed once on registration.
+pub(crate) static PROBED: std::sync::LazyLock<std::sync::Mutex<HashMap<PathBuf, Probed>>> =
+    std::sync::LazyLock::new(Default::default);
+
 /// Ensure path refers to valid audio (based on [usage](Usage)).
 pub fn ensure_valid_audio_and_settings(
     path: &impl AsRef<std::path::Path>,
@@ -223,7 +230,21 @@ pub fn ensure_valid_audio_and_settings(
     settings: Option<&Settings>,
 ) -> Result<Either<StaticSoundData, StreamingSoundData<FromFileError>>, Error> {
     let data = ensure_valid_audio_data(path, m, usage)?;
-    ensure_valid_contextual_audio_settings(&data, settings, path.as_ref())?;
+    let sample_rate = match data {
+        Either::Left(ref data) => Some(data.sample_rate),
+        Either::Right(_) => {
+            let path = m.as_ref().join(path);
+            let probed = Probed::from_file(&path);
+            if let Some(probed) = probed {
+                PROBED
+                    .lock()
+                    .expect("probed headers lock")
+                    .insert(path, probed);
+            }
+            probed.map(|x| x.sample_rate())
+        }
+    };
+    ensure_valid_contextual_audio_settings(&data, settings, path.as_ref(), sample_rate)?;
     Ok(data)
 }
 
@@ -241,6 +262,84 @@ pub fn ensure_valid_audio_with_settings_and_captions(
     Ok(data)
 }
 
+pub fn ensure_valid_audio_with_settings_and_cues(
+    path: &impl AsRef<std::path::Path>,
+    m: &Mod,
+    usage: Usage,
+    settings: Option<&Settings>,
+    cues: Option<&[Cue]>,
+) -> Result<Either<StaticSoundData, StreamingSoundData<FromFileError>>, Error> {
+    let data = ensure_valid_audio_and_settings(path, m, usage, settings)?;
+    let region = settings.and_then(|x| x.region.as_ref());
+    if let Some(Err(why)) = cues.map(|x| Cue::validate_within(x, &data, region)) {
+        return Err(Error::from(validation::Error::InvalidAudioSettings {
+            which: path.as_ref().display().to_string(),
+            why,
+        }));
+    }
+    Ok(data)
+}
+
+/// Ensure loudness normalization, if any, is folded into [Settings] volume,
+/// falling back on manifest [Normalization] for its section.
+///
+/// Only audio decoded on load can be measured: streaming audio is left as-is,
+/// just like audio whose loudness cannot be measured, with a [Warning].
+pub fn ensure_normalized(
+    audio: &Either<StaticSoundData, StreamingSoundData<FromFileError>>,
+    settings: Option<Settings>,
+    source: &Source,
+    normalization: &Normalization,
+    path: &impl AsRef<std::path::Path>,
+    warnings: &mut Vec<Warning>,
+) -> Option<Settings> {
+    let explicit = settings.as_ref().and_then(|x| x.normalize);
+    let Some(target) = normalization.target(explicit, source) else {
+        return settings;
+    };
+    let warn = |issue| Warning {
+        path: path.as_ref().display().to_string(),
+        issue,
+    };
+    let data = match audio {
+        Either::Left(x) => x,
+        Either::Right(_) => {
+            // only worth mentioning when not inherited from section
+            if explicit.is_some() {
+                warnings.push(warn(AudioIssue::StreamingUnnormalized));
+            }
+            return settings;
+        }
+    };
+    // only measure what will actually be heard
+    let frames = region_frames(data, settings.as_ref().and_then(|x| x.region.as_ref()));
+    let Some(loudness) = Loudness::measure(frames, data.sample_rate) else {
+        warnings.push(warn(AudioIssue::Unmeasurable));
+        return settings;
+    };
+    let gain = loudness.gain_to(target, Normalize::CEILING);
+    let mut settings = settings.unwrap_or_default();
+    settings.volume = Some(
+        settings
+            .volume
+            .unwrap_or(Ranged::Fixed(Amplitude::from(Decibels::IDENTITY)))
+            .map(|x| Amplitude::from(Decibels(x.as_decibels().0 + gain.0))),
+    );
+    Some(settings)
+}
+
+/// Ensure subtitles file is located inside [Mod] depot and contains valid [Cue]s.
+pub fn ensure_valid_cues(subtitles: &TimedSubtitles, m: &Mod) -> Result<Vec<Cue>, Error> {
+    use snafu::ResultExt;
+    ensure_located_in_depot(&subtitles.file, m)?;
+    let cues = cues_from_file(&m.as_ref().join(&subtitles.file), subtitles.line).context(
+        InvalidSubtitlesSnafu {
+            path: subtitles.file.display().to_string(),
+        },
+    )?;
+    Ok(cues)
+}
+
 pub fn ensure_valid_audio_data(
     path: &impl AsRef<std::path::Path>,
     m: &Mod,
@@ -265,13 +364,23 @@ pub fn ensure_valid_audio_data(
 }
 
 /// Ensure given settings are valid for audio.
+///
+/// Streaming audio `sample_rate` is only known if probed beforehand.
 pub fn ensure_valid_contextual_audio_settings(
     audio: &Either<StaticSoundData, StreamingSoundData<FromFileError>>,
     settings: Option<&Settings>,
     path: &Path,
+    sample_rate: Option<u32>,
 ) -> Result<(), Error> {
     let mut errors = settings.validate().err().unwrap_or_default();
-    errors.extend(settings.validate_for(audio).err().unwrap_or_default());
+    if let Some(settings) = settings {
+        errors.extend(
+            settings
+                .validate_for_probed(audio, sample_rate)
+                .err()
+                .unwrap_or_default(),
+        );
+    }
     ensure!(
         errors.is_empty(),
         InvalidAudioSettingsSnafu {
@@ -375,6 +484,152 @@ pub fn ensure_store_subtitle<T: PartialEq + Eq + Hash + Clone + Into<Key>>(
     Ok(())
 }
 
+/// Ensure [Translation] overlays an existing [Voice] of the same kind,
+/// then store its subtitles.
+pub fn ensure_translation(
+    k: &str,
+    v: Translation,
+    set: &HashSet<Id>,
+    simple_subs: &mut HashMap<LocaleKey, DialogLine>,
+    complex_subs: &mut HashMap<BothKey, DialogLine>,
+) -> Result<(), Error> {
+    let cname = CName::new(k);
+    let voice = set
+        .iter()
+        .find(|x| *x.source() == Source::Voices && AsRef::<CName>::as_ref(*x).as_str() == k)
+        .context(InvalidTranslationSnafu {
+            cname: k,
+            why: "no matching voice",
+        })?;
+    let v: AnyTranslation = v.into();
+    match (v, AsRef::<Key>::as_ref(voice)) {
+        (Either::Left(subs), Key::Locale(_)) => {
+            for (locale, line) in subs {
+                ensure_store_subtitle::<LocaleKey>(LocaleKey(cname, locale), line, simple_subs)?;
+            }
+        }
+        (Either::Right(subs), Key::Both(_)) => {
+            for (locale, genders) in subs {
+                for (gender, line) in genders {
+                    ensure_store_subtitle::<BothKey>(
+                        BothKey(cname, locale, gender),
+                        line,
+                        complex_subs,
+                    )?;
+                }
+            }
+        }
+        (v, _) => InvalidTranslationSnafu {
+            cname: k,
+            why: if v.is_left() {
+                "voice requires gender"
+            } else {
+                "voice does not support gender"
+            },
+        }
+        .fail()?,
+    }
+    Ok(())
+}
+
+/// Ensure [Snapshot] is valid and its name unique, then store it.
+pub fn ensure_snapshot(
+    k: &str,
+    v: Snapshot,
+    map: &mut HashMap<CName, Snapshot>,
+) -> Result<(), Error> {
+    let c_string = std::ffi::CString::new(k)?;
+    let cname = CName::new(k);
+    ensure!(
+        !map.contains_key(&cname),
+        NonUniqueKeySnafu {
+            cname: k.to_string()
+        }
+    );
+    if let Err(why) = v.validate() {
+        return Err(Error::from(validation::Error::InvalidSnapshot {
+            name: k.to_string(),
+            why,
+        }));
+    }
+    if cname.as_str() != k {
+        CNamePool::add_cstr(&c_string);
+    }
+    map.insert(cname, v);
+    Ok(())
+}
+
+/// Ensure [TrackDucking] is valid and its track not already configured, then store it.
+pub fn ensure_track_ducking(
+    k: DuckSource,
+    v: TrackDucking,
+    map: &mut HashMap<DuckSource, TrackDucking>,
+) -> Result<(), Error> {
+    ensure!(
+        !map.contains_key(&k),
+        NonUniqueKeySnafu {
+            cname: format!("{k:?}")
+        }
+    );
+    if let Err(why) = v.validate() {
+        return Err(Error::from(validation::Error::InvalidDucking {
+            track: format!("{k:?}"),
+            why,
+        }));
+    }
+    map.insert(k, v);
+    Ok(())
+}
+
+/// Ensure manifest [Normalization], if any, is valid.
+pub fn ensure_normalization(
+    normalization: Option<Normalization>,
+    manifest: &Path,
+) -> Result<Normalization, Error> {
+    let normalization = normalization.unwrap_or_default();
+    if let Err(why) = normalization.validate() {
+        return Err(Error::from(validation::Error::InvalidNormalization {
+            manifest: manifest.display().to_string(),
+            why,
+        }));
+    }
+    Ok(normalization)
+}
+
+/// Ensure [Cue]s are properly stored.
+pub fn ensure_store_cues<T: PartialEq + Eq + Hash + Clone + Into<Key>>(
+    key: T,
+    value: Vec<Cue>,
+    store: &mut HashMap<T, Vec<Cue>>,
+) -> Result<(), Error> {
+    ensure!(
+        store.insert(key.clone(), value).is_none(),
+        CannotStoreCuesSnafu { key }
+    );
+    Ok(())
+}
+
+/// Ensure scene [Cue]s are properly stored.
+pub fn ensure_store_scene_cues<T: PartialEq + Eq + Hash + Clone + Into<SceneKey>>(
+    key: T,
+    value: Vec<Cue>,
+    store: &mut HashMap<T, Vec<Cue>>,
+) -> Result<(), Error> {
+    ensure!(
+        store.insert(key.clone(), value).is_none(),
+        CannotStoreSceneCuesSnafu { key }
+    );
+    Ok(())
+}
+
+/// Ensure [Cue]s localization keys are added to [game pool](CNamePool).
+pub fn ensure_cues_in_pool(len: usize, key: impl Fn(usize) -> String) -> Result<(), Error> {
+    for nth in 0..len {
+        CNamePool::add_cstr(&std::ffi::CString::new(key(nth))?);
+    }
+    Ok(())
+}
+
 /// Ensure settings are properly stored.
 pub fn ensure_store_settings<T: PartialEq + Eq + Hash + Clone>(
     key: &T,
@@ -402,6 +657,16 @@ pub fn ensure_store_scene_id(id: SceneId, store: &mut HashSet<SceneId>) -> Resul
     Ok(())
 }
 
+/// Ensure time-stretched audio is only stretched once on load,
+/// rather than on every play, by keeping it in memory.
+#[inline]
+fn ensure_stretched_once(usage: Usage, settings: Option<&Settings>) -> Usage {
+    match usage {
+        Usage::OnDemand if settings.is_some_and(|x| x.time_stretch.is_some()) => Usage::InMemory,
+        x => x,
+    }
+}
+
 /// Ensure guarantees are upheld.
 #[allow(clippy::too_many_arguments)]
 fn ensure<'a, K: PartialEq + Eq + Hash + Clone + Into<Key> + Conflictual>(
@@ -415,11 +680,15 @@ fn ensure<'a, K: PartialEq + Eq + Hash + Clone + Into<Key> + Conflictual>(
     map: &'a mut HashMap<K, StaticSoundData>,
     smap: &'a mut HashMap<K, Settings>,
     source: Source,
-) -> Result<(), Error>
+    normalization: &Normalization,
+    cues: Option<&[Cue]>,
+    warnings: &'a mut Vec<Warning>,
+) -> Result<Id, Error>
 where
     HashSet<Id>: Conflict<K>,
 {
-    let data = ensure_valid_audio_and_settings(&path, m, usage, settings.as_ref())?
+    let usage = ensure_stretched_once(usage, settings.as_ref());
+    let data = ensure_valid_audio_with_settings_and_cues(&path, m, usage, settings.as_ref(), cues)?
         .map_either_with(
             (usage, settings.as_ref().and_then(|x| x.region.clone())),
             |ctx, data| {
@@ -431,6 +700,14 @@ where
             },
             |_, data| data,
         );
+    check_audio_health(
+        &data,
+        settings.as_ref().and_then(|x| x.region.as_ref()),
+        &source,
+        &path,
+        warnings,
+    );
+    let settings = ensure_normalized(&data, settings, &source, normalization, &path, warnings);
     ensure_key_no_conflict(&key, k, set)?;
     let id: Id = match usage {
         Usage::InMemory => Id::InMemory(key.clone().into(), source),
@@ -444,12 +721,55 @@ where
         ),
     };
     if usage == Usage::InMemory {
-        ensure_store_data(key, data.left().unwrap(), settings, &path, map)?;
-    } else if let Some(settings) = settings {
+        // in-memory sound data embed settings, except those applied on every play
+        ensure_store_data(
+            key.clone(),
+            data.left().unwrap(),
+            settings.clone(),
+            &path,
+            map,
+        )?;
+    }
+    if let Some(settings) = settings {
         ensure_store_settings(&key, settings, smap)?;
     }
-    ensure_store_id(id, set)?;
-    Ok(())
+    ensure_store_id(id.clone(), set)?;
+    Ok(id)
+}
+
+/// Ensure audio shared by both genders is only decoded once,
+/// storing whatever was stored for `key` under `alias` too.
+#[allow(clippy::too_many_arguments)]
+fn ensure_alias<'a, K: PartialEq + Eq + Hash + Clone + Into<Key> + Conflictual>(
+    k: &'a str,
+    key: &K,
+    alias: K,
+    id: Id,
+    path: &Path,
+    set: &'a mut HashSet<Id>,
+    map: &'a mut HashMap<K, StaticSoundData>,
+    smap: &'a mut HashMap<K, Settings>,
+) -> Result<(), Error>
+where
+    HashSet<Id>: Conflict<K>,
+{
+    ensure_key_no_conflict(&alias, k, set)?;
+    if let Some(data) = map.get(key).cloned() {
+        ensure_store_data(alias.clone(), data, None, &path, map)?;
+    }
+    if let Some(settings) = smap.get(key).cloned() {
+        ensure_store_settings(&alias, settings, smap)?;
+    }
+    let id = match id {
+        Id::InMemory(_, source) => Id::InMemory(alias.into(), source),
+        Id::OnDemand(crate::Usage::Static(_, path), source) => {
+            Id::OnDemand(crate::Usage::Static(alias.into(), path), source)
+        }
+        Id::OnDemand(crate::Usage::Streaming(_, path), source) => {
+            Id::OnDemand(crate::Usage::Streaming(alias.into(), path), source)
+        }
+    };
+    ensure_store_id(id, set)
 }
 
 /// Ensure guarantees are upheld.
@@ -463,12 +783,16 @@ fn ensure_scene<'a, K: PartialEq + Eq + Hash + Clone + Into<SceneKey> + Conflict
     set: &'a mut HashSet<SceneId>,
     map: &'a mut HashMap<K, StaticSoundData>,
     smap: &'a mut HashMap<K, Settings>,
-) -> Result<(), Error>
+    normalization: &Normalization,
+    cues: Option<&[Cue]>,
+    warnings: &'a mut Vec<Warning>,
+) -> Result<SceneId, Error>
 where
     HashSet<SceneId>: Conflict<K>,
     SceneKey: From<K>,
 {
-    let data = ensure_valid_audio_and_settings(&path, m, usage, settings.as_ref())?
+    let usage = ensure_stretched_once(usage, settings.as_ref());
+    let data = ensure_valid_audio_with_settings_and_cues(&path, m, usage, settings.as_ref(), cues)?
         .map_either_with(
             (usage, settings.as_ref().and_then(|x| x.region.clone())),
             |ctx, data| {
@@ -480,6 +804,22 @@ where
             },
             |_, data| data,
         );
+    // scene dialogs are normalized just like voices
+    check_audio_health(
+        &data,
+        settings.as_ref().and_then(|x| x.region.as_ref()),
+        &Source::Voices,
+        &path,
+        warnings,
+    );
+    let settings = ensure_normalized(
+        &data,
+        settings,
+        &Source::Voices,
+        normalization,
+        &path,
+        warnings,
+    );
     let id: SceneId = match usage {
         Usage::InMemory => SceneId::InMemory(key.clone().into()),
         Usage::OnDemand => SceneId::OnDemand(crate::Usage::Static(
@@ -492,22 +832,61 @@ where
         )),
     };
     if usage == Usage::InMemory {
-        ensure_store_scene_data(key, data.left().unwrap(), settings, &path, map)?;
-    } else if let Some(settings) = settings {
+        ensure_store_scene_data(
+            key.clone(),
+            data.left().unwrap(),
+            settings.clone(),
+            &path,
+            map,
+        )?;
+    }
+    if let Some(settings) = settings {
         ensure_store_settings(&key, settings, smap)?;
     }
-    ensure_store_scene_id(id, set)?;
-    Ok(())
+    ensure_store_scene_id(id.clone(), set)?;
+    Ok(id)
+}
+
+/// Ensure scene dialog shared by both genders is only decoded once,
+/// storing whatever was stored for `key` under `alias` too.
+fn ensure_scene_alias<'a, K: PartialEq + Eq + Hash + Clone + Into<SceneKey>>(
+    key: &K,
+    alias: K,
+    id: SceneId,
+    path: &Path,
+    set: &'a mut HashSet<SceneId>,
+    map: &'a mut HashMap<K, StaticSoundData>,
+    smap: &'a mut HashMap<K, Settings>,
+) -> Result<(), Error> {
+    if let Some(data) = map.get(key).cloned() {
+        ensure_store_scene_data(alias.clone(), data, None, &path, map)?;
+    }
+    if let Some(settings) = smap.get(key).cloned() {
+        ensure_store_settings(&alias, settings, smap)?;
+    }
+    let id = match id {
+        SceneId::InMemory(_) => SceneId::InMemory(alias.into()),
+        SceneId::OnDemand(crate::Usage::Static(_, path)) => {
+            SceneId::OnDemand(crate::Usage::Static(alias.into(), path))
+        }
+        SceneId::OnDemand(crate::Usage::Streaming(_, path)) => {
+            SceneId::OnDemand(crate::Usage::Streaming(alias.into(), path))
+        }
+    };
+    ensure_store_scene_id(id, set)
 }
 
 /// Ensure [Sfx] guarantees are upheld.
+#[allow(clippy::too_many_arguments)]
 pub fn ensure_sfx<'a>(
     k: &'a str,
     v: Sfx,
     m: &Mod,
+    normalization: &Normalization,
     set: &'a mut HashSet<Id>,
     map: &'a mut HashMap<UniqueKey, StaticSoundData>,
     smap: &'a mut HashMap<UniqueKey, Settings>,
+    warnings: &'a mut Vec<Warning>,
 ) -> Result<(), Error> {
     let existed = ensure_key_unique_or_inserted(k)?;
     let UsableAudio {
@@ -528,6 +907,9 @@ pub fn ensure_sfx<'a>(
         map,
         smap,
         Source::Sfx,
+        normalization,
+        None,
+        warnings,
     )?;
 
     if !existed {
@@ -537,24 +919,34 @@ pub fn ensure_sfx<'a>(
 }
 
 /// Ensure [Ono] guarantees are upheld.
+#[allow(clippy::too_many_arguments)]
 pub fn ensure_ono<'a>(
     k: &'a str,
     v: Ono,
     m: &Mod,
+    normalization: &Normalization,
     set: &'a mut HashSet<Id>,
     map: &'a mut HashMap<GenderKey, StaticSoundData>,
     smap: &'a mut HashMap<GenderKey, Settings>,
+    anys: &'a mut HashMap<CName, PlayerGender>,
+    warnings: &'a mut Vec<Warning>,
 ) -> Result<(), Error> {
     let existed = ensure_key_unique_or_inserted(k)?;
-    let (usage, genders) = v.into();
+    let (usage, genders): (Usage, GenderBased<Audio>) = v.into();
     let c_string = std::ffi::CString::new(k)?;
     let cname = CName::new(k);
     let mut key: GenderKey;
+    let mut stored: Option<(GenderKey, Id)> = None;
+    let (any, shared) = (genders.any, genders.shared);
     for (gender, Audio { file, settings }) in genders {
         key = GenderKey(cname, gender);
-        ensure(
+        if shared && let Some((stored, id)) = stored.take() {
+            ensure_alias(k, &stored, key, id, &file, set, map, smap)?;
+            continue;
+        }
+        let id = ensure(
             k,
-            key,
+            key.clone(),
             file,
             m,
             usage,
@@ -563,7 +955,14 @@ pub fn ensure_ono<'a>(
             map,
             smap,
             Source::Ono,
+            normalization,
+            None,
+            warnings,
         )?;
+        stored = Some((key, id));
+    }
+    if let Some(gender) = any {
+        anys.insert(cname, gender);
     }
 
     if !existed {
@@ -578,13 +977,18 @@ pub fn ensure_voice<'a>(
     k: &'a str,
     v: Voice,
     m: &Mod,
+    normalization: &Normalization,
     set: &'a mut HashSet<Id>,
     simple: &'a mut HashMap<LocaleKey, StaticSoundData>,
     complex: &'a mut HashMap<BothKey, StaticSoundData>,
     simple_subs: &'a mut HashMap<LocaleKey, DialogLine>,
     complex_subs: &'a mut HashMap<BothKey, DialogLine>,
+    simple_cues: &'a mut HashMap<LocaleKey, Vec<Cue>>,
+    complex_cues: &'a mut HashMap<BothKey, Vec<Cue>>,
     simple_settings: &'a mut HashMap<LocaleKey, Settings>,
     complex_settings: &'a mut HashMap<BothKey, Settings>,
+    anys: &'a mut HashMap<(CName, Locale), PlayerGender>,
+    warnings: &'a mut Vec<Warning>,
 ) -> Result<(), Error> {
     let mut existed = false;
     let v: AnyVoice = v.into();
@@ -592,21 +996,29 @@ pub fn ensure_voice<'a>(
     let cname = CName::new(k);
     let mut simple_key: LocaleKey;
     let mut complex_key: BothKey;
+    let mut cues: Option<Vec<Cue>>;
     match v {
         Either::Left((aud, usage, subs)) => {
             for (locale, Audio { file, settings }) in aud {
                 existed = existed || ensure_localized_key_unique_or_inserted(set, k, locale)?;
                 simple_key = LocaleKey(cname, locale);
-                if let Some(subs) = subs.as_ref().and_then(|x| x.get(&locale)) {
-                    ensure_store_subtitle::<LocaleKey>(
-                        simple_key.clone(),
-                        subs.clone(),
-                        simple_subs,
-                    )?;
+                cues = None;
+                match subs.as_ref().and_then(|x| x.get(&locale)) {
+                    Some(Subtitles::Line(subs)) => {
+                        ensure_store_subtitle::<LocaleKey>(
+                            simple_key.clone(),
+                            subs.clone(),
+                            simple_subs,
+                        )?;
+                    }
+                    Some(Subtitles::Timed(subs)) => {
+                        cues = Some(ensure_valid_cues(subs, m)?);
+                    }
+                    None => {}
                 }
                 ensure(
                     k,
-                    simple_key,
+                    simple_key.clone(),
                     file,
                     m,
                     usage,
@@ -615,37 +1027,79 @@ pub fn ensure_voice<'a>(
                     simple,
                     simple_settings,
                     Source::Voices,
+                    normalization,
+                    cues.as_deref(),
+                    warnings,
                 )?;
+                if let Some(cues) = cues {
+                    ensure_cues_in_pool(cues.len(), |nth| cue_key(k, None, nth))?;
+                    ensure_store_cues::<LocaleKey>(simple_key, cues, simple_cues)?;
+                }
             }
         }
         Either::Right((aud, usage, subs)) => {
             for (locale, genders) in aud {
                 existed = existed || ensure_localized_key_unique_or_inserted(set, k, locale)?;
+                let (any, shared) = (genders.any, genders.shared);
+                let mut stored: Option<(BothKey, Id)> = None;
                 for (gender, Audio { file, settings }) in genders {
                     complex_key = BothKey(cname, locale, gender);
-                    if let Some(subs) = subs.as_ref().and_then(|x| x.get(&locale)) {
-                        ensure_store_subtitle::<BothKey>(
+                    cues = None;
+                    match subs.as_ref().and_then(|x| x.get(&locale)).map(|x| {
+                        if gender == PlayerGender::Female {
+                            &x.female
+                        } else {
+                            &x.male
+                        }
+                    }) {
+                        Some(Subtitles::Line(subs)) => {
+                            ensure_store_subtitle::<BothKey>(
+                                complex_key.clone(),
+                                subs.clone(),
+                                complex_subs,
+                            )?;
+                        }
+                        Some(Subtitles::Timed(subs)) => {
+                            cues = Some(ensure_valid_cues(subs, m)?);
+                        }
+                        None => {}
+                    }
+                    if shared && let Some((stored, id)) = stored.take() {
+                        ensure_alias(
+                            k,
+                            &stored,
+                            complex_key.clone(),
+                            id,
+                            &file,
+                            set,
+                            complex,
+                            complex_settings,
+                        )?;
+                    } else {
+                        let id = ensure(
+                            k,
                             complex_key.clone(),
-                            if gender == PlayerGender::Female {
-                                subs.female.clone()
-                            } else {
-                                subs.male.clone()
-                            },
-                            complex_subs,
+                            file,
+                            m,
+                            usage,
+                            settings.clone(),
+                            set,
+                            complex,
+                            complex_settings,
+                            Source::Voices,
+                            normalization,
+                            cues.as_deref(),
+                            warnings,
                         )?;
+                        stored = Some((complex_key.clone(), id));
                     }
-                    ensure(
-                        k,
-                        complex_key,
-                        file,
-                        m,
-                        usage,
-                        settings.clone(),
-                        set,
-                        complex,
-                        complex_settings,
-                        Source::Voices,
-                    )?;
+                    if let Some(cues) = cues {
+                        ensure_cues_in_pool(cues.len(), |nth| cue_key(k, Some(gender), nth))?;
+                        ensure_store_cues::<BothKey>(complex_key, cues, complex_cues)?;
+                    }
+                }
+                if let Some(gender) = any {
+                    anys.insert((cname, locale), gender);
                 }
             }
         }
@@ -658,13 +1112,16 @@ pub fn ensure_voice<'a>(
 }
 
 /// Ensure [Music] guarantees are upheld.
+#[allow(clippy::too_many_arguments)]
 pub fn ensure_music<'a>(
     k: &'a str,
     v: Music,
     m: &Mod,
+    normalization: &Normalization,
     set: &'a mut HashSet<Id>,
     map: &'a mut HashMap<UniqueKey, StaticSoundData>,
     smap: &'a mut HashMap<UniqueKey, Settings>,
+    warnings: &'a mut Vec<Warning>,
 ) -> Result<(), Error> {
     let existed = ensure_key_unique_or_inserted(k)?;
     let UsableAudio {
@@ -685,6 +1142,9 @@ pub fn ensure_music<'a>(
         map,
         smap,
         Source::Music,
+        normalization,
+        None,
+        warnings,
     )?;
 
     if !existed {
@@ -698,18 +1158,28 @@ pub fn ensure_jingles<'a>(
     k: &'a str,
     v: Jingle,
     m: &Mod,
+    normalization: &Normalization,
     set: &'a mut HashSet<Id>,
     smap: &'a mut HashMap<UniqueKey, Settings>,
+    warnings: &'a mut Vec<Warning>,
 ) -> Result<(), Error> {
     let existed = ensure_key_unique_or_inserted(k)?;
     let Audio { file, settings } = (&v).into();
-    ensure_valid_audio_with_settings_and_captions(
+    let data = ensure_valid_audio_with_settings_and_captions(
         &file,
         m,
         Usage::Streaming,
         settings.as_ref(),
         v.captions(),
     )?;
+    let settings = ensure_normalized(
+        &data,
+        settings,
+        &Source::Jingle,
+        normalization,
+        &file,
+        warnings,
+    );
     let c_string = std::ffi::CString::new(k)?;
     let cname = CName::new(k);
     let key = UniqueKey(cname);
@@ -735,11 +1205,16 @@ pub fn ensure_scene_dialogs<'a>(
     k: i64,
     v: SceneDialogs,
     m: &Mod,
+    normalization: &Normalization,
     set: &'a mut HashSet<SceneId>,
     single: &'a mut HashMap<SceneLocaleKey, StaticSoundData>,
     dual: &'a mut HashMap<SceneBothKey, StaticSoundData>,
     single_settings: &'a mut HashMap<SceneLocaleKey, Settings>,
     dual_settings: &'a mut HashMap<SceneBothKey, Settings>,
+    single_cues: &'a mut HashMap<SceneLocaleKey, Vec<Cue>>,
+    dual_cues: &'a mut HashMap<SceneBothKey, Vec<Cue>>,
+    anys: &'a mut HashMap<(Cruid, Locale), PlayerGender>,
+    warnings: &'a mut Vec<Warning>,
 ) -> Result<(), Error> {
     let mut errors = Vec::with_capacity(10);
     let mut locale_key: SceneLocaleKey;
@@ -747,12 +1222,24 @@ pub fn ensure_scene_dialogs<'a>(
     let v: AnySceneDialog = v.into();
     let cruid = Cruid::from(k);
     match v {
-        Either::Left((aud, usage)) => {
+        Either::Left((aud, usage, subs)) => {
             for (locale, Audio { file, settings }) in aud {
                 locale_key = SceneLocaleKey(cruid, locale);
                 ensure_scene_key_no_conflict(&locale_key, &(k, locale), set)?;
+                let cues = match subs
+                    .as_ref()
+                    .and_then(|x| x.get(&locale))
+                    .map(|x| ensure_valid_cues(x, m))
+                    .transpose()
+                {
+                    Ok(cues) => cues,
+                    Err(e) => {
+                        errors.push(e);
+                        continue;
+                    }
+                };
                 if let Err(e) = ensure_scene(
-                    locale_key,
+                    locale_key.clone(),
                     file,
                     m,
                     usage,
@@ -760,16 +1247,28 @@ pub fn ensure_scene_dialogs<'a>(
                     set,
                     single,
                     single_settings,
+                    normalization,
+                    cues.as_deref(),
+                    warnings,
                 ) {
                     errors.push(e);
                     continue;
                 }
+                if let Some(cues) = cues
+                    && let Err(e) =
+                        ensure_cues_in_pool(cues.len(), |nth| scene_cue_key(cruid, None, nth))
+                            .and_then(|_| ensure_store_scene_cues(locale_key, cues, single_cues))
+                {
+                    errors.push(e);
+                }
             }
         }
-        Either::Right((aud, usage)) => {
+        Either::Right((aud, usage, subs)) => {
             let mut checked_conflict;
             'outer: for (locale, genders) in aud {
                 checked_conflict = false;
+                let (any, shared) = (genders.any, genders.shared);
+                let mut stored: Option<(SceneBothKey, SceneId)> = None;
                 for (gender, Audio { file, settings }) in genders {
                     both_key = SceneBothKey(Cruid::from(k), locale, gender);
                     // only check once per pair, otherwise gender will conflict with each other
@@ -780,9 +1279,55 @@ pub fn ensure_scene_dialogs<'a>(
                         }
                         checked_conflict = true;
                     }
-                    if let Err(e) =
-                        ensure_scene(both_key, file, m, usage, settings, set, dual, dual_settings)
-                    {
+                    let cues = subs
+                        .as_ref()
+                        .and_then(|x| x.get(&locale))
+                        .map(|x| {
+                            ensure_valid_cues(
+                                if gender == PlayerGender::Female {
+                                    &x.female
+                                } else {
+                                    &x.male
+                                },
+                                m,
+                            )
+                        })
+                        .transpose();
+                    if let Err(e) = cues.and_then(|cues| {
+                        if shared && let Some((stored, id)) = stored.take() {
+                            ensure_scene_alias(
+                                &stored,
+                                both_key.clone(),
+                                id,
+                                &file,
+                                set,
+                                dual,
+                                dual_settings,
+                            )?;
+                        } else {
+                            let id = ensure_scene(
+                                both_key.clone(),
+                                file,
+                                m,
+                                usage,
+                                settings,
+                                set,
+                                dual,
+                                dual_settings,
+                                normalization,
+                                cues.as_deref(),
+                                warnings,
+                            )?;
+                            stored = Some((both_key.clone(), id));
+                        }
+                        if let Some(cues) = cues {
+                            ensure_cues_in_pool(cues.len(), |nth| {
+                                scene_cue_key(cruid, Some(gender), nth)
+                            })?;
+                            ensure_store_scene_cues(both_key.clone(), cues, dual_cues)?;
+                        }
+                        Ok(())
+                    }) {
                         errors.push(e);
                         // other gender might already have been added
                         dual.remove(&SceneBothKey(Cruid::from(k), locale, gender.opposite()));
@@ -791,9 +1336,13 @@ pub fn ensure_scene_dialogs<'a>(
                             locale,
                             gender.opposite(),
                         ));
+                        dual_cues.remove(&SceneBothKey(Cruid::from(k), locale, gender.opposite()));
                         continue 'outer;
                     }
                 }
+                if let Some(gender) = any {
+                    anys.insert((cruid, locale), gender);
+                }
             }
         }
     };
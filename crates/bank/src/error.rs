@@ -114,6 +114,34 @@ pub mod validation {
             visibility(pub(crate))
         )]
         InvalidAudioCaption { which: String, why: String },
+        #[snafu(
+            display("invalid subtitles: {path} ({source})"),
+            visibility(pub(crate))
+        )]
+        InvalidSubtitles {
+            path: String,
+            source: audioware_manifest::error::CueError,
+        },
+        #[snafu(
+            display("invalid translation: {cname} ({why})"),
+            visibility(pub(crate))
+        )]
+        InvalidTranslation { cname: String, why: String },
+        #[snafu(display("invalid snapshot {name}: {}", why.iter().map(|x| format!("{}: {}", x.which, x.why)).collect::<Vec<_>>().join("\n")), visibility(pub(crate)))]
+        InvalidSnapshot {
+            name: String,
+            why: Vec<audioware_core::error::ValidationError>,
+        },
+        #[snafu(display("invalid ducking for {track}: {}", why.iter().map(|x| format!("{}: {}", x.which, x.why)).collect::<Vec<_>>().join("\n")), visibility(pub(crate)))]
+        InvalidDucking {
+            track: String,
+            why: Vec<audioware_core::error::ValidationError>,
+        },
+        #[snafu(display("invalid normalization in {manifest}: {}", why.iter().map(|x| format!("{}: {}", x.which, x.why)).collect::<Vec<_>>().join("\n")), visibility(pub(crate)))]
+        InvalidNormalization {
+            manifest: String,
+            why: Vec<audioware_core::error::ValidationError>,
+        },
         #[snafu(display("cannot store data: {key} ({path})"), visibility(pub(crate)))]
         CannotStoreData { key: Key, path: String },
         #[snafu(
@@ -126,6 +154,13 @@ pub mod validation {
             visibility(pub(crate))
         )]
         CannotStoreSubtitle { key: Key, value: DialogLine },
+        #[snafu(display("cannot store subtitles cues: {key}"), visibility(pub(crate)))]
+        CannotStoreCues { key: Key },
+        #[snafu(
+            display("cannot store scene subtitles cues: {key}"),
+            visibility(pub(crate))
+        )]
+        CannotStoreSceneCues { key: SceneKey },
         #[snafu(display("cannot store audio settings"), visibility(pub(crate)))]
         CannotStoreSettings,
         #[snafu(display("cannot store id: {id}"), visibility(pub(crate)))]
@@ -0,0 +1,78 @@
+//! Audio health checks, reported as warnings on load.
+
+use std::{
+    fmt,
+    path::Path,
+    sync::atomic::{AtomicU32, Ordering},
+};
+
+use audioware_core::health::{AudioIssue, diagnose, is_stereo};
+use audioware_manifest::{Region, Source};
+use either::Either;
+use kira::{
+    Frame,
+    sound::{FromFileError, static_sound::StaticSoundData, streaming::StreamingSoundData},
+};
+
+/// Output device sample rate, if known (`0` otherwise).
+pub static OUTPUT_SAMPLE_RATE: AtomicU32 = AtomicU32::new(0);
+
+/// Audio problem which does not prevent it from loading.
+#[derive(Debug, Clone)]
+pub struct Warning {
+    pub path: String,
+    pub issue: AudioIssue,
+}
+
+impl fmt::Display for Warning {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} ({})", self.issue, self.path)
+    }
+}
+
+/// Frames actually heard, restricted to [Region] if any.
+pub(crate) fn region_frames<'a>(data: &'a StaticSoundData, region: Option<&Region>) -> &'a [Frame] {
+    let Some(region) = region else {
+        return &data.frames[..];
+    };
+    let position = |x: Option<std::time::Duration>, or: usize| {
+        x.map(|x| (x.as_secs_f64() * data.sample_rate as f64) as usize)
+            .unwrap_or(or)
+            .min(data.frames.len())
+    };
+    let starts = position(region.starts, 0);
+    let ends = position(region.ends, data.frames.len()).max(starts);
+    &data.frames[starts..ends]
+}
+
+/// Check audio for common problems, only in [Region] if any.
+///
+/// Only audio decoded in-memory can be inspected.
+pub fn check_audio_health(
+    audio: &Either<StaticSoundData, StreamingSoundData<FromFileError>>,
+    region: Option<&Region>,
+    source: &Source,
+    path: &impl AsRef<Path>,
+    warnings: &mut Vec<Warning>,
+) {
+    let Either::Left(data) = audio else {
+        return;
+    };
+    let frames = region_frames(data, region);
+    let mut issues = diagnose(frames, data.sample_rate);
+    let output = OUTPUT_SAMPLE_RATE.load(Ordering::Relaxed);
+    if output != 0 && data.sample_rate != output {
+        issues.push(AudioIssue::SampleRateMismatch {
+            audio: data.sample_rate,
+            output,
+        });
+    }
+    // onos are spatialized, stereo is wasted at best
+    if *source == Source::Ono && is_stereo(frames) {
+        issues.push(AudioIssue::UnexpectedStereo);
+    }
+    warnings.extend(issues.into_iter().map(|issue| Warning {
+        path: path.as_ref().display().to_string(),
+        issue,
+    }));
+}
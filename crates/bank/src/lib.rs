@@ -2,13 +2,14 @@
 
 use std::{
     collections::{HashMap, HashSet},
+    path::PathBuf,
     time::{Duration, Instant},
 };
 
-use audioware_core::{AudioDuration, With};
+use audioware_core::{AudioDuration, Probed, With};
 use audioware_manifest::{
-    Depot, DialogLine, Locale, Manifest, Mod, PlayerGender, R6Audioware, REDmod, Settings,
-    SpokenLocale,
+    Cue, Depot, DialogLine, DuckSource, Locale, Manifest, Mod, PlayerGender, R6Audioware, REDmod,
+    Settings, Snapshot, SpokenLocale, TrackDucking, Translation,
     error::{CannotParseManifest, CannotReadManifest},
 };
 use either::Either;
@@ -21,6 +22,8 @@ pub mod conflict;
 mod ensure;
 pub mod error;
 pub use error::Error;
+mod health;
+pub use health::*;
 mod id;
 mod key;
 mod scene_id;
@@ -54,6 +57,8 @@ pub struct Banks {
     pub dual_voices: HashMap<BothKey, StaticSoundData>,
     pub single_subs: HashMap<LocaleKey, DialogLine>,
     pub dual_subs: HashMap<BothKey, DialogLine>,
+    pub single_cues: HashMap<LocaleKey, Vec<Cue>>,
+    pub dual_cues: HashMap<BothKey, Vec<Cue>>,
     pub unique_settings: HashMap<UniqueKey, Settings>,
     pub gender_settings: HashMap<GenderKey, Settings>,
     pub single_settings: HashMap<LocaleKey, Settings>,
@@ -62,6 +67,20 @@ pub struct Banks {
     pub dual_scene_dialogs: HashMap<SceneBothKey, StaticSoundData>,
     pub single_scene_dialogs_settings: HashMap<SceneLocaleKey, Settings>,
     pub dual_scene_dialogs_settings: HashMap<SceneBothKey, Settings>,
+    pub single_scene_cues: HashMap<SceneLocaleKey, Vec<Cue>>,
+    pub dual_scene_cues: HashMap<SceneBothKey, Vec<Cue>>,
+    /// Gender whose audio was defined with `any`, per ono.
+    pub any_genders: HashMap<CName, PlayerGender>,
+    /// Gender whose audio was defined with `any`, per voice and locale.
+    pub any_localized_genders: HashMap<(CName, Locale), PlayerGender>,
+    /// Gender whose audio was defined with `any`, per scene dialog and locale.
+    pub any_scene_genders: HashMap<(Cruid, Locale), PlayerGender>,
+    /// Headers of streaming audio, probed on registration.
+    pub sample_rates: HashMap<PathBuf, Probed>,
+    /// Named mixes, see [Snapshot].
+    pub snapshots: HashMap<CName, Snapshot>,
+    /// Tracks ducked whenever sounds play on each track, see [TrackDucking].
+    pub ducking: HashMap<DuckSource, TrackDucking>,
 }
 
 impl Banks {
@@ -87,6 +106,24 @@ impl Banks {
             .iter()
             .any(|x| AsRef::<Cruid>::as_ref(&x) == cruid)
     }
+    /// Gender to fall back to whenever it is unknown,
+    /// for audio defined with `any` gender.
+    pub fn any_gender(&self, cname: &CName, spoken: &SpokenLocale) -> Option<PlayerGender> {
+        self.any_genders
+            .get(cname)
+            .or_else(|| {
+                self.any_localized_genders
+                    .get(&(*cname, spoken.into_inner()))
+            })
+            .copied()
+    }
+    /// Gender to fall back to whenever it is unknown,
+    /// for scene dialog defined with `any` gender.
+    pub fn any_scene_gender(&self, cruid: &Cruid, spoken: &SpokenLocale) -> Option<PlayerGender> {
+        self.any_scene_genders
+            .get(&(*cruid, spoken.into_inner()))
+            .copied()
+    }
     /// Return audio duration (as seconds) if any, otherwise `-1.0`.
     pub fn duration(
         &self,
@@ -97,7 +134,7 @@ impl Banks {
     ) -> f32 {
         let locale = SpokenLocale::from(locale);
         if let Ok(id) = self.ids.try_get(cname, &locale, Some(&gender)) {
-            match (total, id, self.data(id)) {
+            let duration = match (total, id, self.data(id)) {
                 // if no need for total and in-memory, sound data already embed settings
                 (false, Id::InMemory(..), data) => data
                     .left()
@@ -105,6 +142,7 @@ impl Banks {
                     .slice_duration(),
                 // if no need for total and on-demand, check settings
                 (false, Id::OnDemand(..), data) => match (data, self.settings(id)) {
+                    // time-stretched audio is kept in-memory
                     (Either::Left(x), settings) => x.with(settings).slice_duration(),
                     (Either::Right(x), settings) => x.with(settings).slice_duration(),
                 },
@@ -113,12 +151,21 @@ impl Banks {
                     Either::Left(x) => x.total_duration(),
                     Either::Right(x) => x.total_duration(),
                 },
+            };
+            match self.slowest_playback_rate(id) {
+                // playback rate, worst case if randomized
+                Some(rate) if !total && rate > 0.0 => duration.div_f64(rate),
+                _ => duration,
             }
             .as_secs_f32()
         } else {
             -1.0
         }
     }
+    /// Playback rate if fixed, slowest one if randomized, if any.
+    fn slowest_playback_rate(&self, id: &Id) -> Option<f64> {
+        self.declared_settings(id)?.playback_rate.map(|x| x.min().0)
+    }
     /// All languages found in [Manifest]s.
     pub fn languages(&self) -> HashSet<Locale> {
         let mut out = HashSet::new();
@@ -128,6 +175,18 @@ impl Banks {
         for key in self.dual_subs.keys() {
             out.insert(key.1);
         }
+        for key in self.single_cues.keys() {
+            out.insert(key.1);
+        }
+        for key in self.dual_cues.keys() {
+            out.insert(key.1);
+        }
+        for key in self.single_scene_cues.keys() {
+            out.insert(key.1);
+        }
+        for key in self.dual_scene_cues.keys() {
+            out.insert(key.1);
+        }
         out
     }
     fn mods() -> (Vec<Mod>, Vec<Error>) {
@@ -168,6 +227,8 @@ impl Banks {
         let mut dual_voices: HashMap<BothKey, StaticSoundData> = HashMap::new();
         let mut single_subs: HashMap<LocaleKey, DialogLine> = HashMap::new();
         let mut dual_subs: HashMap<BothKey, DialogLine> = HashMap::new();
+        let mut single_cues: HashMap<LocaleKey, Vec<Cue>> = HashMap::new();
+        let mut dual_cues: HashMap<BothKey, Vec<Cue>> = HashMap::new();
         let mut unique_settings: HashMap<UniqueKey, Settings> = HashMap::new();
         let mut gender_settings: HashMap<GenderKey, Settings> = HashMap::new();
         let mut single_settings: HashMap<LocaleKey, Settings> = HashMap::new();
@@ -176,6 +237,16 @@ impl Banks {
         let mut dual_scene_dialogs: HashMap<SceneBothKey, StaticSoundData> = HashMap::new();
         let mut single_scene_dialogs_settings: HashMap<SceneLocaleKey, Settings> = HashMap::new();
         let mut dual_scene_dialogs_settings: HashMap<SceneBothKey, Settings> = HashMap::new();
+        let mut single_scene_cues: HashMap<SceneLocaleKey, Vec<Cue>> = HashMap::new();
+        let mut dual_scene_cues: HashMap<SceneBothKey, Vec<Cue>> = HashMap::new();
+        let mut any_genders: HashMap<CName, PlayerGender> = HashMap::new();
+        let mut any_localized_genders: HashMap<(CName, Locale), PlayerGender> = HashMap::new();
+        let mut any_scene_genders: HashMap<(Cruid, Locale), PlayerGender> = HashMap::new();
+        // translations overlay voices from any mod, so they are only processed once all are loaded
+        let mut translations: Vec<(String, Translation)> = Vec::new();
+        let mut snapshots: HashMap<CName, Snapshot> = HashMap::new();
+        let mut ducking: HashMap<DuckSource, TrackDucking> = HashMap::new();
+        let mut warnings: Vec<Warning> = Vec::new();
 
         for m in mods {
             let paths = m.manifests_paths();
@@ -206,15 +277,24 @@ impl Banks {
                     errors.push(e);
                     continue;
                 }
+                let normalization = match ensure_normalization(manifest.normalize, path) {
+                    Ok(x) => x,
+                    Err(e) => {
+                        errors.push(e);
+                        continue;
+                    }
+                };
                 if let Some(sfx) = manifest.sfx {
                     for (key, value) in sfx {
                         match ensure_sfx(
                             key.as_str(),
                             value,
                             &m,
+                            &normalization,
                             &mut ids,
                             &mut uniques,
                             &mut unique_settings,
+                            &mut warnings,
                         ) {
                             Ok(x) => x,
                             Err(e) => {
@@ -230,9 +310,12 @@ impl Banks {
                             key.as_str(),
                             value,
                             &m,
+                            &normalization,
                             &mut ids,
                             &mut genders,
                             &mut gender_settings,
+                            &mut any_genders,
+                            &mut warnings,
                         ) {
                             Ok(x) => x,
                             Err(e) => {
@@ -248,13 +331,18 @@ impl Banks {
                             key.as_str(),
                             value,
                             &m,
+                            &normalization,
                             &mut ids,
                             &mut single_voices,
                             &mut dual_voices,
                             &mut single_subs,
                             &mut dual_subs,
+                            &mut single_cues,
+                            &mut dual_cues,
                             &mut single_settings,
                             &mut dual_settings,
+                            &mut any_localized_genders,
+                            &mut warnings,
                         ) {
                             Ok(x) => x,
                             Err(e) => {
@@ -270,9 +358,11 @@ impl Banks {
                             key.as_str(),
                             value,
                             &m,
+                            &normalization,
                             &mut ids,
                             &mut uniques,
                             &mut unique_settings,
+                            &mut warnings,
                         ) {
                             Ok(x) => x,
                             Err(e) => {
@@ -288,8 +378,10 @@ impl Banks {
                             key.as_str(),
                             value,
                             &m,
+                            &normalization,
                             &mut ids,
                             &mut unique_settings,
+                            &mut warnings,
                         ) {
                             Ok(x) => x,
                             Err(e) => {
@@ -305,11 +397,16 @@ impl Banks {
                             key as i64,
                             value,
                             &m,
+                            &normalization,
                             &mut scene_ids,
                             &mut single_scene_dialogs,
                             &mut dual_scene_dialogs,
                             &mut single_scene_dialogs_settings,
                             &mut dual_scene_dialogs_settings,
+                            &mut single_scene_cues,
+                            &mut dual_scene_cues,
+                            &mut any_scene_genders,
+                            &mut warnings,
                         ) {
                             Ok(x) => x,
                             Err(e) => {
@@ -319,9 +416,48 @@ impl Banks {
                         };
                     }
                 }
+                if let Some(x) = manifest.translations {
+                    translations.extend(x);
+                }
+                if let Some(x) = manifest.snapshots {
+                    for (key, value) in x {
+                        if let Err(e) = ensure_snapshot(key.as_str(), value, &mut snapshots) {
+                            errors.push(e);
+                        }
+                    }
+                }
+                if let Some(x) = manifest.ducking {
+                    for (key, value) in x {
+                        if let Err(e) = ensure_track_ducking(key, value, &mut ducking) {
+                            errors.push(e);
+                        }
+                    }
+                }
+            }
+        }
+
+        for (key, value) in translations {
+            if let Err(e) =
+                ensure_translation(key.as_str(), value, &ids, &mut single_subs, &mut dual_subs)
+            {
+                errors.push(e);
             }
         }
 
+        let mut probed = std::mem::take(&mut *PROBED.lock().expect("probed headers lock"));
+        let sample_rates: HashMap<PathBuf, Probed> = ids
+            .iter()
+            .filter_map(|x| match x {
+                Id::OnDemand(Usage::Streaming(_, path), ..) => Some(path),
+                _ => None,
+            })
+            .chain(scene_ids.iter().filter_map(|x| match x {
+                SceneId::OnDemand(Usage::Streaming(_, path), ..) => Some(path),
+                _ => None,
+            }))
+            .filter_map(|path| Some((path.clone(), probed.remove(path)?)))
+            .collect();
+
         let lengths = ids.iter().fold((0, 0, 0), |acc, x| {
             let (mut odsta, mut odstr, mut imsta) = acc;
             match x {
@@ -374,6 +510,7 @@ impl Banks {
             len_scene_ids: scene_ids.len(),
             errors,
             scene_errors,
+            warnings,
         };
 
         (
@@ -386,6 +523,8 @@ impl Banks {
                 dual_voices,
                 single_subs,
                 dual_subs,
+                single_cues,
+                dual_cues,
                 unique_settings,
                 gender_settings,
                 single_settings,
@@ -394,6 +533,14 @@ impl Banks {
                 dual_scene_dialogs,
                 single_scene_dialogs_settings,
                 dual_scene_dialogs_settings,
+                single_scene_cues,
+                dual_scene_cues,
+                any_genders,
+                any_localized_genders,
+                any_scene_genders,
+                sample_rates,
+                snapshots,
+                ducking,
             },
             report,
         )
@@ -417,6 +564,8 @@ impl Banks {
         self.dual_voices = banks.dual_voices;
         self.single_subs = banks.single_subs;
         self.dual_subs = banks.dual_subs;
+        self.single_cues = banks.single_cues;
+        self.dual_cues = banks.dual_cues;
         self.unique_settings = banks.unique_settings;
         self.gender_settings = banks.gender_settings;
         self.single_settings = banks.single_settings;
@@ -425,6 +574,12 @@ impl Banks {
         self.dual_scene_dialogs = banks.dual_scene_dialogs;
         self.single_scene_dialogs_settings = banks.single_scene_dialogs_settings;
         self.dual_scene_dialogs_settings = banks.dual_scene_dialogs_settings;
+        self.single_scene_cues = banks.single_scene_cues;
+        self.dual_scene_cues = banks.dual_scene_cues;
+        self.any_genders = banks.any_genders;
+        self.any_localized_genders = banks.any_localized_genders;
+        self.any_scene_genders = banks.any_scene_genders;
+        self.sample_rates = banks.sample_rates;
         initialization
     }
 }
@@ -445,6 +600,7 @@ pub struct Initialization {
     pub scene_errors: Vec<Error>,
     #[cfg(feature = "hot-reload")]
     pub scene_errors: Vec<std::sync::Arc<Error>>,
+    pub warnings: Vec<Warning>,
 }
 
 pub enum InitializationOutcome {
@@ -495,6 +651,7 @@ impl std::fmt::Display for Initialization {
             scene_errors,
             scene_lengths,
             len_scene_ids,
+            warnings,
         } = self;
         write!(
             f,
@@ -506,6 +663,8 @@ for a total of: {len_ids} id(s)
 {scene_lengths}
 for a total of: {len_scene_ids} scene id(s)
 {}
+-------------------------------
+{}
 "##,
             if errors.is_empty() {
                 "no error reported!".to_string()
@@ -530,6 +689,18 @@ for a total of: {len_scene_ids} scene id(s)
                         .collect::<Vec<_>>()
                         .join("\n")
                 )
+            },
+            if warnings.is_empty() {
+                "no warning reported!".to_string()
+            } else {
+                format!(
+                    "warning(s):\n{}",
+                    warnings
+                        .iter()
+                        .map(|w| format!("- {w}"))
+                        .collect::<Vec<_>>()
+                        .join("\n")
+                )
             }
         )
     }
@@ -6,13 +6,14 @@ use std::{
     sync::OnceLock,
 };
 
+use audioware_core::With;
 use either::Either;
 use kira::sound::{FromFileError, static_sound::StaticSoundData, streaming::StreamingSoundData};
 
 use audioware_manifest::{
-    DialogLine, Locale, PlayerGender, Settings as ManifestSettings, WrittenLocale,
+    Cue, DialogLine, Locale, PlayerGender, Settings as ManifestSettings, WrittenLocale,
 };
-use red4ext_rs::types::CName;
+use red4ext_rs::types::{CName, Cruid};
 
 use crate::{Banks, BothKey, Id, Key, LocaleKey, SceneId, SceneKey, Usage};
 
@@ -24,6 +25,12 @@ pub trait BankSettings<K, V> {
     fn settings(&self, key: &K) -> Option<V>;
 }
 
+pub trait BankDeclaredSettings<K> {
+    /// Settings as declared in manifest if any,
+    /// whether already embedded in sound data or not.
+    fn declared_settings(&self, key: &K) -> Option<&ManifestSettings>;
+}
+
 pub trait BankSubtitles {
     type Key;
 
@@ -32,6 +39,29 @@ pub trait BankSubtitles {
     fn subtitles(&self, locale: WrittenLocale) -> Vec<(CName, (String, String))>;
 }
 
+pub trait BankCues<K> {
+    /// Timed subtitles for a given key if any.
+    fn cues(&self, key: &K) -> Option<&[Cue]>;
+    /// Localization key of the nth [Cue] for a given key.
+    fn cue_name(&self, key: &K, nth: usize) -> Option<CName>;
+}
+
+/// Localization key for the nth [Cue] of an audio.
+///
+/// Cues are keyed per gender, since each gender can have a different amount of them.
+pub fn cue_key(name: impl std::fmt::Display, gender: Option<PlayerGender>, nth: usize) -> String {
+    match gender {
+        Some(gender) => format!("{name}#{gender}#{nth}"),
+        None => format!("{name}#{nth}"),
+    }
+}
+
+/// Localization key for the nth [Cue] of a scene dialog.
+pub fn scene_cue_key(cruid: Cruid, gender: Option<PlayerGender>, nth: usize) -> String {
+    // WolvenKit and Codeware represents CRUID as u64
+    cue_key(i64::from(cruid) as u64, gender, nth)
+}
+
 pub struct OnceStorage<K, V>(OnceLock<HashMap<K, V>>);
 impl<K, V> Default for OnceStorage<K, V> {
     fn default() -> Self {
@@ -169,27 +199,58 @@ impl BankData<Id, Either<StaticSoundData, StreamingSoundData<FromFileError>>> fo
                 let settings = self.settings(key);
                 let data = StreamingSoundData::from_file(path)
                     .expect("streaming sound data has already been validated");
+                let probed = self.sample_rates.get(path).copied();
                 if let Some(settings) = settings {
-                    return Either::Right(data.with_settings(settings.into()));
+                    return Either::Right(data.with_settings(settings.into()).with(probed));
                 }
-                Either::Right(data)
-            }
-            // in-memory sound data already embed settings
-            Id::InMemory(Key::Unique(key), ..) => {
-                Either::Left(self.uniques.get(key).cloned().expect("key guarantees"))
-            }
-            Id::InMemory(Key::Gender(key), ..) => {
-                Either::Left(self.genders.get(key).cloned().expect("key guarantees"))
+                Either::Right(data.with(probed))
             }
+            // in-memory sound data already embed settings,
+            // only those sampled on every play are left
+            Id::InMemory(Key::Unique(key), ..) => Either::Left(
+                self.uniques
+                    .get(key)
+                    .cloned()
+                    .expect("key guarantees")
+                    .with(
+                        self.unique_settings
+                            .get(key)
+                            .and_then(ManifestSettings::randomized),
+                    ),
+            ),
+            Id::InMemory(Key::Gender(key), ..) => Either::Left(
+                self.genders
+                    .get(key)
+                    .cloned()
+                    .expect("key guarantees")
+                    .with(
+                        self.gender_settings
+                            .get(key)
+                            .and_then(ManifestSettings::randomized),
+                    ),
+            ),
             Id::InMemory(Key::Locale(key), ..) => Either::Left(
                 self.single_voices
                     .get(key)
                     .cloned()
-                    .expect("key guarantees"),
+                    .expect("key guarantees")
+                    .with(
+                        self.single_settings
+                            .get(key)
+                            .and_then(ManifestSettings::randomized),
+                    ),
+            ),
+            Id::InMemory(Key::Both(key), ..) => Either::Left(
+                self.dual_voices
+                    .get(key)
+                    .cloned()
+                    .expect("key guarantees")
+                    .with(
+                        self.dual_settings
+                            .get(key)
+                            .and_then(ManifestSettings::randomized),
+                    ),
             ),
-            Id::InMemory(Key::Both(key), ..) => {
-                Either::Left(self.dual_voices.get(key).cloned().expect("key guarantees"))
-            }
         }
     }
 }
@@ -211,31 +272,111 @@ impl BankData<SceneId, Either<StaticSoundData, StreamingSoundData<FromFileError>
                 let settings = self.settings(key);
                 let data = StreamingSoundData::from_file(path)
                     .expect("streaming sound data has already been validated");
+                let probed = self.sample_rates.get(path).copied();
                 if let Some(settings) = settings {
-                    return Either::Right(data.with_settings(settings.into()));
+                    return Either::Right(data.with_settings(settings.into()).with(probed));
                 }
-                Either::Right(data)
+                Either::Right(data.with(probed))
             }
-            // in-memory sound data already embed settings
+            // in-memory sound data already embed settings,
+            // only those sampled on every play are left
             SceneId::InMemory(SceneKey::Locale(key), ..) => Either::Left(
                 self.single_scene_dialogs
                     .get(key)
                     .cloned()
-                    .expect("key guarantees"),
+                    .expect("key guarantees")
+                    .with(
+                        self.single_scene_dialogs_settings
+                            .get(key)
+                            .and_then(ManifestSettings::randomized),
+                    ),
             ),
             SceneId::InMemory(SceneKey::Both(key), ..) => Either::Left(
                 self.dual_scene_dialogs
                     .get(key)
                     .cloned()
-                    .expect("key guarantees"),
+                    .expect("key guarantees")
+                    .with(
+                        self.dual_scene_dialogs_settings
+                            .get(key)
+                            .and_then(ManifestSettings::randomized),
+                    ),
             ),
         }
     }
 }
 
+impl BankDeclaredSettings<Id> for Banks {
+    /// Retrieves declared sound settings for a given [Id] if any.
+    fn declared_settings(&self, key: &Id) -> Option<&ManifestSettings> {
+        match AsRef::<Key>::as_ref(key) {
+            Key::Unique(key) => self.unique_settings.get(key),
+            Key::Gender(key) => self.gender_settings.get(key),
+            Key::Locale(key) => self.single_settings.get(key),
+            Key::Both(key) => self.dual_settings.get(key),
+        }
+    }
+}
+
+impl BankDeclaredSettings<SceneId> for Banks {
+    /// Retrieves declared sound settings for a given [SceneId] if any.
+    fn declared_settings(&self, key: &SceneId) -> Option<&ManifestSettings> {
+        match AsRef::<SceneKey>::as_ref(key) {
+            SceneKey::Locale(key) => self.single_scene_dialogs_settings.get(key),
+            SceneKey::Both(key) => self.dual_scene_dialogs_settings.get(key),
+        }
+    }
+}
+
+impl BankCues<Id> for Banks {
+    /// Retrieves timed subtitles for a given [Id] if any.
+    fn cues(&self, key: &Id) -> Option<&[Cue]> {
+        match AsRef::<Key>::as_ref(key) {
+            Key::Locale(key) => self.single_cues.get(key).map(Vec::as_slice),
+            Key::Both(key) => self.dual_cues.get(key).map(Vec::as_slice),
+            Key::Unique(_) | Key::Gender(_) => None,
+        }
+    }
+    fn cue_name(&self, key: &Id, nth: usize) -> Option<CName> {
+        match AsRef::<Key>::as_ref(key) {
+            Key::Locale(key) => Some(CName::new(cue_key(key.0, None, nth).as_str())),
+            Key::Both(key) => Some(CName::new(cue_key(key.0, Some(key.2), nth).as_str())),
+            Key::Unique(_) | Key::Gender(_) => None,
+        }
+    }
+}
+
+impl BankCues<SceneId> for Banks {
+    /// Retrieves timed subtitles for a given [SceneId] if any.
+    fn cues(&self, key: &SceneId) -> Option<&[Cue]> {
+        match AsRef::<SceneKey>::as_ref(key) {
+            SceneKey::Locale(key) => self.single_scene_cues.get(key).map(Vec::as_slice),
+            SceneKey::Both(key) => self.dual_scene_cues.get(key).map(Vec::as_slice),
+        }
+    }
+    fn cue_name(&self, key: &SceneId, nth: usize) -> Option<CName> {
+        Some(match AsRef::<SceneKey>::as_ref(key) {
+            SceneKey::Locale(key) => CName::new(scene_cue_key(key.0, None, nth).as_str()),
+            SceneKey::Both(key) => CName::new(scene_cue_key(key.0, Some(key.2), nth).as_str()),
+        })
+    }
+}
+
+fn keyed_cues(cues: &[Cue], key: impl Fn(usize) -> String) -> Vec<(CName, Cue)> {
+    cues.iter()
+        .enumerate()
+        .map(|(nth, cue)| (CName::new(key(nth).as_str()), cue.clone()))
+        .collect()
+}
+
 impl BankSubtitles for Banks {
     type Key = Id;
     fn subtitles(&self, locale: WrittenLocale) -> Vec<(CName, (String, String))> {
+        let cues = |cues: Vec<(CName, Cue)>| {
+            cues.into_iter()
+                .map(|(k, x)| (k, (x.line.msg.clone(), x.line.msg)))
+                .collect::<Vec<_>>()
+        };
         [
             self.single_subs
                 .iter()
@@ -247,6 +388,26 @@ impl BankSubtitles for Banks {
                 .filter(|x| x.0.1 == locale)
                 .map(|x| (x.0.0, (x.1.msg.clone(), x.1.msg.clone())))
                 .collect::<Vec<_>>(),
+            self.single_cues
+                .iter()
+                .filter(|x| x.0.1 == locale)
+                .flat_map(|(k, x)| cues(keyed_cues(x, |nth| cue_key(k.0, None, nth))))
+                .collect::<Vec<_>>(),
+            self.dual_cues
+                .iter()
+                .filter(|x| x.0.1 == locale)
+                .flat_map(|(k, x)| cues(keyed_cues(x, |nth| cue_key(k.0, Some(k.2), nth))))
+                .collect::<Vec<_>>(),
+            self.single_scene_cues
+                .iter()
+                .filter(|x| x.0.1 == locale)
+                .flat_map(|(k, x)| cues(keyed_cues(x, |nth| scene_cue_key(k.0, None, nth))))
+                .collect::<Vec<_>>(),
+            self.dual_scene_cues
+                .iter()
+                .filter(|x| x.0.1 == locale)
+                .flat_map(|(k, x)| cues(keyed_cues(x, |nth| scene_cue_key(k.0, Some(k.2), nth))))
+                .collect::<Vec<_>>(),
         ]
         .concat()
     }
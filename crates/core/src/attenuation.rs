@@ -0,0 +1,148 @@
+//! Volume attenuation over distance.
+
+use kira::{Decibels, Easing, track::SpatialTrackDistances};
+use serde::Deserialize;
+
+use crate::{Amplitude, factor_to_decibels};
+
+/// Falloff of volume with distance from listener,
+/// in place of [kira] built-in attenuation.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(untagged)]
+pub enum AttenuationCurve {
+    Preset(AttenuationPreset),
+    /// Gain linearly interpolated between points, sorted by distance.
+    Points(Vec<AttenuationPoint>),
+}
+
+/// Physically-based falloff, from min distance onwards.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum AttenuationPreset {
+    /// Inverse distance law: -6dB whenever distance doubles.
+    Logarithmic,
+    /// Inverse square law: -12dB whenever distance doubles.
+    InverseSquare,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize)]
+pub struct AttenuationPoint {
+    /// Distance from listener, in meters.
+    pub distance: f32,
+    pub gain: Amplitude,
+}
+
+impl AttenuationCurve {
+    /// Gain at given distance from listener.
+    ///
+    /// Presets are at full volume up to min distance, and silent past max distance.
+    pub fn gain(&self, distance: f32, distances: SpatialTrackDistances) -> Decibels {
+        let gain = match self {
+            Self::Preset(_) if distance >= distances.max_distance => Decibels::SILENCE,
+            Self::Preset(preset) => {
+                // distances can be left unset, in which case 1m is as good a reference as any
+                let reference = distances.min_distance.max(1.);
+                let ratio = distance.max(reference) / reference;
+                Decibels(match preset {
+                    AttenuationPreset::Logarithmic => -20. * ratio.log10(),
+                    AttenuationPreset::InverseSquare => -40. * ratio.log10(),
+                })
+            }
+            Self::Points(points) => {
+                let factor = match points
+                    .iter()
+                    .position(|x| x.distance > distance)
+                    .unwrap_or(points.len())
+                {
+                    _ if points.is_empty() => 1.,
+                    0 => points[0].gain.as_factor(),
+                    i if i == points.len() => points[i - 1].gain.as_factor(),
+                    i => {
+                        let (before, after) = (points[i - 1], points[i]);
+                        let progress =
+                            (distance - before.distance) / (after.distance - before.distance);
+                        before.gain.as_factor()
+                            + (after.gain.as_factor() - before.gain.as_factor()) * progress
+                    }
+                };
+                factor_to_decibels(factor)
+            }
+        };
+        Decibels(gain.0.clamp(Decibels::SILENCE.0, Decibels::IDENTITY.0))
+    }
+}
+
+/// Gain eased from full volume at min distance, down to silence at max distance.
+pub fn eased_gain(easing: Easing, distance: f32, distances: SpatialTrackDistances) -> Decibels {
+    let range = distances.max_distance - distances.min_distance;
+    let relative = if range > 0. {
+        ((distance - distances.min_distance) / range).clamp(0., 1.)
+    } else if distance > distances.max_distance {
+        1.
+    } else {
+        0.
+    };
+    let volume = easing.apply(1. - relative as f64) as f32;
+    Decibels(Decibels::SILENCE.0 + (Decibels::IDENTITY.0 - Decibels::SILENCE.0) * volume)
+}
+
+#[cfg(test)]
+mod tests {
+    use kira::{Decibels, Easing, track::SpatialTrackDistances};
+    use test_case::test_case;
+
+    use super::{AttenuationCurve, AttenuationPoint, AttenuationPreset, eased_gain};
+    use crate::Amplitude;
+
+    const DISTANCES: SpatialTrackDistances = SpatialTrackDistances {
+        min_distance: 5.,
+        max_distance: 80.,
+    };
+
+    fn points() -> AttenuationCurve {
+        AttenuationCurve::Points(vec![
+            AttenuationPoint {
+                distance: 2.,
+                gain: Amplitude::try_from(1.).unwrap(),
+            },
+            AttenuationPoint {
+                distance: 10.,
+                gain: Amplitude::try_from(0.5).unwrap(),
+            },
+            AttenuationPoint {
+                distance: 20.,
+                gain: Amplitude::try_from(0.).unwrap(),
+            },
+        ])
+    }
+
+    #[test_case(AttenuationPreset::Logarithmic, 3., 0. ; "logarithmic within min distance")]
+    #[test_case(AttenuationPreset::Logarithmic, 10., -6.0206 ; "logarithmic at twice min distance")]
+    #[test_case(AttenuationPreset::Logarithmic, 20., -12.0412 ; "logarithmic at four times min distance")]
+    #[test_case(AttenuationPreset::InverseSquare, 10., -12.0412 ; "inverse square at twice min distance")]
+    #[test_case(AttenuationPreset::InverseSquare, 80., -60. ; "inverse square past max distance")]
+    fn presets(preset: AttenuationPreset, distance: f32, expected: f32) {
+        let gain = AttenuationCurve::Preset(preset).gain(distance, DISTANCES);
+        dbg!("{}", gain);
+        assert!((gain.0 - expected).abs() < 1e-3);
+    }
+
+    #[test_case(0., Decibels::IDENTITY.0 ; "before first point")]
+    #[test_case(6., -2.4988 ; "halfway between points")]
+    #[test_case(10., -6.0206 ; "on point")]
+    #[test_case(50., Decibels::SILENCE.0 ; "after last point")]
+    fn custom(distance: f32, expected: f32) {
+        let gain = points().gain(distance, DISTANCES);
+        dbg!("{}", gain);
+        assert!((gain.0 - expected).abs() < 1e-3);
+    }
+
+    #[test_case(3., Decibels::IDENTITY.0 ; "within min distance")]
+    #[test_case(42.5, -30. ; "halfway between distances")]
+    #[test_case(100., Decibels::SILENCE.0 ; "past max distance")]
+    fn eased(distance: f32, expected: f32) {
+        let gain = eased_gain(Easing::Linear, distance, DISTANCES);
+        dbg!("{}", gain);
+        assert!((gain.0 - expected).abs() < 1e-3);
+    }
+}
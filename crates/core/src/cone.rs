@@ -0,0 +1,103 @@
+//! Directional emitters.
+
+use kira::Decibels;
+
+use crate::{Amplitude, factor_to_decibels, log_cutoff};
+
+/// Directional emitter, louder in front.
+///
+/// Angles are full apertures (in degrees) around emitter forward,
+/// e.g. `90.0` means 45 degrees on either side.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Cone {
+    /// Within which volume is left untouched.
+    pub inner_angle: f32,
+    /// Beyond which volume is at outer gain.
+    pub outer_angle: f32,
+    pub outer_gain: Amplitude,
+    /// Low-pass cutoff (in Hz) beyond outer angle, if any.
+    pub outer_cutoff: Option<f64>,
+}
+
+impl Cone {
+    /// How far outwards listener stands at given angle (in degrees) from emitter forward,
+    /// from `0.0` within inner cone to `1.0` outside outer cone.
+    pub fn outwards(&self, angle: f32) -> f32 {
+        let inner = self.inner_angle.clamp(0., 360.) / 2.;
+        let outer = self.outer_angle.clamp(0., 360.) / 2.;
+        if angle <= inner {
+            0.
+        } else if angle >= outer {
+            1.
+        } else {
+            (angle - inner) / (outer - inner)
+        }
+    }
+    /// Gain at given angle (in degrees) from emitter forward.
+    pub fn gain(&self, angle: f32) -> Decibels {
+        factor_to_decibels(1. + (self.outer_gain.as_factor() - 1.) * self.outwards(angle))
+    }
+    /// Low-pass cutoff at given angle (in degrees) from emitter forward,
+    /// interpolated on a logarithmic scale.
+    pub fn cutoff(&self, angle: f32) -> Option<f64> {
+        Some(log_cutoff(self.outer_cutoff?, self.outwards(angle) as f64))
+    }
+}
+
+/// Angle (in degrees) between emitter forward and direction towards listener.
+pub fn angle_towards(forward: [f32; 3], emitter: [f32; 3], listener: [f32; 3]) -> f32 {
+    let towards = [
+        listener[0] - emitter[0],
+        listener[1] - emitter[1],
+        listener[2] - emitter[2],
+    ];
+    let norm = |x: [f32; 3]| (x[0] * x[0] + x[1] * x[1] + x[2] * x[2]).sqrt();
+    let lengths = norm(forward) * norm(towards);
+    // listener right on top of emitter (or orientation unknown)
+    if lengths <= f32::EPSILON {
+        return 0.;
+    }
+    let dot = forward[0] * towards[0] + forward[1] * towards[1] + forward[2] * towards[2];
+    (dot / lengths).clamp(-1., 1.).acos().to_degrees()
+}
+
+#[cfg(test)]
+mod tests {
+    use kira::Decibels;
+    use test_case::test_case;
+
+    use super::{Cone, angle_towards};
+    use crate::Amplitude;
+    use crate::OPEN_CUTOFF;
+
+    fn cone() -> Cone {
+        Cone {
+            inner_angle: 90.,
+            outer_angle: 270.,
+            outer_gain: Amplitude::try_from(0.25).unwrap(),
+            outer_cutoff: Some(2_000.),
+        }
+    }
+
+    #[test_case(0., Decibels::IDENTITY.0, OPEN_CUTOFF ; "in front")]
+    #[test_case(45., Decibels::IDENTITY.0, OPEN_CUTOFF ; "on inner edge")]
+    #[test_case(90., -4.0824, 6_324.555 ; "halfway outwards")]
+    #[test_case(180., -12.0412, 2_000. ; "behind")]
+    fn directional(angle: f32, gain: f32, cutoff: f64) {
+        let cone = cone();
+        let (actual_gain, actual_cutoff) = (cone.gain(angle), cone.cutoff(angle).unwrap());
+        dbg!("{}", (actual_gain, actual_cutoff));
+        assert!((actual_gain.0 - gain).abs() < 1e-3);
+        assert!((actual_cutoff - cutoff).abs() < 1e-2);
+    }
+
+    #[test_case([1., 0., 0.], [0., 0., 0.], [10., 0., 0.], 0. ; "facing listener")]
+    #[test_case([1., 0., 0.], [0., 0., 0.], [0., 5., 0.], 90. ; "listener aside")]
+    #[test_case([1., 0., 0.], [0., 0., 0.], [-3., 0., 0.], 180. ; "listener behind")]
+    #[test_case([1., 0., 0.], [0., 0., 0.], [0., 0., 0.], 0. ; "listener on top")]
+    fn angle(forward: [f32; 3], emitter: [f32; 3], listener: [f32; 3], expected: f32) {
+        let angle = angle_towards(forward, emitter, listener);
+        dbg!("{}", angle);
+        assert!((angle - expected).abs() < 1e-3);
+    }
+}
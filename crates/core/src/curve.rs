@@ -0,0 +1,63 @@
+//! Parameters varying with distance.
+
+/// Value linearly interpolated between points sorted by distance.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DistanceCurve(Vec<(f32, f32)>);
+
+impl DistanceCurve {
+    /// Points as distance (in meters) and value pairs.
+    ///
+    /// None unless non-empty with strictly increasing, positive distances.
+    pub fn new(points: Vec<(f32, f32)>) -> Option<Self> {
+        if points.is_empty()
+            || points.iter().any(|x| x.0 < 0.)
+            || points.windows(2).any(|x| x[0].0 >= x[1].0)
+        {
+            return None;
+        }
+        Some(Self(points))
+    }
+    /// Value at given distance, clamped to first and last points.
+    pub fn at(&self, distance: f32) -> f32 {
+        let points = &self.0;
+        match points.iter().position(|x| x.0 > distance) {
+            Some(0) => points[0].1,
+            None => points[points.len() - 1].1,
+            Some(i) => {
+                let (before, after) = (points[i - 1], points[i]);
+                let progress = (distance - before.0) / (after.0 - before.0);
+                before.1 + (after.1 - before.1) * progress
+            }
+        }
+    }
+    pub fn values(&self) -> impl Iterator<Item = f32> + '_ {
+        self.0.iter().map(|x| x.1)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use test_case::test_case;
+
+    use super::DistanceCurve;
+
+    #[test_case(0., 20_000. ; "before first point")]
+    #[test_case(30., 14_000. ; "halfway between points")]
+    #[test_case(60., 8_000. ; "on point")]
+    #[test_case(500., 2_000. ; "after last point")]
+    fn interpolated(distance: f32, expected: f32) {
+        let curve = DistanceCurve::new(vec![(0., 20_000.), (60., 8_000.), (200., 2_000.)]).unwrap();
+        let value = curve.at(distance);
+        dbg!("{}", value);
+        assert!((value - expected).abs() < 1e-3);
+    }
+
+    #[test_case(vec![] ; "without points")]
+    #[test_case(vec![(10., 0.5), (5., 0.2)] ; "out of order")]
+    #[test_case(vec![(-1., 0.5)] ; "negative distance")]
+    fn invalid(points: Vec<(f32, f32)>) {
+        let curve = DistanceCurve::new(points);
+        dbg!("{}", &curve);
+        assert!(curve.is_none());
+    }
+}
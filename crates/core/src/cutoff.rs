@@ -0,0 +1,28 @@
+//! Low-pass cutoffs.
+
+/// Low-pass cutoff where filter is inaudible, in Hz.
+pub const OPEN_CUTOFF: f64 = 20_000.;
+
+/// Low-pass cutoff (in Hz) going from open to closed as amount goes from `0.0` to `1.0`,
+/// interpolated on a logarithmic scale.
+pub fn log_cutoff(closed: f64, amount: f64) -> f64 {
+    let closed = closed.clamp(20., OPEN_CUTOFF);
+    OPEN_CUTOFF * (closed / OPEN_CUTOFF).powf(amount.clamp(0., 1.))
+}
+
+#[cfg(test)]
+mod tests {
+    use test_case::test_case;
+
+    use super::{OPEN_CUTOFF, log_cutoff};
+
+    #[test_case(0., OPEN_CUTOFF ; "open")]
+    #[test_case(0.5, 2_000. ; "halfway on a logarithmic scale")]
+    #[test_case(1., 200. ; "closed")]
+    #[test_case(2., 200. ; "more than closed")]
+    fn interpolated(amount: f64, expected: f64) {
+        let cutoff = log_cutoff(200., amount);
+        dbg!("{}", cutoff);
+        assert!((cutoff - expected).abs() < 1e-6);
+    }
+}
@@ -2,3 +2,5 @@
 
 mod r#static;
 mod streaming;
+
+pub use streaming::Probed;
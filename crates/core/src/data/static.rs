@@ -1,12 +1,14 @@
 use kira::{
     Tween,
     sound::{
-        PlaybackPosition,
+        EndPosition, PlaybackPosition, Region,
         static_sound::{StaticSoundData, StaticSoundSettings},
     },
 };
 
-use crate::{AudioDuration, SampleRate, With, settings::SceneDialogSettings};
+use crate::{
+    AudioDuration, SampleRate, TimeStretch, With, settings::SceneDialogSettings, time_stretch,
+};
 
 impl AudioDuration for StaticSoundData {
     fn slice_duration(&self) -> std::time::Duration {
@@ -60,3 +62,37 @@ impl With<SceneDialogSettings> for StaticSoundData {
         })
     }
 }
+
+impl With<TimeStretch> for StaticSoundData {
+    /// Stretch audio in time, along with positions already set
+    /// so that they still refer to the same audio.
+    fn with(mut self, settings: TimeStretch) -> Self
+    where
+        Self: Sized,
+    {
+        let TimeStretch(speed) = settings;
+        if speed <= 0. || speed == 1. {
+            return self;
+        }
+        let scale = |x: PlaybackPosition| match x {
+            PlaybackPosition::Seconds(x) => PlaybackPosition::Seconds(x / speed),
+            PlaybackPosition::Samples(x) => PlaybackPosition::Samples((x as f64 / speed) as usize),
+        };
+        self.frames = time_stretch(&self.frames, self.sample_rate, speed).into();
+        self.slice = self.slice.map(|(start, end)| {
+            (
+                (start as f64 / speed) as usize,
+                (end as f64 / speed) as usize,
+            )
+        });
+        self.settings.start_position = scale(self.settings.start_position);
+        self.settings.loop_region = self.settings.loop_region.map(|x| Region {
+            start: scale(x.start),
+            end: match x.end {
+                EndPosition::Custom(x) => EndPosition::Custom(scale(x)),
+                x => x,
+            },
+        });
+        self
+    }
+}
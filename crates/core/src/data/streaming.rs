@@ -1,3 +1,5 @@
+use std::path::Path;
+
 use kira::{
     Tween,
     sound::{
@@ -5,8 +7,11 @@ use kira::{
         streaming::{StreamingSoundData, StreamingSoundSettings},
     },
 };
+use symphonia::core::{
+    formats::FormatOptions, io::MediaSourceStream, meta::MetadataOptions, probe::Hint,
+};
 
-use crate::{AudioDuration, With, settings::SceneDialogSettings};
+use crate::{AudioDuration, SampleRate, With, settings::SceneDialogSettings};
 
 impl<T> AudioDuration for StreamingSoundData<T>
 where
@@ -52,14 +57,130 @@ impl<E: Send> With<SceneDialogSettings> for StreamingSoundData<E> {
     where
         Self: Sized,
     {
-        let given = self.settings.start_position;
-        self.start_position(match given {
+        self.start_position(match self.settings.start_position {
             PlaybackPosition::Seconds(x) => {
                 PlaybackPosition::Seconds(x + settings.seek_time as f64)
             }
-            PlaybackPosition::Samples(_) => {
-                unreachable!("samples unit is not supported with streaming sound yet")
-            }
+            // only left in samples when sample rate could not be probed
+            PlaybackPosition::Samples(x) => PlaybackPosition::Samples(x),
         })
     }
 }
+
+/// Header of streaming audio, probed once without decoding it.
+///
+/// [StreamingSoundData] does not expose its decoder sample rate.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Probed {
+    sample_rate: u32,
+}
+
+impl Probed {
+    pub fn from_file(path: impl AsRef<Path>) -> Option<Self> {
+        let path = path.as_ref();
+        let file = std::fs::File::open(path).ok()?;
+        let stream = MediaSourceStream::new(Box::new(file), Default::default());
+        let mut hint = Hint::new();
+        if let Some(extension) = path.extension().and_then(|x| x.to_str()) {
+            hint.with_extension(extension);
+        }
+        let probed = symphonia::default::get_probe()
+            .format(
+                &hint,
+                stream,
+                &FormatOptions::default(),
+                &MetadataOptions::default(),
+            )
+            .ok()?;
+        probed
+            .format
+            .default_track()?
+            .codec_params
+            .sample_rate
+            .filter(|x| *x > 0)
+            .map(|sample_rate| Self { sample_rate })
+    }
+}
+
+impl SampleRate for Probed {
+    #[inline]
+    fn sample_rate(&self) -> u32 {
+        self.sample_rate
+    }
+}
+
+/// Convert start position from samples to seconds.
+impl<E: Send> With<Probed> for StreamingSoundData<E> {
+    fn with(self, probed: Probed) -> Self
+    where
+        Self: Sized,
+    {
+        match self.settings.start_position {
+            PlaybackPosition::Samples(x) => self.start_position(PlaybackPosition::Seconds(
+                x as f64 / probed.sample_rate() as f64,
+            )),
+            PlaybackPosition::Seconds(_) => self,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use test_case::test_case;
+
+    use super::Probed;
+    use crate::SampleRate;
+
+    /// Minimal PCM WAV header followed by one second of silence.
+    fn wav(sample_rate: u32) -> Vec<u8> {
+        let data = vec![0u8; sample_rate as usize * 2];
+        let mut wav = Vec::with_capacity(44 + data.len());
+        wav.extend(b"RIFF");
+        wav.extend((36 + data.len() as u32).to_le_bytes());
+        wav.extend(b"WAVEfmt ");
+        wav.extend(16u32.to_le_bytes());
+        wav.extend(1u16.to_le_bytes()); // PCM
+        wav.extend(1u16.to_le_bytes()); // mono
+        wav.extend(sample_rate.to_le_bytes());
+        wav.extend((sample_rate * 2).to_le_bytes());
+        wav.extend(2u16.to_le_bytes());
+        wav.extend(16u16.to_le_bytes());
+        wav.extend(b"data");
+        wav.extend((data.len() as u32).to_le_bytes());
+        wav.extend(data);
+        wav
+    }
+
+    #[test_case(22_050 ; "22.05kHz")]
+    #[test_case(44_100 ; "44.1kHz")]
+    #[test_case(48_000 ; "48kHz")]
+    fn probe(sample_rate: u32) {
+        let path = std::env::temp_dir().join(format!("audioware-probe-{sample_rate}.wav"));
+        std::fs::write(&path, wav(sample_rate)).unwrap();
+        let probed = Probed::from_file(&path).map(|x| x.sample_rate());
+        let _ = std::fs::remove_file(&path);
+        assert_eq!(probed, Some(sample_rate));
+    }
+
+    #[test]
+    fn samples_to_seconds() {
+        use kira::sound::{PlaybackPosition, streaming::StreamingSoundData};
+
+        use crate::With;
+
+        let path = std::env::temp_dir().join("audioware-probe-samples.wav");
+        std::fs::write(&path, wav(44_100)).unwrap();
+        let probed = Probed::from_file(&path).unwrap();
+        let data = StreamingSoundData::from_file(&path)
+            .unwrap()
+            .start_position(PlaybackPosition::Samples(22_050))
+            .with(probed);
+        let _ = std::fs::remove_file(&path);
+        assert_eq!(data.settings.start_position, PlaybackPosition::Seconds(0.5));
+    }
+
+    #[test]
+    fn missing() {
+        assert_eq!(Probed::from_file("missing.wav"), None);
+    }
+}
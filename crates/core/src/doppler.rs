@@ -0,0 +1,154 @@
+//! Doppler shift of moving emitters and listener.
+
+use std::time::{Duration, Instant};
+
+use kira::{PlaybackRate, Value};
+
+/// Speed of sound in air, in m/s.
+const SPEED_OF_SOUND: f32 = 343.;
+/// Beyond which a move is considered a teleport, in m/s.
+const TELEPORT_SPEED: f32 = 250.;
+/// Beyond which samples are too far apart to be reliable,
+/// e.g. after game was paused or loaded.
+const STALE: Duration = Duration::from_millis(500);
+/// Time constant of velocity smoothing.
+const SMOOTHING: f32 = 0.1;
+
+/// Velocity tracked from successive positions.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct Motion {
+    last: Option<([f32; 3], Instant)>,
+    /// Smoothed velocity, in m/s.
+    pub velocity: [f32; 3],
+}
+
+impl Motion {
+    /// Update velocity with position sampled at given instant.
+    pub fn update(&mut self, position: [f32; 3], now: Instant) {
+        let Some((last, then)) = self.last.replace((position, now)) else {
+            return;
+        };
+        let elapsed = now.saturating_duration_since(then);
+        if elapsed.is_zero() {
+            return;
+        }
+        if elapsed > STALE {
+            self.velocity = [0.; 3];
+            return;
+        }
+        let dt = elapsed.as_secs_f32();
+        let sampled = [
+            (position[0] - last[0]) / dt,
+            (position[1] - last[1]) / dt,
+            (position[2] - last[2]) / dt,
+        ];
+        if length(sampled) > TELEPORT_SPEED {
+            self.velocity = [0.; 3];
+            return;
+        }
+        let alpha = 1. - (-dt / SMOOTHING).exp();
+        for (current, sampled) in self.velocity.iter_mut().zip(sampled) {
+            *current += (sampled - *current) * alpha;
+        }
+    }
+}
+
+/// Playback rate factor of sound emitted towards listener.
+///
+/// `factor` exaggerates (above `1.0`) or dampens (below `1.0`) the effect.
+pub fn doppler_shift(
+    emitter: [f32; 3],
+    emitter_velocity: [f32; 3],
+    listener: [f32; 3],
+    listener_velocity: [f32; 3],
+    factor: f32,
+) -> f64 {
+    let towards = [
+        listener[0] - emitter[0],
+        listener[1] - emitter[1],
+        listener[2] - emitter[2],
+    ];
+    let distance = length(towards);
+    if distance <= f32::EPSILON || factor <= 0. {
+        return 1.;
+    }
+    let direction = towards.map(|x| x / distance);
+    // keep away from speed of sound, where shift goes to infinity
+    let limit = SPEED_OF_SOUND / 2.;
+    let emitter_speed = (dot(emitter_velocity, direction) * factor).clamp(-limit, limit);
+    let listener_speed = (dot(listener_velocity, direction) * factor).clamp(-limit, limit);
+    ((SPEED_OF_SOUND - listener_speed) / (SPEED_OF_SOUND - emitter_speed)) as f64
+}
+
+/// Playback rate sound was played with, before any shift: `1.0` unless fixed.
+pub fn base_playback_rate(rate: Value<PlaybackRate>) -> f64 {
+    match rate {
+        Value::Fixed(x) if x.0 > 0. => x.0,
+        _ => 1.,
+    }
+}
+
+/// Playback rate of sound shifted by time dilation and Doppler effect,
+/// on top of its own base rate.
+pub fn shifted_playback_rate(base: f64, dilation: f64, doppler: f64) -> f64 {
+    base * dilation * doppler
+}
+
+fn dot(lhs: [f32; 3], rhs: [f32; 3]) -> f32 {
+    lhs[0] * rhs[0] + lhs[1] * rhs[1] + lhs[2] * rhs[2]
+}
+
+fn length(x: [f32; 3]) -> f32 {
+    dot(x, x).sqrt()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::{Duration, Instant};
+
+    use kira::{PlaybackRate, Semitones, Value};
+    use test_case::test_case;
+
+    use super::{Motion, base_playback_rate, doppler_shift, shifted_playback_rate};
+
+    #[test_case([20., 0., 0.], [0., 0., 0.], 1.0619 ; "emitter approaching")]
+    #[test_case([-20., 0., 0.], [0., 0., 0.], 0.9449 ; "emitter receding")]
+    #[test_case([0., 0., 0.], [-20., 0., 0.], 1.0583 ; "listener approaching")]
+    #[test_case([0., 20., 0.], [0., 0., 0.], 1. ; "emitter passing by")]
+    fn shift(emitter_velocity: [f32; 3], listener_velocity: [f32; 3], expected: f64) {
+        let shift = doppler_shift(
+            [0., 0., 0.],
+            emitter_velocity,
+            [10., 0., 0.],
+            listener_velocity,
+            1.,
+        );
+        dbg!("{}", shift);
+        assert!((shift - expected).abs() < 1e-3);
+    }
+
+    #[test_case(&[[0., 0., 0.], [0.15, 0., 0.], [0.3, 0., 0.]], 15, true ; "steady move")]
+    #[test_case(&[[0., 0., 0.], [0.15, 0., 0.], [500., 0., 0.]], 15, false ; "teleport")]
+    #[test_case(&[[0., 0., 0.], [10., 0., 0.]], 1000, false ; "stale samples")]
+    fn motion(positions: &[[f32; 3]], every: u64, moving: bool) {
+        let start = Instant::now();
+        let mut motion = Motion::default();
+        for (i, position) in positions.iter().enumerate() {
+            motion.update(*position, start + Duration::from_millis(every * i as u64));
+        }
+        dbg!("{}", &motion);
+        assert_eq!(motion.velocity[0] > 0., moving);
+    }
+
+    #[test_case(1. ; "standing still")]
+    #[test_case(1.0619 ; "emitter approaching")]
+    #[test_case(0.9449 ; "emitter receding")]
+    fn keeps_own_pitch(doppler: f64) {
+        // e.g. playback_rate: 2♯
+        let base = base_playback_rate(Value::Fixed(PlaybackRate::from(Semitones(2.))));
+        let rate = shifted_playback_rate(base, 1., doppler);
+        dbg!("{}", rate);
+        assert!((base - 2f64.powf(2. / 12.)).abs() < 1e-9);
+        assert!((rate / base - doppler).abs() < 1e-9);
+    }
+}
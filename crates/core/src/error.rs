@@ -1,8 +1,10 @@
+use std::borrow::Cow;
+
 use snafu::Snafu;
 
 #[derive(Debug, Snafu)]
 #[snafu(display("invalid audio setting {which}: {why}"), visibility(pub))]
 pub struct ValidationError {
     pub which: &'static str,
-    pub why: &'static str,
+    pub why: Cow<'static, str>,
 }
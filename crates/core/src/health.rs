@@ -0,0 +1,178 @@
+//! Common audio assets problems, usually due to a bad export.
+
+use std::{fmt, time::Duration};
+
+use kira::Frame;
+
+/// Samples at or above this amplitude are considered clipped.
+const CLIPPING: f32 = 0.999;
+/// Consecutive clipped samples required to report clipping.
+const CLIPPING_RUN: usize = 3;
+/// Samples below this amplitude (-60 dBFS) are considered silent.
+const SILENCE: f32 = 0.001;
+/// Leading or trailing silence longer than this is reported.
+const SILENCE_DURATION: Duration = Duration::from_millis(250);
+/// Mean amplitude above this (-40 dBFS) is reported as DC offset.
+const DC_OFFSET: f32 = 0.01;
+
+/// Audio asset problem.
+#[derive(Debug, Clone, PartialEq)]
+pub enum AudioIssue {
+    /// Some samples are clipped.
+    Clipping { samples: usize },
+    /// Audio starts with silence.
+    LeadingSilence { duration: Duration },
+    /// Audio ends with silence.
+    TrailingSilence { duration: Duration },
+    /// Waveform is not centered around zero.
+    DcOffset { offset: f32 },
+    /// Audio will be resampled on playback.
+    SampleRateMismatch { audio: u32, output: u32 },
+    /// Audio is stereo where mono is expected.
+    UnexpectedStereo,
+    /// Audio is too short or too quiet to measure its loudness, left unnormalized.
+    Unmeasurable,
+    /// Streaming audio is not decoded on load, left unnormalized.
+    StreamingUnnormalized,
+}
+
+impl fmt::Display for AudioIssue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Clipping { samples } => write!(f, "{samples} clipped sample(s)"),
+            Self::LeadingSilence { duration } => write!(f, "leading silence of {duration:?}"),
+            Self::TrailingSilence { duration } => write!(f, "trailing silence of {duration:?}"),
+            Self::DcOffset { offset } => write!(f, "DC offset of {offset:.3}"),
+            Self::SampleRateMismatch { audio, output } => write!(
+                f,
+                "sample rate of {audio}Hz does not match output device ({output}Hz)"
+            ),
+            Self::UnexpectedStereo => write!(f, "stereo audio where mono is expected"),
+            Self::Unmeasurable => write!(f, "loudness cannot be measured, left unnormalized"),
+            Self::StreamingUnnormalized => {
+                write!(f, "streaming audio cannot be normalized, left as-is")
+            }
+        }
+    }
+}
+
+/// Diagnose problems found in frames alone.
+pub fn diagnose(frames: &[Frame], sample_rate: u32) -> Vec<AudioIssue> {
+    let mut issues = Vec::new();
+    if frames.is_empty() || sample_rate == 0 {
+        return issues;
+    }
+    let amplitude = |x: &Frame| x.left.abs().max(x.right.abs());
+
+    let (mut clipped, mut run) = (0, 0);
+    for sample in frames.iter().flat_map(|x| [x.left, x.right]) {
+        if sample.abs() >= CLIPPING {
+            run += 1;
+        } else {
+            if run >= CLIPPING_RUN {
+                clipped += run;
+            }
+            run = 0;
+        }
+    }
+    if run >= CLIPPING_RUN {
+        clipped += run;
+    }
+    if clipped > 0 {
+        issues.push(AudioIssue::Clipping { samples: clipped });
+    }
+
+    let duration = |frames: usize| Duration::from_secs_f64(frames as f64 / sample_rate as f64);
+    let leading = frames.iter().take_while(|x| amplitude(x) < SILENCE).count();
+    // entirely silent audio is only reported once
+    if leading == frames.len() {
+        issues.push(AudioIssue::LeadingSilence {
+            duration: duration(leading),
+        });
+        return issues;
+    }
+    if duration(leading) > SILENCE_DURATION {
+        issues.push(AudioIssue::LeadingSilence {
+            duration: duration(leading),
+        });
+    }
+    let trailing = frames
+        .iter()
+        .rev()
+        .take_while(|x| amplitude(x) < SILENCE)
+        .count();
+    if duration(trailing) > SILENCE_DURATION {
+        issues.push(AudioIssue::TrailingSilence {
+            duration: duration(trailing),
+        });
+    }
+
+    let (left, right) = frames.iter().fold((0., 0.), |(l, r), x| {
+        (l + x.left as f64, r + x.right as f64)
+    });
+    let offset = (left / frames.len() as f64)
+        .abs()
+        .max((right / frames.len() as f64).abs()) as f32;
+    if offset > DC_OFFSET {
+        issues.push(AudioIssue::DcOffset { offset });
+    }
+    issues
+}
+
+/// Whether frames actually hold different channels.
+pub fn is_stereo(frames: &[Frame]) -> bool {
+    frames.iter().any(|x| x.left != x.right)
+}
+
+#[cfg(test)]
+mod tests {
+    use kira::Frame;
+    use test_case::test_case;
+
+    use super::{AudioIssue, diagnose};
+
+    const RATE: u32 = 1_000;
+
+    fn sine(amplitude: f32, offset: f32) -> impl Iterator<Item = Frame> {
+        (0..RATE).map(move |i| {
+            Frame::from_mono(
+                offset
+                    + amplitude * (2. * std::f32::consts::PI * 10. * i as f32 / RATE as f32).sin(),
+            )
+        })
+    }
+
+    fn silence(frames: usize) -> impl Iterator<Item = Frame> {
+        std::iter::repeat_n(Frame::ZERO, frames)
+    }
+
+    #[test]
+    fn healthy() {
+        let frames = sine(0.5, 0.).collect::<Vec<_>>();
+        assert_eq!(diagnose(&frames, RATE), vec![]);
+    }
+
+    #[test_case(sine(2.0, 0.).collect() ; "clipping")]
+    #[test_case(silence(500).chain(sine(0.5, 0.)).collect() ; "leading silence")]
+    #[test_case(sine(0.5, 0.).chain(silence(500)).collect() ; "trailing silence")]
+    #[test_case(sine(0.5, 0.1).collect() ; "dc offset")]
+    #[test_case(silence(500).collect() ; "only silence")]
+    fn unhealthy(frames: Vec<Frame>) {
+        let issues = diagnose(&frames, RATE);
+        dbg!("{}", &issues);
+        assert_eq!(issues.len(), 1);
+    }
+
+    #[test]
+    fn short_silences_are_fine() {
+        let frames = silence(100)
+            .chain(sine(0.5, 0.))
+            .chain(silence(100))
+            .collect::<Vec<_>>();
+        assert!(
+            !diagnose(&frames, RATE)
+                .iter()
+                .any(|x| matches!(x, AudioIssue::LeadingSilence { .. }))
+        );
+    }
+}
@@ -9,14 +9,38 @@ use kira::{
     },
 };
 
+mod attenuation;
+mod cone;
+mod curve;
+mod cutoff;
 mod data;
+mod doppler;
+mod loudness;
+mod occlusion;
+mod pitch;
 mod settings;
+mod stretch;
 mod types;
+mod virtualization;
 
+pub use attenuation::{AttenuationCurve, AttenuationPoint, AttenuationPreset, eased_gain};
+pub use cone::{Cone, angle_towards};
+pub use curve::DistanceCurve;
+pub use cutoff::{OPEN_CUTOFF, log_cutoff};
 pub mod error;
+pub use data::Probed;
+pub use doppler::{Motion, base_playback_rate, doppler_shift, shifted_playback_rate};
+pub mod health;
+pub use loudness::Loudness;
+pub use occlusion::{Occluded, OcclusionModel, OcclusionProvider};
+pub use pitch::{PitchShiftBuilder, PitchShiftHandle};
 pub use settings::SceneDialogSettings;
 pub use settings::SpatialTrackSettings;
-pub use types::{Amplitude, AmplitudeError, Panning, PanningError};
+pub use stretch::{TimeStretch, time_stretch};
+pub use types::{
+    Amplitude, AmplitudeError, Panning, PanningError, base_volume, factor_to_decibels,
+};
+pub use virtualization::{Audibility, TAKEOVER_MARGIN, advance};
 
 /// Consumes `T`, absorbing its data.
 pub trait With<T> {
@@ -72,6 +96,18 @@ where
     }
 }
 
+impl<E: Send + 'static> With<Tween> for Either<StaticSoundData, StreamingSoundData<E>> {
+    fn with(self, settings: Tween) -> Self
+    where
+        Self: Sized,
+    {
+        match self {
+            Self::Left(x) => Self::Left(x.with(settings)),
+            Self::Right(x) => Self::Right(x.with(settings)),
+        }
+    }
+}
+
 impl<E: Send> With<SceneDialogSettings> for Either<StaticSoundData, StreamingSoundData<E>> {
     fn with(self, settings: SceneDialogSettings) -> Self
     where
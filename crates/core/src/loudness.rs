@@ -0,0 +1,249 @@
+//! Loudness measurement, as described in [ITU-R BS.1770](https://www.itu.int/rec/R-REC-BS.1770).
+
+use kira::{Decibels, Frame};
+
+/// Gating block duration, in seconds.
+const BLOCK: f64 = 0.4;
+/// Gating block overlap.
+const OVERLAP: f64 = 0.75;
+/// Absolute gate, in LUFS.
+const ABSOLUTE_GATE: f64 = -70.;
+/// Relative gate, in LU.
+const RELATIVE_GATE: f64 = -10.;
+/// True peak oversampling factor.
+const OVERSAMPLING: usize = 4;
+/// Taps per oversampling phase.
+const TAPS: usize = 12;
+
+/// Integrated loudness and true peak of some audio.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Loudness {
+    /// Integrated loudness, in LUFS.
+    pub integrated: f32,
+    /// True peak, in dBTP.
+    pub true_peak: f32,
+}
+
+impl Loudness {
+    /// Measure loudness of given frames.
+    ///
+    /// Frames whose channels are identical are measured as mono.
+    ///
+    /// Returns `None` if audio is silent (below absolute gate).
+    pub fn measure(frames: &[Frame], sample_rate: u32) -> Option<Self> {
+        if frames.is_empty() || sample_rate == 0 {
+            return None;
+        }
+        let integrated = integrated(frames, sample_rate)?;
+        Some(Self {
+            integrated: integrated as f32,
+            true_peak: true_peak(frames) as f32,
+        })
+    }
+
+    /// Gain required to reach `target` (in LUFS),
+    /// without true peak exceeding `ceiling` (in dBTP).
+    pub fn gain_to(&self, target: f32, ceiling: f32) -> Decibels {
+        Decibels((target - self.integrated).min(ceiling - self.true_peak))
+    }
+}
+
+/// Second-order IIR filter, in direct form I.
+#[derive(Debug, Clone, Copy, Default)]
+struct Biquad {
+    b: [f64; 3],
+    a: [f64; 2],
+    x: [f64; 2],
+    y: [f64; 2],
+}
+
+impl Biquad {
+    fn process(&mut self, x: f64) -> f64 {
+        let y = self.b[0] * x + self.b[1] * self.x[0] + self.b[2] * self.x[1]
+            - self.a[0] * self.y[0]
+            - self.a[1] * self.y[1];
+        self.x = [x, self.x[0]];
+        self.y = [y, self.y[0]];
+        y
+    }
+}
+
+/// K-weighting filter: high shelf followed by high pass.
+#[derive(Debug, Clone, Copy)]
+struct KWeighting([Biquad; 2]);
+
+impl KWeighting {
+    fn new(sample_rate: u32) -> Self {
+        let rate = sample_rate as f64;
+        // high shelf
+        let (f0, gain, q) = (1681.974450955533, 3.999843853973347, 0.7071752369554196);
+        let k = (std::f64::consts::PI * f0 / rate).tan();
+        let vh = 10f64.powf(gain / 20.);
+        let vb = vh.powf(0.4996667741545416);
+        let a0 = 1. + k / q + k * k;
+        let shelf = Biquad {
+            b: [
+                (vh + vb * k / q + k * k) / a0,
+                2. * (k * k - vh) / a0,
+                (vh - vb * k / q + k * k) / a0,
+            ],
+            a: [2. * (k * k - 1.) / a0, (1. - k / q + k * k) / a0],
+            ..Default::default()
+        };
+        // high pass
+        let (f0, q) = (38.13547087602444, 0.5003270373238773);
+        let k = (std::f64::consts::PI * f0 / rate).tan();
+        let a0 = 1. + k / q + k * k;
+        let pass = Biquad {
+            b: [1., -2., 1.],
+            a: [2. * (k * k - 1.) / a0, (1. - k / q + k * k) / a0],
+            ..Default::default()
+        };
+        Self([shelf, pass])
+    }
+    fn process(&mut self, x: f64) -> f64 {
+        let x = self.0[0].process(x);
+        self.0[1].process(x)
+    }
+}
+
+fn integrated(frames: &[Frame], sample_rate: u32) -> Option<f64> {
+    let mono = frames.iter().all(|x| x.left == x.right);
+    let mut left = KWeighting::new(sample_rate);
+    let mut right = KWeighting::new(sample_rate);
+    // squared K-weighted samples, summed over channels
+    let squared = frames
+        .iter()
+        .map(|x| {
+            let l = left.process(x.left as f64);
+            if mono {
+                return l * l;
+            }
+            let r = right.process(x.right as f64);
+            l * l + r * r
+        })
+        .collect::<Vec<_>>();
+    let size = ((BLOCK * sample_rate as f64) as usize).clamp(1, squared.len());
+    let step = ((size as f64 * (1. - OVERLAP)) as usize).max(1);
+    let blocks = (0..=(squared.len() - size))
+        .step_by(step)
+        .map(|start| squared[start..start + size].iter().sum::<f64>() / size as f64)
+        .filter(|x| loudness(*x) > ABSOLUTE_GATE)
+        .collect::<Vec<_>>();
+    if blocks.is_empty() {
+        return None;
+    }
+    let gate = loudness(blocks.iter().sum::<f64>() / blocks.len() as f64) + RELATIVE_GATE;
+    let gated = blocks
+        .into_iter()
+        .filter(|x| loudness(*x) > gate)
+        .collect::<Vec<_>>();
+    Some(loudness(gated.iter().sum::<f64>() / gated.len() as f64))
+}
+
+#[inline]
+fn loudness(mean_square: f64) -> f64 {
+    -0.691 + 10. * mean_square.log10()
+}
+
+/// Estimate true peak by oversampling with a windowed sinc interpolator.
+///
+/// Inter-sample peaks are only looked for around samples above half sample peak,
+/// since they cannot exceed it by much.
+fn true_peak(frames: &[Frame]) -> f64 {
+    let sample_peak = frames
+        .iter()
+        .map(|x| x.left.abs().max(x.right.abs()) as f64)
+        .fold(0., f64::max);
+    let half = TAPS / 2;
+    // coefficients[phase][tap], for positions between samples
+    let coefficients = (1..OVERSAMPLING)
+        .map(|phase| {
+            (0..TAPS)
+                .map(|tap| {
+                    let t = phase as f64 / OVERSAMPLING as f64 - (tap as f64 - (half - 1) as f64);
+                    let sinc = (std::f64::consts::PI * t).sin() / (std::f64::consts::PI * t);
+                    let window = 0.5 + 0.5 * (std::f64::consts::PI * t / half as f64).cos();
+                    sinc * window
+                })
+                .collect::<Vec<_>>()
+        })
+        .collect::<Vec<_>>();
+    let mut peak = sample_peak;
+    for i in (half - 1)..frames.len().saturating_sub(half) {
+        let candidate = [&frames[i], &frames[i + 1]]
+            .iter()
+            .any(|x| x.left.abs().max(x.right.abs()) as f64 >= sample_peak / 2.);
+        if !candidate {
+            continue;
+        }
+        for phase in coefficients.iter() {
+            let (mut left, mut right) = (0., 0.);
+            for (tap, coefficient) in phase.iter().enumerate() {
+                let frame = &frames[i + tap + 1 - half];
+                left += coefficient * frame.left as f64;
+                right += coefficient * frame.right as f64;
+            }
+            peak = peak.max(left.abs()).max(right.abs());
+        }
+    }
+    20. * peak.log10()
+}
+
+#[cfg(test)]
+mod tests {
+    use kira::Frame;
+    use test_case::test_case;
+
+    use super::Loudness;
+
+    const RATE: u32 = 48_000;
+
+    fn sine(amplitude: f32, frequency: f32, seconds: f32, stereo: bool) -> Vec<Frame> {
+        (0..(seconds * RATE as f32) as usize)
+            .map(|i| {
+                let x = amplitude
+                    * (2. * std::f32::consts::PI * frequency * i as f32 / RATE as f32).sin();
+                if stereo {
+                    Frame::new(x, 0.)
+                } else {
+                    Frame::from_mono(x)
+                }
+            })
+            .collect()
+    }
+
+    #[test_case(1.0, false, -3.01 ; "mono full scale sine")]
+    #[test_case(0.1, false, -23.01 ; "mono sine at minus 20 dBFS")]
+    #[test_case(0.1, true, -23.01 ; "sine at minus 20 dBFS on a single channel")]
+    #[test_case(0.01, false, -43.01 ; "mono sine at minus 40 dBFS")]
+    fn integrated(amplitude: f32, stereo: bool, expected: f32) {
+        let loudness = Loudness::measure(&sine(amplitude, 997., 3., stereo), RATE);
+        dbg!("{}", &loudness);
+        assert!((loudness.unwrap().integrated - expected).abs() < 0.1);
+    }
+
+    #[test_case(1.0, 0.0 ; "full scale sine")]
+    #[test_case(0.5, -6.02 ; "sine at minus 6 dBFS")]
+    fn true_peak(amplitude: f32, expected: f32) {
+        let loudness = Loudness::measure(&sine(amplitude, 997., 1., false), RATE);
+        dbg!("{}", &loudness);
+        assert!((loudness.unwrap().true_peak - expected).abs() < 0.1);
+    }
+
+    #[test]
+    fn silence() {
+        let frames = vec![Frame::ZERO; RATE as usize];
+        assert!(Loudness::measure(&frames, RATE).is_none());
+    }
+
+    #[test_case(-23.0, -1.0, -5.0 ; "limited by target")]
+    #[test_case(-3.0, -1.0, -1.0 ; "limited by ceiling")]
+    fn gain_to(target: f32, ceiling: f32, expected: f32) {
+        let loudness = Loudness {
+            integrated: -18.0,
+            true_peak: 0.0,
+        };
+        assert_eq!(loudness.gain_to(target, ceiling).0, expected);
+    }
+}
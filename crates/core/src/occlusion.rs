@@ -0,0 +1,124 @@
+//! Occlusion between emitters and listener.
+
+use std::time::{Duration, Instant};
+
+use kira::Decibels;
+
+use crate::{Amplitude, amplitude, factor_to_decibels, log_cutoff};
+
+/// Measures how much emitters are occluded from listener,
+/// between `0.0` (clear) and `1.0` (fully occluded),
+/// e.g. from game raycasts or synthetic geometry.
+pub trait OcclusionProvider<K> {
+    /// Current occlusion of given emitter, if known.
+    fn occlusion(&mut self, emitter: &K, position: [f32; 3], listener: [f32; 3]) -> Option<f32>;
+    /// Occlusion reported from elsewhere, e.g. game audio engine.
+    fn report(&mut self, _emitter: &K, _occlusion: f32) {}
+    /// Emitter is gone.
+    fn forget(&mut self, _emitter: &K) {}
+}
+
+/// How occlusion affects sound: volume and low-pass cutoff once fully occluded,
+/// interpolated from none when clear.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct OcclusionModel {
+    pub volume: Amplitude,
+    /// In Hz.
+    pub cutoff: f64,
+    /// Time constant of occlusion changes.
+    pub smoothing: Duration,
+}
+
+impl Default for OcclusionModel {
+    /// Only dulls sound, leaving its volume untouched.
+    fn default() -> Self {
+        Self {
+            volume: amplitude!(1.0),
+            cutoff: 600.,
+            smoothing: Duration::from_millis(150),
+        }
+    }
+}
+
+/// Occlusion smoothed over time.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct Occluded {
+    pub current: f32,
+    last: Option<Instant>,
+}
+
+impl Occluded {
+    /// Move towards occlusion measured at given instant.
+    pub fn update(&mut self, target: f32, now: Instant, model: &OcclusionModel) {
+        let target = target.clamp(0., 1.);
+        let Some(then) = self.last.replace(now) else {
+            self.current = target;
+            return;
+        };
+        let elapsed = now.saturating_duration_since(then).as_secs_f32();
+        let smoothing = model.smoothing.as_secs_f32();
+        let alpha = if smoothing > 0. {
+            1. - (-elapsed / smoothing).exp()
+        } else {
+            1.
+        };
+        self.current += (target - self.current) * alpha;
+    }
+    pub fn gain(&self, model: &OcclusionModel) -> Decibels {
+        factor_to_decibels(1. + (model.volume.as_factor() - 1.) * self.current)
+    }
+    /// Interpolated on a logarithmic scale.
+    pub fn cutoff(&self, model: &OcclusionModel) -> f64 {
+        log_cutoff(model.cutoff, self.current as f64)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::{Duration, Instant};
+
+    use test_case::test_case;
+
+    use super::{Occluded, OcclusionModel, OcclusionProvider};
+    use crate::{Amplitude, amplitude};
+
+    /// Everything past `x = 10` is another room.
+    struct Geometry;
+
+    impl OcclusionProvider<u32> for Geometry {
+        fn occlusion(&mut self, _: &u32, position: [f32; 3], listener: [f32; 3]) -> Option<f32> {
+            let crosses = |x: f32| (position[0] - x) * (listener[0] - x) < 0.;
+            Some(if crosses(10.) { 1. } else { 0. })
+        }
+    }
+
+    #[test_case([2., 0., 0.], 1.0, 0., 20_000. ; "clear")]
+    #[test_case([12., 0., 0.], 1.0, 0., 600. ; "occluded leaves volume untouched by default")]
+    #[test_case([12., 0., 0.], 0.25, -12.0412, 600. ; "occluded with lowered volume")]
+    fn geometry(position: [f32; 3], volume: f32, gain: f32, cutoff: f64) {
+        let model = OcclusionModel {
+            volume: amplitude!(volume),
+            ..Default::default()
+        };
+        let mut occluded = Occluded::default();
+        let occlusion = Geometry.occlusion(&0, position, [0., 0., 0.]).unwrap();
+        occluded.update(occlusion, Instant::now(), &model);
+        let (actual_gain, actual_cutoff) = (occluded.gain(&model), occluded.cutoff(&model));
+        dbg!("{}", (actual_gain, actual_cutoff));
+        assert!((actual_gain.0 - gain).abs() < 1e-3);
+        assert!((actual_cutoff - cutoff).abs() < 1e-2);
+    }
+
+    #[test_case(15, 0.0952 ; "barely started")]
+    #[test_case(150, 0.6321 ; "one time constant")]
+    #[test_case(1500, 0.9999 ; "settled")]
+    fn smoothing(after: u64, expected: f32) {
+        let model = OcclusionModel::default();
+        let start = Instant::now();
+        let mut occluded = Occluded::default();
+        occluded.update(0., start, &model);
+        occluded.update(1., start + Duration::from_millis(after), &model);
+        dbg!("{}", &occluded);
+        assert!((occluded.current - expected).abs() < 1e-3);
+    }
+}
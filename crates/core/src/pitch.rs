@@ -0,0 +1,259 @@
+//! Real-time pitch shift, as a [kira] effect.
+//!
+//! Audio is read back from a short delay line by two taps whose delay
+//! drifts with the pitch ratio, each faded out whenever it wraps around.
+
+use std::sync::{
+    Arc,
+    atomic::{AtomicU64, Ordering},
+};
+
+use kira::{
+    Frame, Tween,
+    effect::{Effect, EffectBuilder},
+    info::Info,
+};
+
+/// Delay line duration, in seconds.
+const WINDOW: f64 = 0.05;
+/// Below this distance to `1.0`, ratio is considered neutral and effect is bypassed.
+const NEUTRAL: f64 = 1e-3;
+
+#[derive(Debug)]
+struct Shared {
+    /// Target ratio, as `f64` bits.
+    ratio: AtomicU64,
+    /// Time to reach target ratio (in seconds), as `f64` bits.
+    duration: AtomicU64,
+}
+
+/// Builds a [PitchShiftHandle].
+#[derive(Debug, Clone, Copy)]
+pub struct PitchShiftBuilder {
+    /// Pitch ratio, e.g. `2.0` for an octave higher.
+    pub ratio: f64,
+}
+
+impl Default for PitchShiftBuilder {
+    fn default() -> Self {
+        Self { ratio: 1. }
+    }
+}
+
+impl EffectBuilder for PitchShiftBuilder {
+    type Handle = PitchShiftHandle;
+
+    fn build(self) -> (Box<dyn Effect>, Self::Handle) {
+        let shared = Arc::new(Shared {
+            ratio: AtomicU64::new(self.ratio.to_bits()),
+            duration: AtomicU64::new(0f64.to_bits()),
+        });
+        (
+            Box::new(PitchShift::new(self.ratio, shared.clone())),
+            PitchShiftHandle { shared },
+        )
+    }
+}
+
+/// Controls a pitch shift effect.
+#[derive(Debug)]
+pub struct PitchShiftHandle {
+    shared: Arc<Shared>,
+}
+
+impl PitchShiftHandle {
+    /// Linearly ramp pitch ratio over `tween` duration.
+    pub fn set_ratio(&mut self, ratio: f64, tween: Tween) {
+        self.shared
+            .duration
+            .store(tween.duration.as_secs_f64().to_bits(), Ordering::Relaxed);
+        self.shared.ratio.store(ratio.to_bits(), Ordering::Relaxed);
+    }
+}
+
+struct PitchShift {
+    shared: Arc<Shared>,
+    shifter: Shifter,
+    /// Ratio ramped towards.
+    target: f64,
+    /// Remaining time to reach target ratio, in seconds.
+    remaining: f64,
+}
+
+impl PitchShift {
+    fn new(ratio: f64, shared: Arc<Shared>) -> Self {
+        Self {
+            shared,
+            shifter: Shifter::new(ratio),
+            target: ratio,
+            remaining: 0.,
+        }
+    }
+}
+
+impl Effect for PitchShift {
+    fn init(&mut self, sample_rate: u32, _: usize) {
+        self.shifter.resize(sample_rate);
+    }
+
+    fn on_change_sample_rate(&mut self, sample_rate: u32) {
+        self.shifter.resize(sample_rate);
+    }
+
+    fn on_start_processing(&mut self) {
+        let target = f64::from_bits(self.shared.ratio.load(Ordering::Relaxed));
+        if target != self.target {
+            self.target = target;
+            self.remaining = f64::from_bits(self.shared.duration.load(Ordering::Relaxed));
+        }
+    }
+
+    fn process(&mut self, input: &mut [Frame], dt: f64, _: &Info) {
+        for frame in input.iter_mut() {
+            if self.remaining > dt {
+                self.shifter.ratio += (self.target - self.shifter.ratio) * dt / self.remaining;
+                self.remaining -= dt;
+            } else {
+                self.shifter.ratio = self.target;
+                self.remaining = 0.;
+            }
+            *frame = self.shifter.next(*frame);
+        }
+    }
+}
+
+/// Pitch shift DSP, regardless of [kira] plumbing.
+#[derive(Debug, Clone)]
+struct Shifter {
+    ratio: f64,
+    /// Delay line.
+    buffer: Vec<Frame>,
+    write: usize,
+    /// Delay line length, in samples.
+    window: f64,
+    /// Normalized delay of first tap, second one is half a window apart.
+    phase: f64,
+    /// Wet amount, faded in and out when ratio (un)becomes neutral.
+    wet: f32,
+}
+
+impl Shifter {
+    fn new(ratio: f64) -> Self {
+        Self {
+            ratio,
+            buffer: vec![],
+            write: 0,
+            window: 0.,
+            phase: 0.,
+            wet: if (ratio - 1.).abs() > NEUTRAL { 1. } else { 0. },
+        }
+    }
+
+    fn resize(&mut self, sample_rate: u32) {
+        self.window = (WINDOW * sample_rate as f64).max(4.);
+        self.buffer = vec![Frame::ZERO; self.window as usize + 2];
+        self.write = 0;
+    }
+
+    /// Read from delay line, `delay` samples behind last written one.
+    fn tap(&self, delay: f64) -> Frame {
+        let len = self.buffer.len();
+        let position = (self.write + len) as f64 - delay;
+        let fraction = position.fract() as f32;
+        let before = self.buffer[position.floor() as usize % len];
+        let after = self.buffer[(position.floor() as usize + 1) % len];
+        before + (after - before) * fraction
+    }
+
+    fn next(&mut self, input: Frame) -> Frame {
+        if self.buffer.is_empty() {
+            return input;
+        }
+        let neutral = (self.ratio - 1.).abs() <= NEUTRAL;
+        let fade = 1. / self.window as f32;
+        self.wet = if neutral {
+            (self.wet - fade).max(0.)
+        } else {
+            (self.wet + fade).min(1.)
+        };
+        self.buffer[self.write] = input;
+        let output = if self.wet > 0. {
+            let first = self.phase;
+            let second = (self.phase + 0.5).fract();
+            // gains are zero whenever tap wraps around, and always sum to 1
+            let gain = |x: f64| (std::f64::consts::PI * x).sin().powi(2) as f32;
+            let shifted = self.tap(first * (self.window - 1.)) * gain(first)
+                + self.tap(second * (self.window - 1.)) * gain(second);
+            input * (1. - self.wet) + shifted * self.wet
+        } else {
+            input
+        };
+        // read taps move at `ratio` speed while writing moves at `1.0`
+        self.phase = (self.phase + (1. - self.ratio) / (self.window - 1.)).rem_euclid(1.);
+        self.write = (self.write + 1) % self.buffer.len();
+        output
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use kira::Frame;
+    use test_case::test_case;
+
+    use super::Shifter;
+
+    const RATE: u32 = 48_000;
+
+    fn sine(frequency: f32, seconds: f32) -> Vec<Frame> {
+        (0..(seconds * RATE as f32) as usize)
+            .map(|i| {
+                Frame::from_mono(
+                    0.5 * (2. * std::f32::consts::PI * frequency * i as f32 / RATE as f32).sin(),
+                )
+            })
+            .collect()
+    }
+
+    /// Estimate dominant frequency (in Hz) from spectrum, ignoring warm-up.
+    fn frequency(frames: &[Frame]) -> f32 {
+        let settled = &frames[frames.len() / 2..];
+        (100..800)
+            .map(|frequency| {
+                let (re, im) = settled
+                    .iter()
+                    .enumerate()
+                    .fold((0., 0.), |(re, im), (i, x)| {
+                        let angle =
+                            2. * std::f32::consts::PI * frequency as f32 * i as f32 / RATE as f32;
+                        (re + x.left * angle.cos(), im + x.left * angle.sin())
+                    });
+                (frequency, re * re + im * im)
+            })
+            .max_by(|a, b| a.1.total_cmp(&b.1))
+            .map(|x| x.0 as f32)
+            .unwrap()
+    }
+
+    fn shift(ratio: f64, frames: &[Frame]) -> Vec<Frame> {
+        let mut shifter = Shifter::new(ratio);
+        shifter.resize(RATE);
+        frames.iter().map(|x| shifter.next(*x)).collect()
+    }
+
+    #[test_case(0.5 ; "octave lower")]
+    #[test_case(1.25 ; "slightly higher")]
+    #[test_case(2.0 ; "octave higher")]
+    fn shifts_pitch(ratio: f64) {
+        // taps half a window apart are in phase at this frequency
+        let shifted = shift(ratio, &sine(320., 1.));
+        let frequency = frequency(&shifted);
+        dbg!("{}", frequency);
+        assert!((frequency - 320. * ratio as f32).abs() < 320. * ratio as f32 * 0.02);
+    }
+
+    #[test]
+    fn neutral() {
+        let frames = sine(320., 0.5);
+        assert_eq!(shift(1., &frames), frames);
+    }
+}
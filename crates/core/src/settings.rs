@@ -1,19 +1,35 @@
 //! Audio settings.
 
 use kira::{
-    Easing,
+    Decibels, Easing,
     track::{SpatialTrackBuilder, SpatialTrackDistances},
 };
 
+use crate::{AttenuationCurve, Cone, DistanceCurve, OcclusionModel, eased_gain};
+
 #[derive(Debug, Clone)]
 pub struct SpatialTrackSettings {
     pub distances: SpatialTrackDistances,
     pub persist_until_sounds_finish: bool,
     pub attenuation_function: Option<Easing>,
+    /// Takes precedence over `attenuation_function` when set.
+    pub attenuation_curve: Option<AttenuationCurve>,
     pub spatialization_strength: f32,
     pub affected_by_reverb_mix: bool,
     pub affected_by_environmental_preset: bool,
     pub enable_occlusion: bool,
+    /// Only applies when `enable_occlusion` is set.
+    pub occlusion_model: OcclusionModel,
+    /// Omnidirectional when unset.
+    pub cone: Option<Cone>,
+    /// Doppler shift intensity, disabled when unset.
+    pub doppler_factor: Option<f32>,
+    /// Low-pass cutoff (in Hz) by distance, dulling distant sounds.
+    pub air_absorption: Option<DistanceCurve>,
+    /// Reverb send (as amplitude factor) by distance, in place of a fixed one.
+    pub reverb_send: Option<DistanceCurve>,
+    /// Higher keeps its track first, once there are no more left.
+    pub priority: i32,
 }
 
 impl Default for SpatialTrackSettings {
@@ -22,10 +38,31 @@ impl Default for SpatialTrackSettings {
             distances: SpatialTrackDistances::default(),
             persist_until_sounds_finish: false,
             attenuation_function: None,
+            attenuation_curve: None,
             spatialization_strength: 0.75,
             affected_by_reverb_mix: true,
             affected_by_environmental_preset: false,
             enable_occlusion: false,
+            occlusion_model: OcclusionModel::default(),
+            cone: None,
+            doppler_factor: None,
+            air_absorption: None,
+            reverb_send: None,
+            priority: 0,
+        }
+    }
+}
+
+impl SpatialTrackSettings {
+    /// Attenuation gain at given distance from listener.
+    pub fn gain(&self, distance: f32) -> Decibels {
+        match &self.attenuation_curve {
+            Some(curve) => curve.gain(distance, self.distances),
+            None => eased_gain(
+                self.attenuation_function.unwrap_or(Easing::Linear),
+                distance,
+                self.distances,
+            ),
         }
     }
 }
@@ -36,7 +73,11 @@ impl From<SpatialTrackSettings> for SpatialTrackBuilder {
             .distances(value.distances)
             .persist_until_sounds_finish(value.persist_until_sounds_finish)
             .spatialization_strength(value.spatialization_strength)
-            .attenuation_function(value.attenuation_function)
+            .attenuation_function(match value.attenuation_curve {
+                // applied through volume instead
+                Some(_) => None,
+                None => value.attenuation_function,
+            })
     }
 }
 
@@ -0,0 +1,134 @@
+//! Pitch-preserving time stretch, with [WSOLA](https://doi.org/10.1109/ICASSP.1993.319366).
+
+use kira::Frame;
+
+/// Analysis window duration, in seconds.
+const WINDOW: f64 = 0.04;
+/// How far a segment can drift from its expected position, in seconds.
+const TOLERANCE: f64 = 0.008;
+/// Only every nth sample is compared when searching for best segment.
+const STRIDE: usize = 4;
+
+/// Speed factor of a pitch-preserving time stretch
+/// (e.g. `0.5` lasts twice as long).
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TimeStretch(pub f64);
+
+/// Stretch frames in time by `speed`, without altering their pitch.
+///
+/// Each segment is picked around its expected position
+/// so that it best overlaps with the natural continuation of the previous one.
+pub fn time_stretch(frames: &[Frame], sample_rate: u32, speed: f64) -> Vec<Frame> {
+    if frames.is_empty() || sample_rate == 0 || speed <= 0. || speed == 1. {
+        return frames.to_vec();
+    }
+    let window = (((WINDOW * sample_rate as f64) as usize) & !1).max(4);
+    let hop = window / 2;
+    let tolerance = (TOLERANCE * sample_rate as f64) as usize;
+    let len = (frames.len() as f64 / speed).round() as usize;
+    // periodic Hann window, which sums to 1 when overlapping by half
+    let hann: Vec<f32> = (0..window)
+        .map(|i| {
+            (std::f32::consts::PI * i as f32 / window as f32)
+                .sin()
+                .powi(2)
+        })
+        .collect();
+    let at = |i: usize| frames.get(i).copied().unwrap_or(Frame::ZERO);
+    let mut out = vec![Frame::ZERO; len + window];
+    let mut sum = vec![0f32; len + window];
+    let mut previous: Option<usize> = None;
+    let mut synthesis = 0;
+    while synthesis < len {
+        let expected = (synthesis as f64 * speed) as usize;
+        let position = match previous {
+            Some(previous) => best_match(&at, previous + hop, expected, tolerance, hop),
+            None => 0,
+        };
+        for (i, weight) in hann.iter().enumerate() {
+            out[synthesis + i] += at(position + i) * *weight;
+            sum[synthesis + i] += weight;
+        }
+        previous = Some(position);
+        synthesis += hop;
+    }
+    out.truncate(len);
+    out.iter_mut()
+        .zip(sum)
+        .filter(|(_, sum)| *sum > 1e-6)
+        .for_each(|(frame, sum)| *frame = *frame / sum);
+    out
+}
+
+/// Position around `expected` which correlates best with `natural`.
+fn best_match(
+    at: &impl Fn(usize) -> Frame,
+    natural: usize,
+    expected: usize,
+    tolerance: usize,
+    overlap: usize,
+) -> usize {
+    let mono = |x: Frame| x.left + x.right;
+    let mut best = (expected, f32::MIN);
+    for candidate in (expected.saturating_sub(tolerance)..=expected + tolerance).step_by(2) {
+        let correlation = (0..overlap)
+            .step_by(STRIDE)
+            .map(|i| mono(at(natural + i)) * mono(at(candidate + i)))
+            .sum::<f32>();
+        if correlation > best.1 {
+            best = (candidate, correlation);
+        }
+    }
+    best.0
+}
+
+#[cfg(test)]
+mod tests {
+    use kira::Frame;
+    use test_case::test_case;
+
+    use super::time_stretch;
+
+    const RATE: u32 = 44_100;
+
+    fn sine(frequency: f32, seconds: f32) -> Vec<Frame> {
+        (0..(seconds * RATE as f32) as usize)
+            .map(|i| {
+                Frame::from_mono(
+                    0.5 * (2. * std::f32::consts::PI * frequency * i as f32 / RATE as f32).sin(),
+                )
+            })
+            .collect()
+    }
+
+    /// Estimate frequency from zero crossings, ignoring edges.
+    fn frequency(frames: &[Frame]) -> f32 {
+        let middle = &frames[frames.len() / 4..frames.len() * 3 / 4];
+        let crossings = middle
+            .windows(2)
+            .filter(|x| (x[0].left < 0.) != (x[1].left < 0.))
+            .count();
+        crossings as f32 / 2. / (middle.len() as f32 / RATE as f32)
+    }
+
+    #[test_case(0.5 ; "twice as slow")]
+    #[test_case(0.8 ; "slightly slower")]
+    #[test_case(1.25 ; "slightly faster")]
+    #[test_case(2.0 ; "twice as fast")]
+    fn preserves_pitch(speed: f64) {
+        let frames = sine(440., 2.);
+        let stretched = time_stretch(&frames, RATE, speed);
+        let expected = (frames.len() as f64 / speed).round() as usize;
+        dbg!("{}", stretched.len());
+        assert_eq!(stretched.len(), expected);
+        let frequency = frequency(&stretched);
+        dbg!("{}", frequency);
+        assert!((frequency - 440.).abs() < 440. * 0.02);
+    }
+
+    #[test]
+    fn identity() {
+        let frames = sine(440., 0.5);
+        assert_eq!(time_stretch(&frames, RATE, 1.), frames);
+    }
+}
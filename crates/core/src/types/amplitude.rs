@@ -1,6 +1,6 @@
-use std::ops::Div;
+use std::{ops::Div, str::FromStr};
 
-use kira::Decibels;
+use kira::{Decibels, Value};
 use serde::Deserialize;
 use snafu::Snafu;
 
@@ -9,14 +9,43 @@ fn amplitude_to_decibels(amplitude: f32) -> Decibels {
     Decibels(20. * amplitude.log10())
 }
 
+/// Gain of linear factor, no quieter than [Decibels::SILENCE].
+pub fn factor_to_decibels(factor: f32) -> Decibels {
+    if factor <= 0. {
+        return Decibels::SILENCE;
+    }
+    Decibels(amplitude_to_decibels(factor).0.max(Decibels::SILENCE.0))
+}
+
+/// Volume sound was played with: [Decibels::IDENTITY] unless fixed.
+pub fn base_volume(volume: Value<Decibels>) -> Decibels {
+    match volume {
+        Value::Fixed(x) => x,
+        _ => Decibels::IDENTITY,
+    }
+}
+
 #[derive(Debug, Snafu)]
 pub enum AmplitudeError {
     #[snafu(display("amplitude must be greater or equal to 0.0"))]
     CannotBeNegative,
+    #[snafu(display("invalid amplitude: {value} (expected e.g. 0.5, -6dB or 50%)"))]
+    Invalid { value: String },
 }
 
-#[derive(Debug, Clone, Copy, PartialEq)]
-pub struct Amplitude(f32);
+/// Notation amplitude was written in, preserved for display.
+#[derive(Debug, Clone, Copy)]
+enum Notation {
+    /// Linear gain factor, e.g. `0.5`.
+    Factor(f32),
+    /// Gain in decibels, e.g. `-6dB`.
+    Decibels(f32),
+    /// Linear gain in percent, e.g. `50%`.
+    Percent(f32),
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct Amplitude(Notation);
 
 #[macro_export]
 macro_rules! amplitude {
@@ -24,8 +53,19 @@ macro_rules! amplitude {
 }
 
 impl Amplitude {
-    pub fn as_decibels(&self) -> Decibels {
+    /// Linear gain factor, regardless of notation.
+    pub fn as_factor(&self) -> f32 {
         match self.0 {
+            Notation::Factor(x) => x,
+            Notation::Decibels(x) => Decibels(x).as_amplitude(),
+            Notation::Percent(x) => x / 100.,
+        }
+    }
+    pub fn as_decibels(&self) -> Decibels {
+        if let Notation::Decibels(x) = self.0 {
+            return Decibels(x);
+        }
+        match self.as_factor() {
             1.0 => Decibels::IDENTITY,
             0.0 => Decibels::SILENCE,
             x if x < 0.0 => unreachable!(),
@@ -34,35 +74,79 @@ impl Amplitude {
     }
 }
 
+impl PartialEq for Amplitude {
+    fn eq(&self, other: &Self) -> bool {
+        self.as_factor() == other.as_factor()
+    }
+}
+
 impl TryFrom<f32> for Amplitude {
     type Error = AmplitudeError;
 
     fn try_from(value: f32) -> Result<Self, Self::Error> {
         if value >= 0.0 {
-            Ok(Amplitude(value))
+            Ok(Amplitude(Notation::Factor(value)))
         } else {
             Err(AmplitudeError::CannotBeNegative)
         }
     }
 }
 
+impl FromStr for Amplitude {
+    type Err = AmplitudeError;
+
+    /// Parse either a factor (`0.5`), decibels (`-6dB`, `+3 dB`) or percent (`50%`).
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        let invalid = || AmplitudeError::Invalid {
+            value: s.to_string(),
+        };
+        if let Some(x) = s
+            .strip_suffix("dB")
+            .or_else(|| s.strip_suffix("db"))
+            .or_else(|| s.strip_suffix("DB"))
+        {
+            let x: f32 = x.trim().parse().map_err(|_| invalid())?;
+            if x.is_nan() {
+                return Err(invalid());
+            }
+            return Ok(Amplitude(Notation::Decibels(x)));
+        }
+        if let Some(x) = s.strip_suffix('%') {
+            let x: f32 = x.trim().parse().map_err(|_| invalid())?;
+            if !x.is_finite() {
+                return Err(invalid());
+            }
+            if x < 0.0 {
+                return Err(AmplitudeError::CannotBeNegative);
+            }
+            return Ok(Amplitude(Notation::Percent(x)));
+        }
+        let x: f32 = s.parse().map_err(|_| invalid())?;
+        if !x.is_finite() {
+            return Err(invalid());
+        }
+        Amplitude::try_from(x)
+    }
+}
+
 impl Div<f32> for Amplitude {
     type Output = f32;
 
     fn div(self, rhs: f32) -> Self::Output {
-        self.0 / rhs
+        self.as_factor() / rhs
     }
 }
 
 impl From<Decibels> for Amplitude {
     fn from(value: Decibels) -> Self {
-        Self(value.as_amplitude())
+        Self(Notation::Decibels(value.0))
     }
 }
 
 impl From<Amplitude> for Decibels {
     fn from(value: Amplitude) -> Self {
-        amplitude_to_decibels(value.0)
+        value.as_decibels()
     }
 }
 
@@ -77,7 +161,7 @@ impl<'de> Deserialize<'de> for Amplitude {
             type Value = Amplitude;
 
             fn expecting(&self, formatter: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-                formatter.write_str("a number greater or equal to 0.0")
+                formatter.write_str("a number greater or equal to 0.0, or e.g. -6dB or 50%")
             }
 
             fn visit_f64<E>(self, value: f64) -> Result<Self::Value, E>
@@ -93,6 +177,13 @@ impl<'de> Deserialize<'de> for Amplitude {
             {
                 Amplitude::try_from(value as f32).map_err(E::custom)
             }
+
+            fn visit_str<E>(self, value: &str) -> Result<Self::Value, E>
+            where
+                E: serde::de::Error,
+            {
+                value.parse().map_err(E::custom)
+            }
         }
 
         deserializer.deserialize_any(AmplitudeVisitor)
@@ -101,7 +192,11 @@ impl<'de> Deserialize<'de> for Amplitude {
 
 impl std::fmt::Display for Amplitude {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "{}", self.0)
+        match self.0 {
+            Notation::Factor(x) => write!(f, "{x}"),
+            Notation::Decibels(x) => write!(f, "{x:+}dB"),
+            Notation::Percent(x) => write!(f, "{x}%"),
+        }
     }
 }
 
@@ -110,7 +205,17 @@ mod tests {
     use kira::Decibels;
     use test_case::test_case;
 
-    use crate::Amplitude;
+    use crate::{Amplitude, factor_to_decibels};
+
+    #[test_case(0.0, Decibels::SILENCE.0 ; "silence")]
+    #[test_case(1e-9, Decibels::SILENCE.0 ; "quieter than silence")]
+    #[test_case(0.5, -6.0206 ; "half")]
+    #[test_case(1.0, Decibels::IDENTITY.0 ; "identity")]
+    fn factor_as_decibels(given: f32, expected: f32) {
+        let gain = factor_to_decibels(given);
+        dbg!("{}", gain);
+        assert!((gain.0 - expected).abs() < 1e-3);
+    }
 
     #[test_case(0.0, Decibels::SILENCE ; "silence")]
     #[test_case(0.25118864, Decibels(-12.) ; "minus 12dB")]
@@ -121,4 +226,41 @@ mod tests {
     fn amplitude_as_decibels(given: f32, expected: Decibels) {
         assert_eq!(amplitude!(given).as_decibels(), expected);
     }
+
+    #[test_case("0.5", 0.5 ; "factor")]
+    #[test_case("50%", 0.5 ; "percent")]
+    #[test_case("150 %", 1.5 ; "percent with space")]
+    #[test_case("-6dB", 0.5011872 ; "minus 6dB")]
+    #[test_case("+3 dB", 1.4125376 ; "plus 3dB with space")]
+    #[test_case("0dB", 1.0 ; "identity in decibels")]
+    fn parse_amplitude(given: &str, expected: f32) {
+        let amplitude = given.parse::<Amplitude>();
+        dbg!("{}", &amplitude);
+        assert!((amplitude.unwrap().as_factor() - expected).abs() < 1e-6);
+    }
+
+    #[test_case("-0.5" ; "negative factor")]
+    #[test_case("-50%" ; "negative percent")]
+    #[test_case("loud" ; "not a number")]
+    #[test_case("-6 LUFS" ; "loudness is not a gain")]
+    #[test_case("NaNdB" ; "not a number in decibels")]
+    fn invalid_amplitude(given: &str) {
+        let amplitude = given.parse::<Amplitude>();
+        dbg!("{}", &amplitude);
+        assert!(amplitude.is_err());
+    }
+
+    #[test_case("0.5" ; "factor")]
+    #[test_case("50%" ; "percent")]
+    #[test_case("-6dB" ; "minus 6dB")]
+    #[test_case("+3dB" ; "plus 3dB")]
+    #[test_case("+0dB" ; "identity in decibels")]
+    fn display_round_trips(given: &str) {
+        let amplitude = given.parse::<Amplitude>().unwrap();
+        assert_eq!(amplitude.to_string(), given);
+        assert_eq!(
+            amplitude.to_string().parse::<Amplitude>().unwrap(),
+            amplitude
+        );
+    }
 }
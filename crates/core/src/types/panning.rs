@@ -17,7 +17,8 @@ impl From<PanningError> for ValidationError {
             which: "panning",
             why: match value {
                 PanningError::OutOfRange => "must be a value between -1.0 and 1.0 (inclusive)",
-            },
+            }
+            .into(),
         }
     }
 }
@@ -0,0 +1,110 @@
+//! Emitters competing for a limited amount of spatial tracks.
+
+use std::cmp::Ordering;
+
+use kira::Decibels;
+
+/// How much louder than a real emitter of same priority
+/// a virtual one must be to take its track, so they don't keep swapping.
+pub const TAKEOVER_MARGIN: Decibels = Decibels(3.);
+
+/// How much emitter deserves a real track.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Audibility {
+    /// Set per emitter, higher keeps its track first.
+    pub priority: i32,
+    /// Attenuation gain at current distance from listener.
+    pub gain: Decibels,
+}
+
+impl Audibility {
+    pub fn audible(&self) -> bool {
+        self.gain > Decibels::SILENCE
+    }
+    /// Whether emitter should take the track of `other`.
+    pub fn outranks(&self, other: &Self) -> bool {
+        if !self.audible() {
+            return false;
+        }
+        match self.priority.cmp(&other.priority) {
+            Ordering::Greater => true,
+            Ordering::Less => false,
+            Ordering::Equal => self.gain.0 > other.gain.0 + TAKEOVER_MARGIN.0,
+        }
+    }
+}
+
+impl PartialOrd for Audibility {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(
+            self.priority
+                .cmp(&other.priority)
+                .then(self.gain.0.total_cmp(&other.gain.0)),
+        )
+    }
+}
+
+/// Logical position (in seconds) of sound after `elapsed` seconds at given `rate`
+/// (e.g. own playback rate times time dilation), wrapping back to loop start if any,
+/// or `None` if it would have finished already.
+pub fn advance(
+    position: f64,
+    elapsed: f64,
+    rate: f64,
+    duration: f64,
+    loop_region: Option<(f64, f64)>,
+) -> Option<f64> {
+    let position = position + elapsed.max(0.) * rate.max(0.);
+    let end = loop_region.map_or(duration, |(_, end)| end.min(duration));
+    if position < end {
+        return Some(position);
+    }
+    match loop_region {
+        Some((start, _)) if end > start => Some(start + (position - start) % (end - start)),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use kira::Decibels;
+    use test_case::test_case;
+
+    use super::{Audibility, advance};
+
+    #[test_case(0, -6., 0, -12. => true ; "louder beyond margin")]
+    #[test_case(0, -10., 0, -12. => false ; "louder within margin")]
+    #[test_case(1, -40., 0, -6. => true ; "higher priority")]
+    #[test_case(0, -6., 1, -40. => false ; "lower priority")]
+    #[test_case(1, -60., 0, -6. => false ; "inaudible")]
+    fn takeover(priority: i32, gain: f32, other_priority: i32, other_gain: f32) -> bool {
+        let candidate = Audibility {
+            priority,
+            gain: Decibels(gain),
+        };
+        let other = Audibility {
+            priority: other_priority,
+            gain: Decibels(other_gain),
+        };
+        dbg!("{}", &candidate);
+        candidate.outranks(&other)
+    }
+
+    #[test_case(1., 2., 1., 10., None => Some(3.) ; "still playing")]
+    #[test_case(8., 4., 1., 10., None => None ; "finished")]
+    #[test_case(8., 4., 1., 10., Some((0., 10.)) => Some(2.) ; "looped")]
+    #[test_case(0., 4., 1., 0., Some((0., 0.)) => None ; "empty loop")]
+    #[test_case(1., 2., 2., 10., None => Some(5.) ; "sped up")]
+    #[test_case(1., 4., 0.5, 10., None => Some(3.) ; "slowed down")]
+    #[test_case(8., 4., 1., 10., Some((5., 10.)) => Some(7.) ; "looped back to loop start")]
+    #[test_case(5., 4., 1., 10., Some((2., 8.)) => Some(3.) ; "looped before end")]
+    fn logical_position(
+        position: f64,
+        elapsed: f64,
+        rate: f64,
+        duration: f64,
+        loop_region: Option<(f64, f64)>,
+    ) -> Option<f64> {
+        advance(position, elapsed, rate, duration, loop_region)
+    }
+}
@@ -0,0 +1,89 @@
+//! Export subtitles to translate, and import them back as an overlay manifest.
+//!
+//! ```sh
+//! audioware-translations export <mod folder> <source locale> <target locale> <table.csv|table.xlf>
+//! audioware-translations import <table.csv|table.xlf> <overlay.yaml>
+//! ```
+
+use std::{
+    collections::HashMap,
+    path::{Path, PathBuf},
+    process::ExitCode,
+};
+
+use audioware_manifest::{Locale, Manifest, Mod, TableFormat, TranslationTable, Voice};
+
+const USAGE: &str = r#"usage:
+  audioware-translations export <mod folder> <source locale> <target locale> <table.csv|table.xlf>
+  audioware-translations import <table.csv|table.xlf> <overlay.yaml>"#;
+
+fn main() -> ExitCode {
+    let args = std::env::args().skip(1).collect::<Vec<_>>();
+    let args = args.iter().map(String::as_str).collect::<Vec<_>>();
+    let outcome = match args.as_slice() {
+        ["export", folder, source, target, output] => {
+            export(Path::new(folder), source, target, Path::new(output))
+        }
+        ["import", input, output] => import(Path::new(input), Path::new(output)),
+        _ => Err(USAGE.to_string()),
+    };
+    match outcome {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("{e}");
+            ExitCode::FAILURE
+        }
+    }
+}
+
+fn export(folder: &Path, source: &str, target: &str, output: &Path) -> Result<(), String> {
+    let source = Locale::try_from(source).map_err(|e| e.to_string())?;
+    let target = Locale::try_from(target).map_err(|e| e.to_string())?;
+    let format = TableFormat::try_from_path(output).map_err(|e| e.to_string())?;
+    let mut voices: HashMap<String, Voice> = HashMap::new();
+    let mut duplicates = vec![];
+    for path in Mod::from(PathBuf::from(folder)).manifests_paths() {
+        let file = std::fs::read(&path).map_err(|e| format!("{}: {e}", path.display()))?;
+        let manifest = serde_yaml::from_slice::<Manifest>(file.as_slice())
+            .map_err(|e| format!("{}: {e}", path.display()))?;
+        for (key, voice) in manifest.voices.unwrap_or_default() {
+            if voices.contains_key(&key) {
+                duplicates.push(format!("duplicate voice: {key} ({})", path.display()));
+                continue;
+            }
+            voices.insert(key, voice);
+        }
+    }
+    if !duplicates.is_empty() {
+        return Err(duplicates.join("\n"));
+    }
+    let table = TranslationTable::from_voices(voices, source, target);
+    std::fs::write(output, table.write(format)).map_err(|e| e.to_string())?;
+    println!(
+        "exported {} subtitle(s) to {}",
+        table.rows.len(),
+        output.display()
+    );
+    Ok(())
+}
+
+fn import(input: &Path, output: &Path) -> Result<(), String> {
+    let format = TableFormat::try_from_path(input).map_err(|e| e.to_string())?;
+    let content = std::fs::read_to_string(input).map_err(|e| e.to_string())?;
+    let table = TranslationTable::read(&content, format).map_err(|e| e.to_string())?;
+    for row in table.half_translated() {
+        eprintln!(
+            "warning: {} is missing its {} translation, {} subtitle kept instead",
+            row.key,
+            row.gender.map(|x| x.to_string()).unwrap_or_default(),
+            table.source
+        );
+    }
+    std::fs::write(output, table.to_overlay()).map_err(|e| e.to_string())?;
+    println!(
+        "imported {} translated into {}",
+        table.target,
+        output.display()
+    );
+    Ok(())
+}
@@ -2,13 +2,16 @@
 
 use std::{collections::HashMap, fmt, hash::Hash, path::PathBuf};
 
-use crate::{PlayerGender, ScnDialogLineType};
+use crate::{PlayerGender, ScnDialogLineType, error::ConversionError};
 use fixed_map::Map;
 use semver::Version;
 use serde::Deserialize;
 
+mod cue;
 #[doc(hidden)]
 mod dialog;
+mod ducking;
+mod effect;
 #[doc(hidden)]
 mod jingle;
 mod music;
@@ -17,10 +20,15 @@ mod ono;
 mod playlist;
 mod setting;
 mod sfx;
+mod snapshot;
+mod translation;
 mod voice;
 
+pub use cue::*;
 #[doc(hidden)]
 pub use dialog::*;
+pub use ducking::*;
+pub use effect::*;
 #[doc(hidden)]
 pub use jingle::*;
 pub use music::*;
@@ -29,6 +37,8 @@ pub use ono::*;
 pub use playlist::*;
 pub use setting::*;
 pub use sfx::*;
+pub use snapshot::*;
+pub use translation::*;
 pub use voice::*;
 
 /// allows modder to describe audio files, subtitles and settings.
@@ -46,6 +56,12 @@ pub struct Manifest {
     #[doc(hidden)]
     #[serde(rename = "dialogs")]
     pub scene_dialogs: Option<HashMap<u64, SceneDialogs>>,
+    pub translations: Option<HashMap<String, Translation>>,
+    pub snapshots: Option<HashMap<String, Snapshot>>,
+    /// Tracks ducked whenever sounds play on each track.
+    pub ducking: Option<HashMap<DuckSource, TrackDucking>>,
+    /// Default normalization for each section.
+    pub normalize: Option<Normalization>,
 }
 
 // until proper implementations for 'playlist' and 'jingles' are added
@@ -58,6 +74,10 @@ impl fmt::Debug for Manifest {
             .field("voices", &self.voices)
             .field("music", &self.music)
             .field("dialogs", &self.scene_dialogs)
+            .field("translations", &self.translations)
+            .field("snapshots", &self.snapshots)
+            .field("ducking", &self.ducking)
+            .field("normalize", &self.normalize)
             .finish_non_exhaustive()
     }
 }
@@ -77,7 +97,7 @@ pub struct Audio {
     pub settings: Option<Settings>,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Clone, Deserialize)]
 #[serde(untagged)]
 pub enum AnyAudio {
     Inline(PathBuf),
@@ -140,14 +160,29 @@ pub fn any_audios_into_audios(
     value: GenderBased<AnyAudio>,
     settings: Option<Settings>,
 ) -> GenderBased<Audio> {
-    let GenderBased { female, male } = value;
+    let GenderBased {
+        female,
+        male,
+        any,
+        shared,
+    } = value;
     let (mut female, mut male) = (Audio::from(female), Audio::from(male));
     if let Some(settings) = settings {
         female.merge_settings(settings.clone());
         male.merge_settings(settings);
-        return GenderBased { female, male };
+        return GenderBased {
+            female,
+            male,
+            any,
+            shared,
+        };
+    }
+    GenderBased {
+        female,
+        male,
+        any,
+        shared,
     }
-    GenderBased { female, male }
 }
 
 impl Audio {
@@ -179,6 +214,59 @@ impl Audio {
                 if me.fade_in_tween.is_none() && parent.fade_in_tween.is_some() {
                     me.fade_in_tween = parent.fade_in_tween;
                 }
+                if me.fade_out_tween.is_none() && parent.fade_out_tween.is_some() {
+                    me.fade_out_tween = parent.fade_out_tween;
+                }
+                if me.on_stop.is_none() && parent.on_stop.is_some() {
+                    me.on_stop = parent.on_stop;
+                }
+                if me.normalize.is_none() && parent.normalize.is_some() {
+                    me.normalize = parent.normalize;
+                }
+                if me.affected_by_time_dilation.is_none()
+                    && parent.affected_by_time_dilation.is_some()
+                {
+                    me.affected_by_time_dilation = parent.affected_by_time_dilation;
+                }
+                if me.reverse.is_none() && parent.reverse.is_some() {
+                    me.reverse = parent.reverse;
+                }
+                if me.time_stretch.is_none() && parent.time_stretch.is_some() {
+                    me.time_stretch = parent.time_stretch;
+                }
+                if me.markers.is_none() && parent.markers.is_some() {
+                    me.markers = parent.markers;
+                }
+                if me.effects.is_none() && parent.effects.is_some() {
+                    me.effects = parent.effects;
+                }
+                if me.spatial.is_none() && parent.spatial.is_some() {
+                    me.spatial = parent.spatial;
+                }
+                if me.max_instances.is_none() && parent.max_instances.is_some() {
+                    me.max_instances = parent.max_instances;
+                }
+                if me.instance_scope.is_none() && parent.instance_scope.is_some() {
+                    me.instance_scope = parent.instance_scope;
+                }
+                if me.steal.is_none() && parent.steal.is_some() {
+                    me.steal = parent.steal;
+                }
+                if me.cooldown.is_none() && parent.cooldown.is_some() {
+                    me.cooldown = parent.cooldown;
+                }
+                if me.min_retrigger_interval.is_none() && parent.min_retrigger_interval.is_some() {
+                    me.min_retrigger_interval = parent.min_retrigger_interval;
+                }
+                if me.retrigger_scope.is_none() && parent.retrigger_scope.is_some() {
+                    me.retrigger_scope = parent.retrigger_scope;
+                }
+                if me.duck.is_none() && parent.duck.is_some() {
+                    me.duck = parent.duck;
+                }
+                if me.ducking.is_none() && parent.ducking.is_some() {
+                    me.ducking = parent.ducking;
+                }
             }
             None => {
                 self.settings = Some(parent);
@@ -187,11 +275,104 @@ impl Audio {
     }
 }
 
+/// Values for each [PlayerGender].
+///
+/// Either gender can be omitted in favor of `any`,
+/// which is then also used whenever gender is unknown.
 #[derive(Debug, Deserialize)]
+#[serde(
+    try_from = "PartialGenderBased<T>",
+    bound(deserialize = "T: Deserialize<'de> + Clone")
+)]
 pub struct GenderBased<T> {
-    #[serde(rename = "fem")]
     pub female: T,
     pub male: T,
+    /// Gender whose value was defined with `any`, if any.
+    pub any: Option<PlayerGender>,
+    /// Whether both genders were defined with `any` alone,
+    /// sharing the very same value.
+    pub shared: bool,
+}
+
+/// [GenderBased] as written in manifests.
+#[derive(Debug, Deserialize)]
+struct PartialGenderBased<T> {
+    #[serde(rename = "fem")]
+    female: Option<T>,
+    male: Option<T>,
+    any: Option<T>,
+}
+
+impl<T: Clone> TryFrom<PartialGenderBased<T>> for GenderBased<T> {
+    type Error = ConversionError;
+
+    fn try_from(value: PartialGenderBased<T>) -> Result<Self, Self::Error> {
+        match value {
+            PartialGenderBased {
+                female: Some(_),
+                male: Some(_),
+                any: Some(_),
+            } => Err(ConversionError::AmbiguousGenders),
+            PartialGenderBased {
+                female: Some(female),
+                male: Some(male),
+                any: None,
+            } => Ok(Self {
+                female,
+                male,
+                any: None,
+                shared: false,
+            }),
+            PartialGenderBased {
+                female: Some(female),
+                male: None,
+                any: Some(male),
+            } => Ok(Self {
+                female,
+                male,
+                any: Some(PlayerGender::Male),
+                shared: false,
+            }),
+            PartialGenderBased {
+                female: None,
+                male: Some(male),
+                any: Some(female),
+            } => Ok(Self {
+                female,
+                male,
+                any: Some(PlayerGender::Female),
+                shared: false,
+            }),
+            PartialGenderBased {
+                female: None,
+                male: None,
+                any: Some(any),
+            } => Ok(Self {
+                female: any.clone(),
+                male: any,
+                any: Some(PlayerGender::Female),
+                shared: true,
+            }),
+            PartialGenderBased { female: None, .. } => Err(ConversionError::MissingGender {
+                gender: PlayerGender::Female,
+            }),
+            PartialGenderBased { male: None, .. } => Err(ConversionError::MissingGender {
+                gender: PlayerGender::Male,
+            }),
+        }
+    }
+}
+
+impl<T> GenderBased<T> {
+    /// Map values for each gender, keeping `any` as-is.
+    pub fn map<U>(self, mut f: impl FnMut(T) -> U) -> GenderBased<U> {
+        GenderBased {
+            female: f(self.female),
+            male: f(self.male),
+            any: self.any,
+            shared: self.shared,
+        }
+    }
 }
 
 impl<T> GenderBased<T> {
@@ -211,6 +392,8 @@ where
         Self {
             female: self.female.clone(),
             male: self.male.clone(),
+            any: self.any,
+            shared: self.shared,
         }
     }
 }
@@ -284,6 +467,20 @@ impl std::fmt::Display for DialogLine {
     }
 }
 
+/// Timed subtitles file, split into cues.
+#[derive(Debug, Clone)]
+pub struct TimedSubtitles {
+    pub file: PathBuf,
+    pub line: ScnDialogLineType,
+}
+
+/// Subtitles for audio, either a single [DialogLine] or [TimedSubtitles].
+#[derive(Debug, Clone)]
+pub enum Subtitles {
+    Line(DialogLine),
+    Timed(TimedSubtitles),
+}
+
 /// Manifest sources.
 ///
 /// Also called ["sections" in the book](https://cyb3rpsych0s1s.github.io/audioware/SECTIONS.html).
@@ -297,6 +494,16 @@ pub enum Source {
     Jingle,
 }
 
+impl Source {
+    /// Default target integrated loudness (in LUFS), when normalized.
+    pub fn loudness_target(&self) -> f32 {
+        match self {
+            Self::Ono | Self::Voices => -18.0,
+            Self::Sfx | Self::Playlist | Self::Music | Self::Jingle => -20.0,
+        }
+    }
+}
+
 impl fmt::Display for Source {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         write!(
@@ -320,7 +527,7 @@ mod tests {
 
     use test_case::test_case;
 
-    use super::Subtitle;
+    use super::{GenderBased, Subtitle};
 
     #[test_case(r##"subtitle: "hello world""## ; "implicit subtitle")]
     #[test_case(r##"subtitle:
@@ -331,4 +538,15 @@ mod tests {
         dbg!("{}", &subtitle);
         assert!(subtitle.is_ok());
     }
+
+    #[test_case(r##"any: ./a.wav"##, true ; "any alone")]
+    #[test_case(r##"fem: ./a.wav
+any: ./b.wav"##, false ; "female and any")]
+    #[test_case(r##"fem: ./a.wav
+male: ./b.wav"##, false ; "both genders")]
+    fn shared_genders(yaml: &str, shared: bool) {
+        let genders = serde_yaml::from_str::<GenderBased<String>>(yaml);
+        dbg!("{}", &genders);
+        assert_eq!(genders.unwrap().shared, shared);
+    }
 }
@@ -0,0 +1,331 @@
+//! Timed subtitles, read from [SubRip](https://en.wikipedia.org/wiki/SubRip)
+//! or [WebVTT](https://www.w3.org/TR/webvtt1/) files.
+
+use std::{path::Path, time::Duration};
+
+use audioware_core::error::ValidationError;
+use either::Either;
+use kira::sound::{FromFileError, static_sound::StaticSoundData, streaming::StreamingSoundData};
+use snafu::{OptionExt, ResultExt, ensure};
+
+use crate::{
+    ScnDialogLineType,
+    error::{
+        CannotReadSubtitlesSnafu, CueError, MalformedCueSnafu, MissingHeaderSnafu,
+        UnsupportedSubtitlesSnafu,
+    },
+};
+
+use super::{DialogLine, Region, ValidateFor};
+
+/// [DialogLine] displayed for a portion of the audio.
+#[derive(Debug, Clone)]
+pub struct Cue {
+    pub starts: Duration,
+    pub ends: Duration,
+    pub line: DialogLine,
+}
+
+impl Cue {
+    /// How long the cue should be displayed for.
+    pub fn duration(&self) -> Duration {
+        self.ends - self.starts
+    }
+}
+
+/// Supported subtitles file formats.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CueFormat {
+    /// `.srt`
+    SubRip,
+    /// `.vtt`
+    WebVtt,
+}
+
+impl CueFormat {
+    pub fn try_from_path(path: &Path) -> Result<Self, CueError> {
+        match path
+            .extension()
+            .and_then(|x| x.to_str())
+            .map(|x| x.to_ascii_lowercase())
+            .as_deref()
+        {
+            Some("srt") => Ok(Self::SubRip),
+            Some("vtt") => Ok(Self::WebVtt),
+            _ => UnsupportedSubtitlesSnafu {
+                path: path.display().to_string(),
+            }
+            .fail(),
+        }
+    }
+    const fn decimal_separator(&self) -> char {
+        match self {
+            Self::SubRip => ',',
+            Self::WebVtt => '.',
+        }
+    }
+}
+
+/// Read cues from subtitles file, whose format is deduced from its extension.
+pub fn cues_from_file(path: &Path, line: ScnDialogLineType) -> Result<Vec<Cue>, CueError> {
+    let format = CueFormat::try_from_path(path)?;
+    let content = std::fs::read_to_string(path).context(CannotReadSubtitlesSnafu {
+        path: path.display().to_string(),
+    })?;
+    parse_cues(&content, format, line)
+}
+
+/// Parse cues from subtitles file content.
+pub fn parse_cues(
+    content: &str,
+    format: CueFormat,
+    line: ScnDialogLineType,
+) -> Result<Vec<Cue>, CueError> {
+    let content = content.trim_start_matches('\u{feff}').replace("\r\n", "\n");
+    let mut blocks = content
+        .split("\n\n")
+        .map(str::trim)
+        .filter(|x| !x.is_empty());
+    if format == CueFormat::WebVtt {
+        let header = blocks.next().unwrap_or_default();
+        ensure!(
+            header == "WEBVTT" || header.starts_with("WEBVTT ") || header.starts_with("WEBVTT\n"),
+            MissingHeaderSnafu
+        );
+    }
+    let mut cues: Vec<Cue> = Vec::new();
+    for block in blocks {
+        if format == CueFormat::WebVtt
+            && ["NOTE", "STYLE", "REGION"]
+                .iter()
+                .any(|x| block.starts_with(x))
+        {
+            continue;
+        }
+        let index = cues.len() + 1;
+        let mut lines = block.lines();
+        let mut timings = lines.next().unwrap_or_default();
+        // cue number (SubRip) or identifier (WebVTT)
+        if !timings.contains("-->") {
+            timings = lines.next().unwrap_or_default();
+        }
+        let Some((starts, ends)) = timings.split_once("-->") else {
+            return MalformedCueSnafu {
+                index,
+                why: "missing timings",
+            }
+            .fail();
+        };
+        let starts = parse_timestamp(starts.trim(), format).context(MalformedCueSnafu {
+            index,
+            why: "invalid start timestamp",
+        })?;
+        // WebVTT allows cue settings after timings
+        let ends = ends.split_whitespace().next().unwrap_or_default();
+        let ends = parse_timestamp(ends, format).context(MalformedCueSnafu {
+            index,
+            why: "invalid end timestamp",
+        })?;
+        ensure!(
+            starts < ends,
+            MalformedCueSnafu {
+                index,
+                why: "must start before it ends",
+            }
+        );
+        if let Some(previous) = cues.last() {
+            ensure!(
+                previous.starts <= starts,
+                MalformedCueSnafu {
+                    index,
+                    why: "unordered sequence",
+                }
+            );
+        }
+        let msg = lines.collect::<Vec<_>>().join("\n");
+        ensure!(
+            !msg.trim().is_empty(),
+            MalformedCueSnafu {
+                index,
+                why: "missing text",
+            }
+        );
+        cues.push(Cue {
+            starts,
+            ends,
+            line: DialogLine { msg, line },
+        });
+    }
+    Ok(cues)
+}
+
+/// Parse `HH:MM:SS,mmm` (SubRip) or `[HH:]MM:SS.mmm` (WebVTT).
+fn parse_timestamp(value: &str, format: CueFormat) -> Option<Duration> {
+    let (hms, millis) = value.split_once(format.decimal_separator())?;
+    if millis.len() != 3 {
+        return None;
+    }
+    let millis: u64 = millis.parse().ok()?;
+    let parts = hms
+        .split(':')
+        .map(|x| x.parse::<u64>().ok())
+        .collect::<Option<Vec<_>>>()?;
+    let (hours, minutes, seconds) = match (format, parts.as_slice()) {
+        (_, [h, m, s]) => (*h, *m, *s),
+        (CueFormat::WebVtt, [m, s]) => (0, *m, *s),
+        _ => return None,
+    };
+    if minutes >= 60 || seconds >= 60 {
+        return None;
+    }
+    Some(Duration::from_millis(
+        ((hours * 60 + minutes) * 60 + seconds) * 1000 + millis,
+    ))
+}
+
+impl Cue {
+    /// Ensure cues fit within audio, or within its [Region] if any,
+    /// since cues outside of it would never be displayed.
+    pub fn validate_within(
+        cues: &[Cue],
+        audio: &Either<StaticSoundData, StreamingSoundData<FromFileError>>,
+        region: Option<&Region>,
+    ) -> Result<(), Vec<ValidationError>> {
+        let duration = match audio {
+            Either::Left(x) => x.unsliced_duration(),
+            Either::Right(x) => x.unsliced_duration(),
+        };
+        within(cues, duration, region)
+    }
+}
+
+fn within(
+    cues: &[Cue],
+    duration: Duration,
+    region: Option<&Region>,
+) -> Result<(), Vec<ValidationError>> {
+    let starts = region.and_then(|x| x.starts).unwrap_or_default();
+    let ends = region
+        .and_then(|x| x.ends)
+        .map(|x| x.min(duration))
+        .unwrap_or(duration);
+    let mut errors = vec![];
+    if cues.iter().any(|x| x.ends > ends) {
+        errors.push(ValidationError {
+            which: "subtitles",
+            why: if ends < duration {
+                "cue ends after audio region".into()
+            } else {
+                "cue ends after audio duration".into()
+            },
+        });
+    }
+    if cues.iter().any(|x| x.ends <= starts) {
+        errors.push(ValidationError {
+            which: "subtitles",
+            why: "cue ends before audio region starts".into(),
+        });
+    }
+    if errors.is_empty() {
+        return Ok(());
+    }
+    Err(errors)
+}
+
+impl ValidateFor<Either<StaticSoundData, StreamingSoundData<FromFileError>>> for [Cue] {
+    fn validate_for(
+        &self,
+        audio: &Either<StaticSoundData, StreamingSoundData<FromFileError>>,
+    ) -> Result<(), Vec<ValidationError>> {
+        Cue::validate_within(self, audio, None)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use test_case::test_case;
+
+    use std::time::Duration;
+
+    use super::{CueFormat, parse_cues, within};
+    use crate::{Region, ScnDialogLineType};
+
+    #[test_case(r##"1
+00:00:00,500 --> 00:00:02,000
+Hello there.
+
+2
+00:00:02,250 --> 00:00:04,000
+General Kenobi!
+You are a bold one."##, CueFormat::SubRip, 2 ; "subrip")]
+    #[test_case(r##"WEBVTT
+
+00:00.500 --> 00:02.000
+Hello there.
+
+intro
+00:00:02.250 --> 00:00:04.000 align:start
+General Kenobi!"##, CueFormat::WebVtt, 2 ; "webvtt")]
+    #[test_case(r##"WEBVTT - with title
+
+NOTE this is a comment
+
+00:00.500 --> 00:02.000
+Hello there."##, CueFormat::WebVtt, 1 ; "webvtt with comment")]
+    fn cues(content: &str, format: CueFormat, len: usize) {
+        let cues = parse_cues(content, format, ScnDialogLineType::Regular);
+        dbg!("{}", &cues);
+        assert_eq!(cues.map(|x| x.len()).ok(), Some(len));
+    }
+
+    #[test_case(r##"1
+00:00:02,000 --> 00:00:00,500
+Hello there."##, CueFormat::SubRip ; "ends before it starts")]
+    #[test_case(r##"1
+00:00:00.500 --> 00:00:02.000
+Hello there."##, CueFormat::SubRip ; "wrong decimal separator")]
+    #[test_case(r##"1
+00:00:00,500 --> 00:00:02,000"##, CueFormat::SubRip ; "missing text")]
+    #[test_case(r##"1
+Hello there."##, CueFormat::SubRip ; "missing timings")]
+    #[test_case(r##"1
+00:00:02,000 --> 00:00:03,000
+Hello there.
+
+2
+00:00:00,500 --> 00:00:01,000
+General Kenobi!"##, CueFormat::SubRip ; "unordered")]
+    #[test_case(r##"00:00.500 --> 00:02.000
+Hello there."##, CueFormat::WebVtt ; "missing header")]
+    fn malformed(content: &str, format: CueFormat) {
+        let cues = parse_cues(content, format, ScnDialogLineType::Regular);
+        dbg!("{}", &cues);
+        assert!(cues.is_err());
+    }
+
+    #[test_case(None, true ; "whole audio")]
+    #[test_case(Some((Some(1), None)), true ; "region overlapping cues")]
+    #[test_case(Some((None, Some(3))), false ; "region ending before last cue")]
+    #[test_case(Some((Some(5), None)), false ; "region starting after every cue")]
+    fn cues_within_region(region: Option<(Option<u64>, Option<u64>)>, valid: bool) {
+        let cues = parse_cues(
+            r##"1
+00:00:00,500 --> 00:00:02,000
+Hello there.
+
+2
+00:00:02,250 --> 00:00:04,000
+General Kenobi!"##,
+            CueFormat::SubRip,
+            ScnDialogLineType::Regular,
+        )
+        .unwrap();
+        let region = region.map(|(starts, ends)| Region {
+            starts: starts.map(Duration::from_secs),
+            ends: ends.map(Duration::from_secs),
+        });
+        let result = within(&cues, Duration::from_secs(10), region.as_ref());
+        dbg!("{}", &result);
+        assert_eq!(result.is_ok(), valid);
+    }
+}
@@ -3,7 +3,7 @@ use std::{collections::HashMap, path::PathBuf};
 use either::Either;
 use serde::Deserialize;
 
-use crate::{Audio, Locale, paths_into_audios};
+use crate::{Audio, Locale, ScnDialogLineType, TimedSubtitles, paths_into_audios};
 
 use super::{GenderBased, Settings, Usage};
 
@@ -23,14 +23,71 @@ pub enum SceneDialogs {
         usage: Option<Usage>,
         settings: Option<Settings>,
     },
+    SingleMulti {
+        #[serde(flatten)]
+        dialogs: HashMap<Locale, SceneDialog>,
+        usage: Option<Usage>,
+        line: Option<ScnDialogLineType>,
+        settings: Option<Settings>,
+    },
+    DualMulti {
+        #[serde(flatten)]
+        dialogs: HashMap<Locale, GenderBased<SceneDialog>>,
+        usage: Option<Usage>,
+        line: Option<ScnDialogLineType>,
+        settings: Option<Settings>,
+    },
 }
 
-pub type AnySceneDialog =
-    Either<(HashMap<Locale, Audio>, Usage), (HashMap<Locale, GenderBased<Audio>>, Usage)>;
+/// Scene dialog with its `.srt` / `.vtt` subtitles file.
+#[derive(Debug, Clone, Deserialize)]
+pub struct SceneDialog {
+    #[serde(flatten)]
+    pub basic: Audio,
+    pub subtitles: PathBuf,
+}
+
+impl SceneDialog {
+    fn split(
+        self,
+        line: ScnDialogLineType,
+        settings: Option<&Settings>,
+    ) -> (Audio, TimedSubtitles) {
+        let mut audio = self.basic;
+        if let Some(settings) = settings {
+            audio.merge_settings(settings.clone());
+        }
+        (
+            audio,
+            TimedSubtitles {
+                file: self.subtitles,
+                line,
+            },
+        )
+    }
+}
+
+/// ultimately a scene dialog is just either file path for each locale
+/// with optional gender,
+/// audio usage
+/// and optional timed subtitles
+pub type AnySceneDialog = Either<
+    (
+        HashMap<Locale, Audio>,
+        Usage,
+        Option<HashMap<Locale, TimedSubtitles>>,
+    ),
+    (
+        HashMap<Locale, GenderBased<Audio>>,
+        Usage,
+        Option<HashMap<Locale, GenderBased<TimedSubtitles>>>,
+    ),
+>;
 
 impl From<SceneDialogs> for AnySceneDialog {
     fn from(value: SceneDialogs) -> Self {
         let default_usage = Usage::OnDemand;
+        let default_line = ScnDialogLineType::Regular;
         match value {
             SceneDialogs::SingleInline {
                 dialogs,
@@ -38,7 +95,7 @@ impl From<SceneDialogs> for AnySceneDialog {
                 settings,
             } => {
                 let dialogs = paths_into_audios(dialogs, settings);
-                Either::Left((dialogs, usage.unwrap_or(default_usage)))
+                Either::Left((dialogs, usage.unwrap_or(default_usage), None))
             }
             SceneDialogs::DualInline {
                 dialogs,
@@ -50,20 +107,69 @@ impl From<SceneDialogs> for AnySceneDialog {
                     .map(|(k, v)| {
                         (
                             k,
-                            GenderBased::<Audio> {
-                                female: Audio {
-                                    file: v.female,
-                                    settings: settings.clone(),
-                                },
-                                male: Audio {
-                                    file: v.male,
-                                    settings: settings.clone(),
-                                },
-                            },
+                            v.map(|file| Audio {
+                                file,
+                                settings: settings.clone(),
+                            }),
                         )
                     })
                     .collect();
-                Either::Right((dialogs, usage.unwrap_or(default_usage)))
+                Either::Right((dialogs, usage.unwrap_or(default_usage), None))
+            }
+            SceneDialogs::SingleMulti {
+                dialogs,
+                usage,
+                line,
+                settings,
+            } => {
+                let mut aud: HashMap<Locale, Audio> = HashMap::with_capacity(dialogs.len());
+                let mut sub: HashMap<Locale, TimedSubtitles> =
+                    HashMap::with_capacity(dialogs.len());
+                for (k, v) in dialogs.into_iter() {
+                    let (audio, subtitles) =
+                        v.split(line.unwrap_or(default_line), settings.as_ref());
+                    aud.insert(k, audio);
+                    sub.insert(k, subtitles);
+                }
+                Either::Left((aud, usage.unwrap_or(default_usage), Some(sub)))
+            }
+            SceneDialogs::DualMulti {
+                dialogs,
+                usage,
+                line,
+                settings,
+            } => {
+                let mut aud: HashMap<Locale, GenderBased<Audio>> =
+                    HashMap::with_capacity(dialogs.len());
+                let mut sub: HashMap<Locale, GenderBased<TimedSubtitles>> =
+                    HashMap::with_capacity(dialogs.len());
+                for (k, v) in dialogs.into_iter() {
+                    let GenderBased {
+                        female,
+                        male,
+                        any,
+                        shared,
+                    } = v.map(|x| x.split(line.unwrap_or(default_line), settings.as_ref()));
+                    aud.insert(
+                        k,
+                        GenderBased {
+                            female: female.0,
+                            male: male.0,
+                            any,
+                            shared,
+                        },
+                    );
+                    sub.insert(
+                        k,
+                        GenderBased {
+                            female: female.1,
+                            male: male.1,
+                            any,
+                            shared,
+                        },
+                    );
+                }
+                Either::Right((aud, usage.unwrap_or(default_usage), Some(sub)))
             }
         }
     }
@@ -97,6 +203,85 @@ mod tests {
             assert!(unique_dialog.is_err());
         }
     }
+    mod timed_dialog {
+        use std::collections::HashMap;
+
+        use super::super::SceneDialogs;
+        use test_case::test_case;
+
+        #[test_case(r##"1300277703738512077:
+    en-us:
+        file: ./somewhere/dialog.wav
+        subtitles: ./somewhere/dialog.srt
+    line: radio"## ; "scene dialog with timed subtitles")]
+        #[test_case(r##"1300277703738512077:
+    en-us:
+        fem:
+            file: ./somewhere/dialog.wav
+            subtitles: ./somewhere/dialog.srt
+        male:
+            file: ./somewhere/else/dialog.wav
+            subtitles: ./somewhere/else/dialog.vtt"## ; "dual scene dialog with timed subtitles")]
+        fn basic_format(yaml: &str) {
+            let dialog = serde_yaml::from_str::<HashMap<u64, SceneDialogs>>(yaml);
+            dbg!("{}", &dialog);
+            assert!(dialog.is_ok());
+        }
+
+        #[test_case(r##"1300277703738512077:
+    en-us:
+        file: ./somewhere/dialog.wav
+        subtitles: ./somewhere/dialog.srt
+    fr-fr: ./somewhere/else/dialog.wav"## ; "format must be consistent")]
+        fn incompatibility(yaml: &str) {
+            let dialog = serde_yaml::from_str::<HashMap<u64, SceneDialogs>>(yaml);
+            dbg!("{}", &dialog);
+            assert!(dialog.is_err());
+        }
+    }
+    mod markers {
+        use std::collections::HashMap;
+
+        use either::Either;
+        use test_case::test_case;
+
+        use super::super::{AnySceneDialog, SceneDialogs};
+        use crate::{Locale, Validate};
+
+        #[test_case(r##"1300277703738512077:
+    en-us: ./somewhere/dialog.wav
+    settings:
+        markers:
+          - name: door_slam
+            time: 1s"## ; "single scene dialog with marker")]
+        #[test_case(r##"1300277703738512077:
+    en-us:
+        fem: ./somewhere/dialog.wav
+        male: ./somewhere/else/dialog.wav
+    settings:
+        markers:
+          - name: door_slam
+            time: 1s"## ; "dual scene dialog with marker")]
+        fn scene_dialog_markers(yaml: &str) {
+            let mut dialogs = serde_yaml::from_str::<HashMap<u64, SceneDialogs>>(yaml).unwrap();
+            dbg!("{}", &dialogs);
+            let dialog: AnySceneDialog = dialogs.remove(&1300277703738512077).unwrap().into();
+            let settings = match dialog {
+                Either::Left((aud, ..)) => vec![aud[&Locale::English].settings.clone()],
+                Either::Right((aud, ..)) => {
+                    let genders = aud[&Locale::English].clone();
+                    vec![genders.female.settings, genders.male.settings]
+                }
+            };
+            for settings in settings {
+                let settings = settings.unwrap();
+                assert!(settings.validate().is_ok());
+                let markers = settings.markers.unwrap();
+                assert_eq!(markers.len(), 1);
+                assert_eq!(markers[0].name, "door_slam");
+            }
+        }
+    }
     mod dual_dialog {
         use std::collections::HashMap;
 
@@ -120,6 +305,12 @@ mod tests {
     settings:
         region:
             starts: 500ms"## ; "dual scene dialog with specific settings")]
+        #[test_case(r##"1300277703738512077:
+    en-us:
+        fem: ./somewhere/dialog.wav
+        any: ./somewhere/else/dialog.wav
+    fr-fr:
+        any: ./elsewhere/dialog.wav"## ; "dual scene dialog with gender-agnostic fallback")]
         fn basic_format_with_settings(yaml: &str) {
             let dual_dialog = serde_yaml::from_str::<HashMap<u64, SceneDialogs>>(yaml);
             dbg!("{}", &dual_dialog);
@@ -134,6 +325,11 @@ mod tests {
         fem: ./fem/dialog.wav
         male: ./male/dialog.wav
     fr-fr: ./unique/dialog.wav"## ; "format must be consistent, mixing unique and gender-based is not allowed")]
+        #[test_case(r##"1300277703738512077:
+    en-us:
+        fem: ./fem/dialog.wav
+        male: ./male/dialog.wav
+        any: ./any/dialog.wav"## ; "gender-agnostic fallback is not allowed alongside both genders")]
         fn incompatibility(yaml: &str) {
             let dual_dialog = serde_yaml::from_str::<HashMap<u64, SceneDialogs>>(yaml);
             dbg!("{}", &dual_dialog);
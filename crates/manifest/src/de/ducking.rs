@@ -0,0 +1,110 @@
+//! Tracks ducked whenever sounds play on a given track.
+
+use audioware_core::error::ValidationError;
+use kira::Decibels;
+use serde::Deserialize;
+
+use super::{DuckTarget, Ducking, Validate};
+
+/// Track whose sounds duck other tracks, see [TrackDucking].
+#[derive(Debug, Deserialize, Clone, Copy, PartialEq, Eq, Hash)]
+#[serde(rename_all = "kebab-case")]
+pub enum DuckSource {
+    Dialogue,
+    /// V's own voice.
+    Vocal,
+    Holocall,
+    Sfx,
+    Music,
+    Radioport,
+    CarRadio,
+}
+
+impl DuckSource {
+    pub const ALL: [Self; 7] = [
+        Self::Dialogue,
+        Self::Vocal,
+        Self::Holocall,
+        Self::Sfx,
+        Self::Music,
+        Self::Radioport,
+        Self::CarRadio,
+    ];
+}
+
+/// Tracks ducked whenever a sound plays on given track,
+/// unless sound declares its own `duck` or `ducking`.
+///
+/// Whatever is left unset keeps its built-in default:
+/// dialogue, vocal and holocall duck music and sfx by `-9dB`.
+#[derive(Debug, Default, Deserialize, Clone, PartialEq)]
+pub struct TrackDucking {
+    /// Tracks ducked, empty to duck none.
+    pub duck: Option<Vec<DuckTarget>>,
+    /// How deep and fast tracks get ducked.
+    #[serde(flatten)]
+    pub ducking: Ducking,
+}
+
+impl Validate for TrackDucking {
+    fn validate(&self) -> Result<(), Vec<ValidationError>> {
+        let mut errors = vec![];
+        if self.duck.is_none() && self.ducking == Ducking::default() {
+            errors.push(ValidationError {
+                which: "ducking",
+                why: "must set at least one of duck, depth, attack or release".into(),
+            });
+        }
+        if let Some(depth) = self.ducking.depth
+            && depth.as_decibels() > Decibels::IDENTITY
+        {
+            errors.push(ValidationError {
+                which: "depth",
+                why: "depth cannot be louder than 0dB".into(),
+            });
+        }
+        if errors.is_empty() {
+            return Ok(());
+        }
+        Err(errors)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use test_case::test_case;
+
+    use super::{DuckSource, TrackDucking};
+    use crate::{DuckTarget, Validate};
+
+    #[test_case(r##"dialogue:
+  duck: [music]
+  depth: -12dB
+  attack: 100ms
+  release: 1s"##, DuckSource::Dialogue, Some(vec![DuckTarget::Music]) ; "dialogue")]
+    #[test_case(r##"holocall:
+  depth: 50%"##, DuckSource::Holocall, None ; "holocall depth only")]
+    #[test_case(r##"car-radio:
+  duck: [radioport]"##, DuckSource::CarRadio, Some(vec![DuckTarget::Radioport]) ; "car radio")]
+    #[test_case(r##"vocal:
+  duck: []"##, DuckSource::Vocal, Some(vec![]) ; "vocal ducks none")]
+    fn track_ducking(yaml: &str, source: DuckSource, targets: Option<Vec<DuckTarget>>) {
+        let matrix = serde_yaml::from_str::<HashMap<DuckSource, TrackDucking>>(yaml).unwrap();
+        dbg!("{}", &matrix);
+        let ducking = matrix.get(&source).unwrap();
+        assert!(ducking.validate().is_ok());
+        assert_eq!(ducking.duck, targets);
+    }
+
+    #[test_case(r##"{}"## ; "nothing set")]
+    #[test_case(r##"depth: +3dB"## ; "louder depth")]
+    #[test_case(r##"attack: fast"## ; "unparseable attack")]
+    #[test_case(r##"duck: [dialogue]"## ; "unknown target")]
+    fn invalid_track_ducking(yaml: &str) {
+        let ducking = serde_yaml::from_str::<TrackDucking>(yaml);
+        dbg!("{}", &ducking);
+        assert!(ducking.is_err() || ducking.unwrap().validate().is_err());
+    }
+}
@@ -0,0 +1,341 @@
+//! Insert effects, mirroring [kira] effect builders.
+
+use std::time::Duration;
+
+use audioware_core::{Amplitude, error::ValidationError};
+use kira::{
+    Mix,
+    effect::{
+        compressor::{CompressorBuilder, CompressorHandle},
+        delay::{DelayBuilder, DelayHandle},
+        distortion::{DistortionBuilder, DistortionHandle, DistortionKind},
+        filter::{FilterBuilder, FilterHandle, FilterMode},
+        reverb::{ReverbBuilder, ReverbHandle},
+    },
+    track::TrackBuilder,
+};
+use serde::Deserialize;
+
+use super::Validate;
+
+/// Effect inserted on sound own track, in declaration order.
+///
+/// e.g. `- type: low_pass` followed by its parameters.
+#[derive(Debug, Deserialize, Clone, PartialEq)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum Effect {
+    LowPass(Filter),
+    HighPass(Filter),
+    Distortion(Distortion),
+    Delay(Delay),
+    Compressor(Compressor),
+    Reverb(Reverb),
+}
+
+/// See [FilterBuilder].
+#[derive(Debug, Deserialize, Clone, Copy, PartialEq)]
+pub struct Filter {
+    /// Cutoff frequency, in Hz.
+    pub cutoff: f64,
+    /// Between `0.0` and `1.0`.
+    pub resonance: Option<f64>,
+    pub mix: Option<f32>,
+}
+
+/// See [DistortionBuilder].
+#[derive(Debug, Deserialize, Clone, Copy, PartialEq)]
+pub struct Distortion {
+    pub kind: Option<DistortionType>,
+    pub drive: Option<Amplitude>,
+    pub mix: Option<f32>,
+}
+
+/// See [DistortionKind].
+#[derive(Debug, Deserialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "kebab-case")]
+pub enum DistortionType {
+    HardClip,
+    SoftClip,
+}
+
+/// See [DelayBuilder].
+#[derive(Debug, Deserialize, Clone, Copy, PartialEq)]
+pub struct Delay {
+    #[serde(with = "humantime_serde", default)]
+    pub delay_time: Option<Duration>,
+    pub feedback: Option<Amplitude>,
+    pub mix: Option<f32>,
+}
+
+/// See [CompressorBuilder].
+#[derive(Debug, Deserialize, Clone, Copy, PartialEq)]
+pub struct Compressor {
+    /// Volume above which audio is compressed, in dBFS.
+    pub threshold: Option<f64>,
+    pub ratio: Option<f64>,
+    #[serde(with = "humantime_serde", default)]
+    pub attack: Option<Duration>,
+    #[serde(with = "humantime_serde", default)]
+    pub release: Option<Duration>,
+    pub makeup_gain: Option<Amplitude>,
+    pub mix: Option<f32>,
+}
+
+/// See [ReverbBuilder].
+#[derive(Debug, Deserialize, Clone, Copy, PartialEq)]
+pub struct Reverb {
+    /// Between `0.0` and `1.0`.
+    pub feedback: Option<f64>,
+    /// Between `0.0` and `1.0`.
+    pub damping: Option<f64>,
+    /// Between `0.0` and `1.0`.
+    pub stereo_width: Option<f64>,
+    pub mix: Option<f32>,
+}
+
+/// Controls an inserted [Effect] at runtime.
+pub enum EffectHandle {
+    Filter(FilterHandle),
+    Distortion(DistortionHandle),
+    Delay(DelayHandle),
+    Compressor(CompressorHandle),
+    Reverb(ReverbHandle),
+}
+
+impl Effect {
+    /// Insert effect at the end of the track effects chain.
+    pub fn insert(&self, track: &mut TrackBuilder) -> EffectHandle {
+        match *self {
+            Self::LowPass(x) => {
+                EffectHandle::Filter(track.add_effect(x.into_builder(FilterMode::LowPass)))
+            }
+            Self::HighPass(x) => {
+                EffectHandle::Filter(track.add_effect(x.into_builder(FilterMode::HighPass)))
+            }
+            Self::Distortion(x) => {
+                let mut builder = DistortionBuilder::new();
+                if let Some(kind) = x.kind {
+                    builder = builder.kind(match kind {
+                        DistortionType::HardClip => DistortionKind::HardClip,
+                        DistortionType::SoftClip => DistortionKind::SoftClip,
+                    });
+                }
+                if let Some(drive) = x.drive {
+                    builder = builder.drive(drive.as_decibels());
+                }
+                if let Some(mix) = x.mix {
+                    builder = builder.mix(Mix(mix));
+                }
+                EffectHandle::Distortion(track.add_effect(builder))
+            }
+            Self::Delay(x) => {
+                let mut builder = DelayBuilder::new();
+                if let Some(delay_time) = x.delay_time {
+                    builder = builder.delay_time(delay_time);
+                }
+                if let Some(feedback) = x.feedback {
+                    builder = builder.feedback(feedback.as_decibels());
+                }
+                if let Some(mix) = x.mix {
+                    builder = builder.mix(Mix(mix));
+                }
+                EffectHandle::Delay(track.add_effect(builder))
+            }
+            Self::Compressor(x) => {
+                let mut builder = CompressorBuilder::new();
+                if let Some(threshold) = x.threshold {
+                    builder = builder.threshold(threshold);
+                }
+                if let Some(ratio) = x.ratio {
+                    builder = builder.ratio(ratio);
+                }
+                if let Some(attack) = x.attack {
+                    builder = builder.attack_duration(attack);
+                }
+                if let Some(release) = x.release {
+                    builder = builder.release_duration(release);
+                }
+                if let Some(makeup_gain) = x.makeup_gain {
+                    builder = builder.makeup_gain(makeup_gain.as_decibels());
+                }
+                if let Some(mix) = x.mix {
+                    builder = builder.mix(Mix(mix));
+                }
+                EffectHandle::Compressor(track.add_effect(builder))
+            }
+            Self::Reverb(x) => {
+                let mut builder = ReverbBuilder::new();
+                if let Some(feedback) = x.feedback {
+                    builder = builder.feedback(feedback);
+                }
+                if let Some(damping) = x.damping {
+                    builder = builder.damping(damping);
+                }
+                if let Some(stereo_width) = x.stereo_width {
+                    builder = builder.stereo_width(stereo_width);
+                }
+                if let Some(mix) = x.mix {
+                    builder = builder.mix(Mix(mix));
+                }
+                EffectHandle::Reverb(track.add_effect(builder))
+            }
+        }
+    }
+    fn name(&self) -> &'static str {
+        match self {
+            Self::LowPass(_) => "effects.low_pass",
+            Self::HighPass(_) => "effects.high_pass",
+            Self::Distortion(_) => "effects.distortion",
+            Self::Delay(_) => "effects.delay",
+            Self::Compressor(_) => "effects.compressor",
+            Self::Reverb(_) => "effects.reverb",
+        }
+    }
+    fn mix(&self) -> Option<f32> {
+        match self {
+            Self::LowPass(x) | Self::HighPass(x) => x.mix,
+            Self::Distortion(x) => x.mix,
+            Self::Delay(x) => x.mix,
+            Self::Compressor(x) => x.mix,
+            Self::Reverb(x) => x.mix,
+        }
+    }
+}
+
+impl Filter {
+    fn into_builder(self, mode: FilterMode) -> FilterBuilder {
+        let mut builder = FilterBuilder::new().mode(mode).cutoff(self.cutoff);
+        if let Some(resonance) = self.resonance {
+            builder = builder.resonance(resonance);
+        }
+        if let Some(mix) = self.mix {
+            builder = builder.mix(Mix(mix));
+        }
+        builder
+    }
+}
+
+/// Track with insert effects, in declaration order.
+pub fn track_with_effects(effects: &[Effect]) -> (TrackBuilder, Vec<EffectHandle>) {
+    let mut builder = TrackBuilder::new();
+    let handles = effects.iter().map(|x| x.insert(&mut builder)).collect();
+    (builder, handles)
+}
+
+impl Validate for Effect {
+    fn validate(&self) -> Result<(), Vec<ValidationError>> {
+        let which = self.name();
+        let mut errors = vec![];
+        let mut within = |value: Option<f64>, range: std::ops::RangeInclusive<f64>, what: &str| {
+            if let Some(value) = value
+                && !range.contains(&value)
+            {
+                errors.push(ValidationError {
+                    which,
+                    why: format!(
+                        "{what} must be between {:?} and {:?} (got {value})",
+                        range.start(),
+                        range.end()
+                    )
+                    .into(),
+                });
+            }
+        };
+        within(self.mix().map(f64::from), 0.0..=1.0, "mix");
+        match *self {
+            Self::LowPass(x) | Self::HighPass(x) => {
+                within(Some(x.cutoff), 20.0..=20_000.0, "cutoff");
+                within(x.resonance, 0.0..=1.0, "resonance");
+            }
+            Self::Distortion(_) => {}
+            Self::Delay(x) => {
+                within(
+                    x.delay_time.as_ref().map(Duration::as_secs_f64),
+                    0.001..=10.0,
+                    "delay_time (in seconds)",
+                );
+                if let Some(feedback) = x.feedback
+                    && feedback.as_factor() >= 1.0
+                {
+                    errors.push(ValidationError {
+                        which,
+                        why: "feedback must be below 0dB, otherwise it never fades out".into(),
+                    });
+                }
+            }
+            Self::Compressor(x) => {
+                within(x.threshold, -100.0..=0.0, "threshold");
+                within(x.ratio, 1.0..=100.0, "ratio");
+            }
+            Self::Reverb(x) => {
+                within(x.feedback, 0.0..=1.0, "feedback");
+                within(x.damping, 0.0..=1.0, "damping");
+                within(x.stereo_width, 0.0..=1.0, "stereo_width");
+            }
+        }
+        if errors.is_empty() {
+            return Ok(());
+        }
+        Err(errors)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use test_case::test_case;
+
+    use super::Effect;
+    use crate::Validate;
+
+    #[test_case(r##"type: low_pass
+cutoff: 3000"## ; "low-pass filter")]
+    #[test_case(r##"type: high_pass
+cutoff: 300
+resonance: 0.3"## ; "high-pass filter")]
+    #[test_case(r##"type: distortion
+kind: soft-clip
+drive: 6dB
+mix: 0.5"## ; "distortion")]
+    #[test_case(r##"type: delay
+delay_time: 250ms
+feedback: -6dB"## ; "delay")]
+    #[test_case(r##"type: compressor
+threshold: -24
+ratio: 4
+attack: 10ms
+release: 100ms
+makeup_gain: +3dB"## ; "compressor")]
+    #[test_case(r##"type: reverb
+feedback: 0.8
+damping: 0.3
+mix: 0.25"## ; "reverb")]
+    fn valid(yaml: &str) {
+        let effect = serde_yaml::from_str::<Effect>(yaml);
+        dbg!("{}", &effect);
+        assert!(effect.unwrap().validate().is_ok());
+    }
+
+    #[test_case(r##"type: low_pass
+cutoff: 0"## ; "cutoff too low")]
+    #[test_case(r##"type: reverb
+mix: 2"## ; "mix too high")]
+    #[test_case(r##"type: delay
+feedback: 0dB"## ; "delay never fading out")]
+    #[test_case(r##"type: compressor
+ratio: 0.5"## ; "expanding compressor")]
+    fn invalid(yaml: &str) {
+        let effect = serde_yaml::from_str::<Effect>(yaml);
+        dbg!("{}", &effect);
+        assert!(effect.unwrap().validate().is_err());
+    }
+
+    #[test_case(r##"type: flanger
+mix: 1"## ; "unsupported effect")]
+    #[test_case(r##"type: low_pass
+resonance: 0.5"## ; "filter without cutoff")]
+    fn unsupported(yaml: &str) {
+        let effect = serde_yaml::from_str::<Effect>(yaml);
+        dbg!("{}", &effect);
+        assert!(effect.is_err());
+    }
+}
@@ -36,9 +36,26 @@ mod tests {
     fem: ./somewhere/sfx.wav
     male: ./somewhere/else/sfx.wav
     usage: on-demand"## ; "explicit on-demand ono")]
+    #[test_case(r##"id:
+    any: ./somewhere/sfx.wav"## ; "gender-agnostic ono")]
+    #[test_case(r##"id:
+    fem: ./somewhere/sfx.wav
+    any: ./somewhere/else/sfx.wav"## ; "ono with gender-agnostic fallback")]
     fn ono(yaml: &str) {
         let ono = serde_yaml::from_str::<HashMap<String, Ono>>(yaml);
         dbg!("{}", &ono);
         assert!(ono.is_ok());
     }
+
+    #[test_case(r##"id:
+    fem: ./somewhere/sfx.wav"## ; "partially defined gender is not allowed")]
+    #[test_case(r##"id:
+    fem: ./somewhere/sfx.wav
+    male: ./somewhere/else/sfx.wav
+    any: ./elsewhere/sfx.wav"## ; "gender-agnostic fallback is not allowed alongside both genders")]
+    fn incompatibility(yaml: &str) {
+        let ono = serde_yaml::from_str::<HashMap<String, Ono>>(yaml);
+        dbg!("{}", &ono);
+        assert!(ono.is_err());
+    }
 }
@@ -1,6 +1,8 @@
 use std::time::Duration;
 
-use audioware_core::{Amplitude, Panning, With, error::ValidationError};
+use audioware_core::{
+    Amplitude, AttenuationCurve, Panning, TimeStretch, With, error::ValidationError,
+};
 use either::Either;
 use kira::{
     Decibels, PlaybackRate, Semitones, StartTime, Tween,
@@ -11,6 +13,8 @@ use kira::{
 };
 use serde::Deserialize;
 
+use crate::{Effect, Source};
+
 pub trait Validate {
     fn validate(&self) -> Result<(), Vec<ValidationError>>;
 }
@@ -28,15 +32,100 @@ pub struct Settings {
     pub start_time: Option<Duration>,
     #[serde(with = "humantime_serde", default)]
     pub start_position: Option<Duration>,
-    pub volume: Option<Amplitude>,
+    #[serde(deserialize_with = "amplitude_or_range", default)]
+    pub volume: Option<Ranged<Amplitude>>,
     pub panning: Option<f32>,
     #[serde(rename = "loop")]
     pub r#loop: Option<bool>,
     pub region: Option<self::Region>,
     #[serde(deserialize_with = "factor_or_semitones", default)]
-    pub playback_rate: Option<PlaybackRate>,
+    pub playback_rate: Option<Ranged<PlaybackRate>>,
     pub fade_in_tween: Option<Interpolation>,
-    pub affected_by_time_dilation: Option<bool>,
+    pub fade_out_tween: Option<Interpolation>,
+    pub on_stop: Option<OnStop>,
+    pub affected_by_time_dilation: Option<TimeDilation>,
+    pub normalize: Option<Normalize>,
+    /// Play backwards, static audio only.
+    pub reverse: Option<bool>,
+    /// Speed factor which preserves pitch, static audio only.
+    pub time_stretch: Option<f64>,
+    /// Named moments notifying scripts whenever reached.
+    pub markers: Option<Vec<Marker>>,
+    /// Inserted on sound own track, in declaration order.
+    pub effects: Option<Vec<Effect>>,
+    /// Only applies when played on emitters.
+    pub spatial: Option<Spatial>,
+    /// Caps how many instances of sound play at once.
+    pub max_instances: Option<u32>,
+    /// Where `max_instances` applies, globally by default.
+    pub instance_scope: Option<InstanceScope>,
+    /// Which instance gives way once `max_instances` is reached.
+    pub steal: Option<Steal>,
+    /// Drops plays arriving this soon after previous one.
+    #[serde(with = "humantime_serde", default)]
+    pub cooldown: Option<Duration>,
+    /// Delays plays arriving this soon after previous one.
+    #[serde(with = "humantime_serde", default)]
+    pub min_retrigger_interval: Option<Duration>,
+    /// Where `cooldown` or `min_retrigger_interval` applies, globally by default.
+    pub retrigger_scope: Option<InstanceScope>,
+    /// Tracks ducked while sound plays, instead of the ones its track ducks by default.
+    pub duck: Option<Vec<DuckTarget>>,
+    /// How deep and fast tracks get ducked.
+    pub ducking: Option<Ducking>,
+}
+
+impl Settings {
+    /// Cap on instances playing at once, if any.
+    pub fn instance_limit(&self) -> Option<InstanceLimit> {
+        Some(InstanceLimit {
+            max: self.max_instances?,
+            scope: self.instance_scope.unwrap_or_default(),
+            steal: self.steal.unwrap_or_default(),
+        })
+    }
+    /// Window in which plays are dropped or delayed, if any.
+    pub fn retrigger(&self) -> Option<Retrigger> {
+        let (window, queue) = match (self.cooldown, self.min_retrigger_interval) {
+            (Some(cooldown), _) => (cooldown, false),
+            (None, Some(interval)) => (interval, true),
+            (None, None) => return None,
+        };
+        Some(Retrigger {
+            window,
+            scope: self.retrigger_scope.unwrap_or_default(),
+            queue,
+        })
+    }
+    /// Only settings sampled anew on every play, if any.
+    pub fn randomized(&self) -> Option<Self> {
+        let volume = self.volume.filter(Ranged::is_range);
+        let playback_rate = self.playback_rate.filter(Ranged::is_range);
+        if volume.is_none() && playback_rate.is_none() {
+            return None;
+        }
+        Some(Self {
+            volume,
+            playback_rate,
+            ..Default::default()
+        })
+    }
+    /// Only settings applied to sound data, e.g. to play it again later.
+    ///
+    /// Leaves markers, effects and ducking out, so it is cheap to keep around.
+    pub fn replayed(&self) -> Self {
+        Self {
+            start_position: self.start_position,
+            volume: self.volume,
+            panning: self.panning,
+            r#loop: self.r#loop,
+            region: self.region.clone(),
+            playback_rate: self.playback_rate,
+            reverse: self.reverse,
+            time_stretch: self.time_stretch,
+            ..Default::default()
+        }
+    }
 }
 
 macro_rules! impl_with {
@@ -53,7 +142,7 @@ macro_rules! impl_with {
             $self = $self.start_position(x);
         }
         if let Some(x) = $settings.volume {
-            $self = $self.volume(x.as_decibels());
+            $self = $self.volume(x.sample().as_decibels());
         }
         if let Some(x) = $settings.panning {
             $self = $self.panning(x);
@@ -70,7 +159,7 @@ macro_rules! impl_with {
             }
         }
         if let Some(x) = $settings.playback_rate {
-            $self = $self.playback_rate(x);
+            $self = $self.playback_rate(x.sample());
         }
         if let Some(x) = $settings.fade_in_tween.map(Into::<Tween>::into) {
             $self = $self.fade_in_tween(x);
@@ -84,7 +173,16 @@ impl With<Settings> for StaticSoundData {
     where
         Self: Sized,
     {
-        impl_with!(self, settings)
+        let reverse = settings.reverse.unwrap_or(false);
+        let time_stretch = settings.time_stretch;
+        self = impl_with!(self, settings);
+        if reverse {
+            self = self.reverse(true);
+        }
+        if let Some(x) = time_stretch {
+            self = self.with(TimeStretch(x));
+        }
+        self
     }
 }
 
@@ -116,6 +214,239 @@ where
     }
 }
 
+/// Named moment within audio.
+#[derive(Debug, Deserialize, Clone, PartialEq)]
+pub struct Marker {
+    pub name: String,
+    #[serde(with = "humantime_serde")]
+    pub time: Duration,
+}
+
+/// Spatialization specific to sound.
+#[derive(Debug, Deserialize, Clone, PartialEq)]
+pub struct Spatial {
+    /// Overrides the attenuation of the emitter sound plays on.
+    pub attenuation: Option<AttenuationCurve>,
+}
+
+/// Loudness normalization, folded into volume on load.
+#[derive(Debug, Deserialize, Clone, Copy, PartialEq)]
+#[serde(untagged)]
+pub enum Normalize {
+    /// Normalize to [Source] default target when `true`.
+    Toggle(bool),
+    /// Normalize to given integrated loudness (in LUFS).
+    Target(#[serde(deserialize_with = "lufs")] f32),
+}
+
+/// Either a bare number or e.g. `-16 LUFS`.
+fn lufs<'de, D>(deserializer: D) -> Result<f32, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum NumberOrString {
+        Number(f32),
+        String(String),
+    }
+    match NumberOrString::deserialize(deserializer)? {
+        NumberOrString::Number(x) => Ok(x),
+        NumberOrString::String(s) => s
+            .trim()
+            .strip_suffix("LUFS")
+            .ok_or_else(|| format!("invalid loudness: {s} (expected e.g. -16 LUFS)"))
+            .and_then(|x| x.trim().parse::<f32>().map_err(|e| e.to_string()))
+            .map_err(serde::de::Error::custom),
+    }
+}
+
+/// Default [Normalize] for each section of a [Manifest](crate::Manifest),
+/// applied to its entries unless their own [Settings] override it.
+#[derive(Debug, Default, Deserialize, Clone, Copy, PartialEq)]
+pub struct Normalization {
+    pub sfx: Option<Normalize>,
+    pub onos: Option<Normalize>,
+    pub voices: Option<Normalize>,
+    pub music: Option<Normalize>,
+    #[doc(hidden)]
+    pub jingles: Option<Normalize>,
+}
+
+impl Normalization {
+    /// Default for given [Source], scene dialogs being voices.
+    pub fn get(&self, source: &Source) -> Option<Normalize> {
+        match source {
+            Source::Sfx => self.sfx,
+            Source::Ono => self.onos,
+            Source::Voices => self.voices,
+            Source::Music | Source::Playlist => self.music,
+            Source::Jingle => self.jingles,
+        }
+    }
+
+    /// Target integrated loudness (in LUFS) for an entry, if any:
+    /// `normalize: true` picks section default target when set,
+    /// [Source] default target otherwise.
+    pub fn target(&self, normalize: Option<Normalize>, source: &Source) -> Option<f32> {
+        let default = self.get(source);
+        match (normalize.or(default)?, default) {
+            (Normalize::Toggle(true), Some(Normalize::Target(x))) => Some(x),
+            (x, _) => x.target(source),
+        }
+    }
+}
+
+impl Normalize {
+    /// Ceiling never exceeded once normalized (in dBTP).
+    pub const CEILING: f32 = -1.0;
+    /// Accepted target integrated loudness (in LUFS).
+    pub const TARGETS: std::ops::RangeInclusive<f32> = -70.0..=0.0;
+
+    /// Target integrated loudness (in LUFS), if any.
+    pub fn target(&self, source: &Source) -> Option<f32> {
+        match self {
+            Self::Toggle(true) => Some(source.loudness_target()),
+            Self::Toggle(false) => None,
+            Self::Target(x) => Some(*x),
+        }
+    }
+}
+
+/// How audio stops when no tween is explicitly given.
+#[derive(Debug, Default, Deserialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "kebab-case")]
+pub enum OnStop {
+    /// Fade out with `fade_out_tween` if any.
+    #[default]
+    Fade,
+    /// Stop looping and finish current loop iteration.
+    FinishLoopIteration,
+    /// Stop looping and play until the end of audio.
+    PlayToEnd,
+}
+
+/// Where a cap on instances applies.
+#[derive(Debug, Default, Deserialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "kebab-case")]
+pub enum InstanceScope {
+    /// Across all instances of sound.
+    #[default]
+    Global,
+    /// Per entity sound plays on.
+    PerEntity,
+    /// Per emitter (entity and tag) sound plays on.
+    PerEmitter,
+}
+
+/// Which instance gives way once cap is reached.
+#[derive(Debug, Default, Deserialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "kebab-case")]
+pub enum Steal {
+    /// Oldest instance fades out.
+    #[default]
+    Oldest,
+    /// Quietest instance fades out, volume and attenuation included.
+    Quietest,
+    /// Instance farthest from listener fades out.
+    Farthest,
+    /// New instance does not play.
+    RejectNew,
+}
+
+/// Cap on instances of a sound, see [Settings::instance_limit].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct InstanceLimit {
+    pub max: u32,
+    pub scope: InstanceScope,
+    pub steal: Steal,
+}
+
+/// Window following a play, see [Settings::retrigger].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Retrigger {
+    pub window: Duration,
+    pub scope: InstanceScope,
+    /// Whether plays inside window are delayed until it ends, or dropped.
+    pub queue: bool,
+}
+
+/// Track which can be ducked, see [Settings::duck].
+#[derive(Debug, Deserialize, Clone, Copy, PartialEq, Eq, Hash)]
+#[serde(rename_all = "kebab-case")]
+pub enum DuckTarget {
+    Music,
+    Sfx,
+    Radioport,
+    CarRadio,
+}
+
+impl DuckTarget {
+    pub const ALL: [Self; 4] = [Self::Music, Self::Sfx, Self::Radioport, Self::CarRadio];
+}
+
+/// How ducked tracks fade, see [Settings::ducking].
+#[derive(Debug, Default, Deserialize, Clone, Copy, PartialEq)]
+pub struct Ducking {
+    /// Volume ducked tracks fade to, e.g. `-9dB`.
+    pub depth: Option<Amplitude>,
+    /// How long ducked tracks take to fade down.
+    #[serde(with = "humantime_serde", default)]
+    pub attack: Option<Duration>,
+    /// How long ducked tracks take to fade back up, once sound ends.
+    #[serde(with = "humantime_serde", default)]
+    pub release: Option<Duration>,
+}
+
+/// How audio reacts to time dilation (e.g. Sandevistan).
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub enum TimeDilation {
+    /// Not affected.
+    Unaffected,
+    /// Slowed down or sped up, along with its pitch.
+    #[default]
+    Pitch,
+    /// Slowed down or sped up, preserving its pitch.
+    Stretch,
+}
+
+impl TimeDilation {
+    pub fn is_affected(&self) -> bool {
+        *self != Self::Unaffected
+    }
+}
+
+impl From<bool> for TimeDilation {
+    fn from(value: bool) -> Self {
+        if value { Self::Pitch } else { Self::Unaffected }
+    }
+}
+
+impl<'de> Deserialize<'de> for TimeDilation {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        #[derive(Deserialize)]
+        #[serde(rename_all = "kebab-case")]
+        enum Mode {
+            Pitch,
+            Stretch,
+        }
+        #[derive(Deserialize)]
+        #[serde(untagged)]
+        enum ToggleOrMode {
+            Toggle(bool),
+            Mode(Mode),
+        }
+        Ok(match ToggleOrMode::deserialize(deserializer)? {
+            ToggleOrMode::Toggle(x) => x.into(),
+            ToggleOrMode::Mode(Mode::Pitch) => Self::Pitch,
+            ToggleOrMode::Mode(Mode::Stretch) => Self::Stretch,
+        })
+    }
+}
+
 /// Deserialization type
 /// for [kira::sound::Region].
 #[derive(Debug, Deserialize, Clone)]
@@ -165,45 +496,139 @@ impl IntoOptionalRegion for self::Region {
     }
 }
 
-fn factor_or_semitones<'de, D>(deserializer: D) -> Result<Option<PlaybackRate>, D::Error>
+fn factor_or_semitones<'de, D>(deserializer: D) -> Result<Option<Ranged<PlaybackRate>>, D::Error>
 where
     D: serde::Deserializer<'de>,
 {
     let s: Option<&str> = Deserialize::deserialize(deserializer)?;
-    if let Some(s) = s {
-        let s = s.trim();
-        if s.starts_with('x') || s.starts_with('X') {
-            return Ok(Some(PlaybackRate(
-                s[1..].trim().parse().map_err(serde::de::Error::custom)?,
-            )));
-        }
-        if s.ends_with('♯') {
-            return Ok(Some(
-                Semitones(
-                    s[..(s.len() - '♯'.len_utf8())]
-                        .trim()
-                        .parse()
-                        .map_err(serde::de::Error::custom)?,
-                )
-                .into(),
-            ));
-        }
-        if s.ends_with('♭') {
-            return Ok(Some(
-                Semitones(
-                    -s[..(s.len() - '♭'.len_utf8())]
-                        .trim()
-                        .parse()
-                        .map_err(serde::de::Error::custom)?,
-                )
-                .into(),
-            ));
+    s.map(|s| ranged(s, parse_factor_or_semitones))
+        .transpose()
+        .map_err(serde::de::Error::custom)
+}
+
+fn amplitude_or_range<'de, D>(deserializer: D) -> Result<Option<Ranged<Amplitude>>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum AmplitudeOrRange {
+        Amplitude(Amplitude),
+        Range(String),
+    }
+    let value: Option<AmplitudeOrRange> = Deserialize::deserialize(deserializer)?;
+    match value {
+        Some(AmplitudeOrRange::Amplitude(x)) => Ok(Some(Ranged::Fixed(x))),
+        Some(AmplitudeOrRange::Range(s)) => {
+            ranged(&s, |x| x.parse::<Amplitude>().map_err(|e| e.to_string()))
+                .map(Some)
+                .map_err(serde::de::Error::custom)
+        }
+        None => Ok(None),
+    }
+}
+
+/// Parse either a single value or a `min..max` range.
+fn ranged<T>(s: &str, parse: impl Fn(&str) -> Result<T, String>) -> Result<Ranged<T>, String> {
+    match s.split_once("..") {
+        Some((min, max)) => Ok(Ranged::Range {
+            min: parse(min.trim())?,
+            max: parse(max.trim())?,
+        }),
+        None => parse(s.trim()).map(Ranged::Fixed),
+    }
+}
+
+fn parse_factor_or_semitones(s: &str) -> Result<PlaybackRate, String> {
+    if s.starts_with('x') || s.starts_with('X') {
+        return Ok(PlaybackRate(
+            s[1..].trim().parse().map_err(|e| format!("{e}"))?,
+        ));
+    }
+    if s.ends_with('♯') {
+        return Ok(Semitones(
+            s[..(s.len() - '♯'.len_utf8())]
+                .trim()
+                .parse()
+                .map_err(|e| format!("{e}"))?,
+        )
+        .into());
+    }
+    // flat always lowers pitch, so `-1♭` reads just like `1♭`
+    if s.ends_with('♭') {
+        return Ok(Semitones(
+            -s[..(s.len() - '♭'.len_utf8())]
+                .trim()
+                .parse::<f64>()
+                .map_err(|e| format!("{e}"))?
+                .abs(),
+        )
+        .into());
+    }
+    Err(format!("invalid factor or semitone: {s}"))
+}
+
+/// Either a fixed value, or a range sampled anew on every play.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Ranged<T> {
+    Fixed(T),
+    Range { min: T, max: T },
+}
+
+impl<T: Copy> Ranged<T> {
+    pub fn min(&self) -> T {
+        match self {
+            Self::Fixed(x) | Self::Range { min: x, .. } => *x,
+        }
+    }
+    pub fn max(&self) -> T {
+        match self {
+            Self::Fixed(x) | Self::Range { max: x, .. } => *x,
+        }
+    }
+    pub fn is_range(&self) -> bool {
+        matches!(self, Self::Range { .. })
+    }
+    pub fn map<U>(self, f: impl Fn(T) -> U) -> Ranged<U> {
+        match self {
+            Self::Fixed(x) => Ranged::Fixed(f(x)),
+            Self::Range { min, max } => Ranged::Range {
+                min: f(min),
+                max: f(max),
+            },
+        }
+    }
+}
+
+impl Ranged<Amplitude> {
+    /// Sample volume, uniformly in decibels.
+    pub fn sample(&self) -> Amplitude {
+        match self {
+            Self::Fixed(x) => *x,
+            Self::Range { min, max } => {
+                let (min, max) = (min.as_decibels().0, max.as_decibels().0);
+                Amplitude::from(Decibels(fastrand_between(min as f64, max as f64) as f32))
+            }
+        }
+    }
+}
+
+impl Ranged<PlaybackRate> {
+    /// Sample playback rate, uniformly in semitones.
+    pub fn sample(&self) -> PlaybackRate {
+        match self {
+            Self::Fixed(x) => *x,
+            Self::Range { min, max } => {
+                let (min, max) = (min.0.log2(), max.0.log2());
+                PlaybackRate(fastrand_between(min, max).exp2())
+            }
         }
-        return Err(serde::de::Error::custom(format!(
-            "invalid factor or semitone: {s}"
-        )));
     }
-    Ok(None)
+}
+
+#[inline]
+fn fastrand_between(min: f64, max: f64) -> f64 {
+    min + (max - min) * fastrand::f64()
 }
 
 /// Deserialization type for [kira::Tween].
@@ -234,7 +659,7 @@ impl From<Interpolation> for Tween {
 }
 
 macro_rules! impl_from_settings {
-    ($into:path) => {
+    ($into:path $(, $flag:ident)?) => {
         #[allow(clippy::needless_update)]
         impl From<self::Settings> for $into {
             fn from(value: self::Settings) -> Self {
@@ -249,7 +674,7 @@ macro_rules! impl_from_settings {
                         .unwrap_or_default(),
                     volume: value
                         .volume
-                        .map(|x| ::kira::Value::Fixed(x.as_decibels()))
+                        .map(|x| ::kira::Value::Fixed(x.sample().as_decibels()))
                         .unwrap_or_default(),
                     panning: value
                         .panning
@@ -265,7 +690,11 @@ macro_rules! impl_from_settings {
                     } else {
                         None
                     },
-                    playback_rate: value.playback_rate.map(Into::into).unwrap_or_default(),
+                    playback_rate: value
+                        .playback_rate
+                        .map(|x| x.sample().into())
+                        .unwrap_or_default(),
+                    $($flag: value.$flag.unwrap_or_default(),)?
                     ..Default::default()
                 }
             }
@@ -273,7 +702,7 @@ macro_rules! impl_from_settings {
     };
 }
 
-impl_from_settings!(::kira::sound::static_sound::StaticSoundSettings);
+impl_from_settings!(::kira::sound::static_sound::StaticSoundSettings, reverse);
 impl_from_settings!(::kira::sound::streaming::StreamingSoundSettings);
 
 impl Validate for Settings {
@@ -285,11 +714,153 @@ impl Validate for Settings {
             errors.push(e.into());
         }
         if let Some(volume) = self.volume
-            && volume.as_decibels() > Decibels(85.0)
+            && volume.max().as_decibels() > Decibels(85.0)
+        {
+            errors.push(ValidationError {
+                which: "volume",
+                why: format!(
+                    "audio should not be louder than +85dB (got {:+.1}dB)",
+                    volume.max().as_decibels().0
+                )
+                .into(),
+            });
+        }
+        if let Some(Ranged::Range { min, max }) = self.volume
+            && min.as_decibels() > max.as_decibels()
         {
             errors.push(ValidationError {
                 which: "volume",
-                why: "audio should not be louder than 85.0 dB",
+                why: format!(
+                    "range must start below its end (got {:+.1}dB..{:+.1}dB)",
+                    min.as_decibels().0,
+                    max.as_decibels().0
+                )
+                .into(),
+            });
+        }
+        if self.playback_rate.is_some_and(|x| x.min().0 <= 0.0) {
+            errors.push(ValidationError {
+                which: "playback_rate",
+                why: "must be greater than 0.0".into(),
+            });
+        }
+        if let Some(Ranged::Range { min, max }) = self.playback_rate {
+            if min.0 > max.0 {
+                errors.push(ValidationError {
+                    which: "playback_rate",
+                    why: "range must start below its end".into(),
+                });
+            }
+        }
+        if self.fade_out_tween.is_some() && self.on_stop.is_some_and(|x| x != OnStop::Fade) {
+            errors.push(ValidationError {
+                which: "fade_out_tween",
+                why: "fade-out tween is only used when stopping with fade".into(),
+            });
+        }
+        if let Some(time_stretch) = self.time_stretch
+            && !(0.25..=4.0).contains(&time_stretch)
+        {
+            errors.push(ValidationError {
+                which: "time_stretch",
+                why: format!("must be between 0.25 and 4.0 (got {time_stretch})").into(),
+            });
+        }
+        if let Some(markers) = self.markers.as_deref() {
+            if markers.iter().any(|x| x.name.trim().is_empty()) {
+                errors.push(ValidationError {
+                    which: "markers",
+                    why: "name cannot be empty".into(),
+                });
+            }
+            if let Some(duplicate) = markers
+                .iter()
+                .enumerate()
+                .find(|(i, x)| markers[..*i].iter().any(|y| y.name == x.name))
+                .map(|(_, x)| x)
+            {
+                errors.push(ValidationError {
+                    which: "markers",
+                    why: format!("name must be unique (got {} twice)", duplicate.name).into(),
+                });
+            }
+        }
+        for effect in self.effects.iter().flatten() {
+            if let Err(e) = effect.validate() {
+                errors.extend(e);
+            }
+        }
+        if let Some(AttenuationCurve::Points(points)) =
+            self.spatial.as_ref().and_then(|x| x.attenuation.as_ref())
+        {
+            let mut why = vec![];
+            if points.is_empty() {
+                why.push("must contain at least one point".to_string());
+            }
+            if points.iter().any(|x| x.distance < 0.) {
+                why.push("distance cannot be negative".to_string());
+            }
+            if points.windows(2).any(|x| x[0].distance >= x[1].distance) {
+                why.push("distances must be strictly increasing".to_string());
+            }
+            if points.iter().any(|x| x.gain.as_factor() > 1.) {
+                why.push("gain cannot exceed 0dB".to_string());
+            }
+            errors.extend(why.into_iter().map(|why| ValidationError {
+                which: "spatial.attenuation",
+                why: why.into(),
+            }));
+        }
+        if self.max_instances == Some(0) {
+            errors.push(ValidationError {
+                which: "max_instances",
+                why: "must be at least 1".into(),
+            });
+        }
+        if self.max_instances.is_none() && (self.instance_scope.is_some() || self.steal.is_some()) {
+            errors.push(ValidationError {
+                which: "max_instances",
+                why: "instance scope and steal policy are only used along with max instances"
+                    .into(),
+            });
+        }
+        if self.cooldown.is_some() && self.min_retrigger_interval.is_some() {
+            errors.push(ValidationError {
+                which: "cooldown",
+                why: "cannot be used along with min retrigger interval".into(),
+            });
+        }
+        if self.cooldown == Some(Duration::ZERO)
+            || self.min_retrigger_interval == Some(Duration::ZERO)
+        {
+            errors.push(ValidationError {
+                which: "cooldown",
+                why: "cooldown and min retrigger interval must be greater than zero".into(),
+            });
+        }
+        if self.retrigger_scope.is_some()
+            && self.cooldown.is_none()
+            && self.min_retrigger_interval.is_none()
+        {
+            errors.push(ValidationError {
+                which: "retrigger_scope",
+                why: "only used along with cooldown or min retrigger interval".into(),
+            });
+        }
+        if let Some(depth) = self.ducking.and_then(|x| x.depth)
+            && depth.as_decibels() > Decibels::IDENTITY
+        {
+            errors.push(ValidationError {
+                which: "ducking",
+                why: "depth cannot be louder than 0dB".into(),
+            });
+        }
+        if let Some(Normalize::Target(target)) = self.normalize
+            && !Normalize::TARGETS.contains(&target)
+        {
+            errors.push(ValidationError {
+                which: "normalize",
+                why: "target loudness must be between -70.0 and 0.0 LUFS".into(),
             });
         }
         if errors.is_empty() {
@@ -306,46 +877,89 @@ impl Validate for Option<&Settings> {
 }
 
 impl ValidateFor<Either<StaticSoundData, StreamingSoundData<FromFileError>>> for Settings {
+    #[inline]
     fn validate_for(
         &self,
         audio: &Either<StaticSoundData, StreamingSoundData<FromFileError>>,
     ) -> Result<(), Vec<ValidationError>> {
+        self.validate_for_probed(audio, None)
+    }
+}
+
+impl Settings {
+    /// Validate settings for audio,
+    /// given streaming audio sample rate if probed.
+    pub fn validate_for_probed(
+        &self,
+        audio: &Either<StaticSoundData, StreamingSoundData<FromFileError>>,
+        sample_rate: Option<u32>,
+    ) -> Result<(), Vec<ValidationError>> {
+        let sample_rate = match audio {
+            Either::Left(data) => Some(data.sample_rate),
+            Either::Right(_) => sample_rate,
+        };
         let mut errors = vec![];
+        if audio.is_right() {
+            if self.reverse.unwrap_or(false) {
+                errors.push(ValidationError {
+                    which: "reverse",
+                    why: "not supported with streaming audio".into(),
+                });
+            }
+            if self.time_stretch.is_some() {
+                errors.push(ValidationError {
+                    which: "time_stretch",
+                    why: "not supported with streaming audio".into(),
+                });
+            }
+        }
+        if let Some(markers) = self.markers.as_deref() {
+            let total_duration = match audio {
+                Either::Left(x) => x.unsliced_duration(),
+                Either::Right(x) => x.unsliced_duration(),
+            };
+            for marker in markers.iter().filter(|x| x.time >= total_duration) {
+                errors.push(ValidationError {
+                    which: "markers",
+                    why: format!("{} is past audio duration", marker.name).into(),
+                });
+            }
+        }
         if let Some(ref region) = self.region {
             let total_duration = match audio {
                 Either::Left(x) => x.unsliced_duration(),
                 Either::Right(x) => x.unsliced_duration(),
             }
             .as_secs_f64();
-            let start: f64 = match (region.starts(), audio) {
+            let start: f64 = match (region.starts(), sample_rate) {
                 (Some(PlaybackPosition::Seconds(seconds)), _) => seconds,
-                (Some(PlaybackPosition::Samples(samples)), Either::Left(data)) => {
-                    samples as f64 / data.sample_rate as f64
+                (Some(PlaybackPosition::Samples(samples)), Some(sample_rate)) => {
+                    samples as f64 / sample_rate as f64
                 }
-                // no sample rate method yet
-                (Some(PlaybackPosition::Samples(_)), Either::Right(_)) => {
+                (Some(PlaybackPosition::Samples(_)), None) => {
                     errors.push(ValidationError {
                         which: "region.starts",
-                        why: "samples unit is not supported with streaming sound yet",
+                        why: "samples unit requires audio sample rate to be probed".into(),
                     });
                     return Err(errors);
                 }
                 // none implicitly means beginning of the audio
                 (None, _) => 0.0,
             };
-            let end: f64 = match (region.ends(), audio) {
+            let end: f64 = match (region.ends(), sample_rate) {
                 (Some(EndPosition::Custom(PlaybackPosition::Seconds(x))), _) => x,
                 (
                     Some(EndPosition::Custom(PlaybackPosition::Samples(samples))),
-                    Either::Left(data),
-                ) => samples as f64 / data.sample_rate as f64,
-                // no sample rate method yet
-                (Some(EndPosition::Custom(PlaybackPosition::Samples(_))), Either::Right(_)) => {
-                    errors.push(ValidationError { which: "region.ends", why: "samples unit is not supported with streaming sound yet" });
+                    Some(sample_rate),
+                ) => samples as f64 / sample_rate as f64,
+                (Some(EndPosition::Custom(PlaybackPosition::Samples(_))), None) => {
+                    errors.push(ValidationError {
+                        which: "region.ends",
+                        why: "samples unit requires audio sample rate to be probed".into(),
+                    });
                     return Err(errors);
                 }
-                (Some(EndPosition::EndOfAudio), Either::Left(_)) |
-                (Some(EndPosition::EndOfAudio), Either::Right(_)) |
+                (Some(EndPosition::EndOfAudio), _) |
                 // none implicitly means end of the audio
                 (None, _) => total_duration,
             };
@@ -357,7 +971,7 @@ impl ValidateFor<Either<StaticSoundData, StreamingSoundData<FromFileError>>> for
             {
                 errors.push(ValidationError {
                     which: "region",
-                    why: "must be within audio duration and starts before it ends",
+                    why: "must be within audio duration and starts before it ends".into(),
                 });
             }
             if let Some(start_position) = self.start_position.map(|x| x.as_secs_f64())
@@ -365,7 +979,7 @@ impl ValidateFor<Either<StaticSoundData, StreamingSoundData<FromFileError>>> for
             {
                 errors.push(ValidationError {
                     which: "start_position",
-                    why: "greater than audio duration",
+                    why: "greater than audio duration".into(),
                 });
             }
         } else if let Some(start_position) = self.start_position.map(|x| x.as_secs_f64()) {
@@ -377,7 +991,7 @@ impl ValidateFor<Either<StaticSoundData, StreamingSoundData<FromFileError>>> for
             if start_position >= duration {
                 errors.push(ValidationError {
                     which: "start_position",
-                    why: "greater than audio duration",
+                    why: "greater than audio duration".into(),
                 });
             }
         }
@@ -407,13 +1021,41 @@ impl ValidateFor<Either<StaticSoundData, StreamingSoundData<FromFileError>>> for
     }
 }
 
+impl Validate for Normalization {
+    fn validate(&self) -> Result<(), Vec<ValidationError>> {
+        let errors = [
+            ("sfx", self.sfx),
+            ("onos", self.onos),
+            ("voices", self.voices),
+            ("music", self.music),
+            ("jingles", self.jingles),
+        ]
+        .into_iter()
+        .filter_map(|(which, x)| match x {
+            Some(Normalize::Target(target)) if !Normalize::TARGETS.contains(&target) => {
+                Some(ValidationError {
+                    which,
+                    why: "target loudness must be between -70.0 and 0.0 LUFS".into(),
+                })
+            }
+            _ => None,
+        })
+        .collect::<Vec<_>>();
+        if errors.is_empty() {
+            return Ok(());
+        }
+        Err(errors)
+    }
+}
+
 #[cfg(test)]
 mod tests {
-    use std::collections::HashMap;
+    use std::{collections::HashMap, time::Duration};
 
     use test_case::test_case;
 
-    use super::Settings;
+    use super::{DuckTarget, InstanceScope, Normalize, Settings, Steal, TimeDilation};
+    use crate::Source;
 
     mod duration {
         use test_case::test_case;
@@ -470,9 +1112,338 @@ mod tests {
     fade_in_tween:
         duration: 9s
         InPowi: 2"## ; "complex settings")]
+    #[test_case(r##"settings:
+    volume: 0.8..1.0"## ; "volume range")]
+    #[test_case(r##"settings:
+    playback_rate: 1♭..1♯"## ; "playback rate range in semitones")]
+    #[test_case(r##"settings:
+    playback_rate: x0.9..x1.1"## ; "playback rate range as factors")]
+    #[test_case(r##"settings:
+    normalize: true"## ; "normalize to default target")]
+    #[test_case(r##"settings:
+    normalize: -16"## ; "normalize to integer target")]
+    #[test_case(r##"settings:
+    normalize: -14.5
+    volume: 0.5"## ; "normalize to target + volume")]
+    #[test_case(r##"settings:
+    reverse: true"## ; "reverse")]
+    #[test_case(r##"settings:
+    time_stretch: 0.8"## ; "time stretch")]
+    #[test_case(r##"settings:
+    affected_by_time_dilation: false"## ; "unaffected by time dilation")]
+    #[test_case(r##"settings:
+    affected_by_time_dilation: stretch"## ; "time dilation preserving pitch")]
+    #[test_case(r##"settings:
+    markers:
+      - name: door_slam
+        time: 1s
+      - name: lights_out
+        time: 2500ms"## ; "markers")]
+    #[test_case(r##"settings:
+    effects:
+      - type: high_pass
+        cutoff: 300
+      - type: low_pass
+        cutoff: 3000
+      - type: distortion
+        drive: 6dB
+        mix: 0.3"## ; "effects")]
+    #[test_case(r##"settings:
+    spatial:
+      attenuation: inverse-square"## ; "attenuation preset")]
+    #[test_case(r##"settings:
+    spatial:
+      attenuation:
+        - distance: 2
+          gain: 1.0
+        - distance: 15
+          gain: -12dB
+        - distance: 40
+          gain: 0.0"## ; "attenuation points")]
+    #[test_case(r##"settings:
+    normalize: -16 LUFS"## ; "normalize to target in LUFS")]
+    #[test_case(r##"settings:
+    volume: -6dB"## ; "volume in decibels")]
+    #[test_case(r##"settings:
+    volume: +3 dB"## ; "volume in positive decibels")]
+    #[test_case(r##"settings:
+    volume: 50%"## ; "volume in percent")]
+    #[test_case(r##"settings:
+    volume: -12dB..-6dB"## ; "volume range in decibels")]
+    #[test_case(r##"settings:
+    fade_out_tween:
+        duration: 2s
+        OutPowi: 2"## ; "fade-out tween")]
+    #[test_case(r##"settings:
+    loop: true
+    on_stop: finish-loop-iteration"## ; "finish loop iteration on stop")]
+    #[test_case(r##"settings:
+    on_stop: play-to-end"## ; "play to end on stop")]
+    #[test_case(r##"settings:
+    on_stop: fade
+    fade_out_tween:
+        duration: 500ms
+        Linear:"## ; "fade on stop with fade-out tween")]
     fn settings(yaml: &str) {
         let settings = serde_yaml::from_str::<HashMap<String, Settings>>(yaml);
         dbg!("{}", &settings);
         assert!(settings.is_ok());
     }
+
+    #[test_case(r##"normalize: 6"## ; "louder than full scale")]
+    #[test_case(r##"normalize: -90"## ; "quieter than absolute gate")]
+    fn invalid_normalize(yaml: &str) {
+        use super::Validate;
+        let settings = serde_yaml::from_str::<Settings>(yaml);
+        dbg!("{}", &settings);
+        assert!(settings.unwrap().validate().is_err());
+    }
+
+    #[test_case(r##"voices: -16 LUFS"##, None, Source::Voices, Some(-16.0) ; "section target")]
+    #[test_case(r##"voices: -16 LUFS"##, Some(Normalize::Toggle(true)), Source::Voices, Some(-16.0) ; "toggled to section target")]
+    #[test_case(r##"voices: -16 LUFS"##, Some(Normalize::Target(-12.0)), Source::Voices, Some(-12.0) ; "entry target wins")]
+    #[test_case(r##"voices: -16 LUFS"##, Some(Normalize::Toggle(false)), Source::Voices, None ; "entry opts out")]
+    #[test_case(r##"voices: -16 LUFS"##, None, Source::Sfx, None ; "other section")]
+    #[test_case(r##"sfx: true"##, None, Source::Sfx, Some(-20.0) ; "section toggled to source target")]
+    #[test_case(r##"sfx: false"##, Some(Normalize::Toggle(true)), Source::Sfx, Some(-20.0) ; "entry opts in")]
+    fn normalization(
+        yaml: &str,
+        normalize: Option<Normalize>,
+        source: Source,
+        target: Option<f32>,
+    ) {
+        use super::{Normalization, Validate};
+        let normalization = serde_yaml::from_str::<Normalization>(yaml).unwrap();
+        dbg!("{}", &normalization);
+        assert!(normalization.validate().is_ok());
+        assert_eq!(normalization.target(normalize, &source), target);
+    }
+
+    #[test_case(r##"music: -90"## ; "quieter than absolute gate")]
+    fn invalid_normalization(yaml: &str) {
+        use super::{Normalization, Validate};
+        let normalization = serde_yaml::from_str::<Normalization>(yaml);
+        dbg!("{}", &normalization);
+        assert!(normalization.unwrap().validate().is_err());
+    }
+
+    #[test_case(r##"on_stop: play-to-end
+fade_out_tween:
+    duration: 1s
+    Linear:"## ; "fade-out tween when playing to end")]
+    #[test_case(r##"on_stop: fade-out"## ; "unknown policy")]
+    fn invalid_on_stop(yaml: &str) {
+        use super::Validate;
+        let settings = serde_yaml::from_str::<Settings>(yaml);
+        dbg!("{}", &settings);
+        assert!(settings.is_err() || settings.unwrap().validate().is_err());
+    }
+
+    #[test_case(r##"max_instances: 3"##, InstanceScope::Global, Steal::Oldest ; "defaults")]
+    #[test_case(r##"max_instances: 1
+instance_scope: per-entity
+steal: reject-new"##, InstanceScope::PerEntity, Steal::RejectNew ; "per entity rejecting new")]
+    #[test_case(r##"max_instances: 2
+instance_scope: per-emitter
+steal: quietest"##, InstanceScope::PerEmitter, Steal::Quietest ; "per emitter stealing quietest")]
+    #[test_case(r##"max_instances: 4
+steal: farthest"##, InstanceScope::Global, Steal::Farthest ; "globally stealing farthest")]
+    fn instance_limit(yaml: &str, scope: InstanceScope, steal: Steal) {
+        use super::Validate;
+        let settings = serde_yaml::from_str::<Settings>(yaml).unwrap();
+        dbg!("{}", &settings);
+        assert!(settings.validate().is_ok());
+        let limit = settings.instance_limit().unwrap();
+        assert_eq!(limit.scope, scope);
+        assert_eq!(limit.steal, steal);
+    }
+
+    #[test_case(r##"max_instances: 0"## ; "no instance at all")]
+    #[test_case(r##"steal: oldest"## ; "steal without max instances")]
+    #[test_case(r##"instance_scope: per-entity"## ; "scope without max instances")]
+    #[test_case(r##"max_instances: 2
+steal: newest"## ; "unknown steal policy")]
+    #[test_case(r##"max_instances: -1"## ; "negative max instances")]
+    fn invalid_instance_limit(yaml: &str) {
+        use super::Validate;
+        let settings = serde_yaml::from_str::<Settings>(yaml);
+        dbg!("{}", &settings);
+        assert!(settings.is_err() || settings.unwrap().validate().is_err());
+    }
+
+    #[test_case(r##"cooldown: 500ms"##, 500, false, InstanceScope::Global ; "cooldown")]
+    #[test_case(r##"min_retrigger_interval: 80ms
+retrigger_scope: per-entity"##, 80, true, InstanceScope::PerEntity ; "interval per entity")]
+    #[test_case(r##"cooldown: 2s
+retrigger_scope: per-emitter"##, 2000, false, InstanceScope::PerEmitter ; "cooldown per emitter")]
+    fn retrigger(yaml: &str, millis: u64, queue: bool, scope: InstanceScope) {
+        use super::Validate;
+        let settings = serde_yaml::from_str::<Settings>(yaml).unwrap();
+        dbg!("{}", &settings);
+        assert!(settings.validate().is_ok());
+        let retrigger = settings.retrigger().unwrap();
+        assert_eq!(retrigger.window, Duration::from_millis(millis));
+        assert_eq!(retrigger.queue, queue);
+        assert_eq!(retrigger.scope, scope);
+    }
+
+    #[test_case(r##"cooldown: 0s"## ; "zero cooldown")]
+    #[test_case(r##"min_retrigger_interval: 0ms"## ; "zero interval")]
+    #[test_case(r##"cooldown: 1s
+min_retrigger_interval: 100ms"## ; "both cooldown and interval")]
+    #[test_case(r##"retrigger_scope: global"## ; "scope without window")]
+    #[test_case(r##"cooldown: soon"## ; "unparseable cooldown")]
+    fn invalid_retrigger(yaml: &str) {
+        use super::Validate;
+        let settings = serde_yaml::from_str::<Settings>(yaml);
+        dbg!("{}", &settings);
+        assert!(settings.is_err() || settings.unwrap().validate().is_err());
+    }
+
+    #[test_case(r##"duck: [music, sfx]"##, Some(vec![DuckTarget::Music, DuckTarget::Sfx]) ; "targets")]
+    #[test_case(r##"duck: []"##, Some(vec![]) ; "no target")]
+    #[test_case(r##"duck: [car-radio]
+ducking:
+  depth: -12dB
+  attack: 100ms
+  release: 1s"##, Some(vec![DuckTarget::CarRadio]) ; "targets with ducking")]
+    #[test_case(r##"ducking:
+  depth: 50%"##, None ; "ducking only")]
+    fn duck(yaml: &str, targets: Option<Vec<DuckTarget>>) {
+        use super::Validate;
+        let settings = serde_yaml::from_str::<Settings>(yaml).unwrap();
+        dbg!("{}", &settings);
+        assert!(settings.validate().is_ok());
+        assert_eq!(settings.duck, targets);
+    }
+
+    #[test_case(r##"duck: [dialogue]"## ; "unknown target")]
+    #[test_case(r##"duck: music"## ; "not a list")]
+    #[test_case(r##"ducking:
+  depth: +3dB"## ; "louder depth")]
+    #[test_case(r##"ducking:
+  attack: fast"## ; "unparseable attack")]
+    fn invalid_duck(yaml: &str) {
+        use super::Validate;
+        let settings = serde_yaml::from_str::<Settings>(yaml);
+        dbg!("{}", &settings);
+        assert!(settings.is_err() || settings.unwrap().validate().is_err());
+    }
+
+    #[test_case(r##"volume: 1.0..0.5"## ; "reversed volume range")]
+    #[test_case(r##"playback_rate: 1♯..1♭"## ; "reversed playback rate range")]
+    #[test_case(r##"volume: 1.0..100000.0"## ; "volume range too loud")]
+    #[test_case(r##"volume: -6dB..-12dB"## ; "reversed volume range in decibels")]
+    #[test_case(r##"volume: +90dB"## ; "volume too loud in decibels")]
+    fn invalid_range(yaml: &str) {
+        use super::Validate;
+        let settings = serde_yaml::from_str::<Settings>(yaml);
+        dbg!("{}", &settings);
+        assert!(settings.unwrap().validate().is_err());
+    }
+
+    #[test_case(r##"time_stretch: 8"## ; "time stretch too fast")]
+    #[test_case(r##"time_stretch: 0"## ; "time stretch at a standstill")]
+    fn invalid_time_stretch(yaml: &str) {
+        use super::Validate;
+        let settings = serde_yaml::from_str::<Settings>(yaml);
+        dbg!("{}", &settings);
+        assert!(settings.unwrap().validate().is_err());
+    }
+
+    #[test_case(r##"markers:
+  - name: ""
+    time: 1s"## ; "marker without name")]
+    #[test_case(r##"markers:
+  - name: door_slam
+    time: 1s
+  - name: door_slam
+    time: 2s"## ; "duplicate markers")]
+    fn invalid_markers(yaml: &str) {
+        use super::Validate;
+        let settings = serde_yaml::from_str::<Settings>(yaml);
+        dbg!("{}", &settings);
+        assert!(settings.unwrap().validate().is_err());
+    }
+
+    #[test_case(r##"spatial:
+  attenuation: []"## ; "attenuation without points")]
+    #[test_case(r##"spatial:
+  attenuation:
+    - distance: 10
+      gain: 0.5
+    - distance: 5
+      gain: 0.2"## ; "attenuation points out of order")]
+    #[test_case(r##"spatial:
+  attenuation:
+    - distance: 1
+      gain: +6dB"## ; "attenuation louder than full scale")]
+    fn invalid_attenuation(yaml: &str) {
+        use super::Validate;
+        let settings = serde_yaml::from_str::<Settings>(yaml);
+        dbg!("{}", &settings);
+        assert!(settings.unwrap().validate().is_err());
+    }
+
+    #[test_case(r##"affected_by_time_dilation: true"##, TimeDilation::Pitch ; "affected")]
+    #[test_case(r##"affected_by_time_dilation: false"##, TimeDilation::Unaffected ; "unaffected")]
+    #[test_case(r##"affected_by_time_dilation: pitch"##, TimeDilation::Pitch ; "pitch")]
+    #[test_case(r##"affected_by_time_dilation: stretch"##, TimeDilation::Stretch ; "stretch")]
+    fn time_dilation(yaml: &str, expected: TimeDilation) {
+        let settings = serde_yaml::from_str::<Settings>(yaml);
+        dbg!("{}", &settings);
+        assert_eq!(settings.unwrap().affected_by_time_dilation, Some(expected));
+    }
+
+    #[test_case(r##"volume: -6 LUFS"## ; "loudness is not a gain")]
+    #[test_case(r##"affected_by_time_dilation: slow-motion"## ; "unknown time dilation")]
+    #[test_case(r##"volume: -50%"## ; "negative percent")]
+    #[test_case(r##"normalize: -16dB"## ; "gain is not a loudness")]
+    fn invalid_unit(yaml: &str) {
+        let settings = serde_yaml::from_str::<Settings>(yaml);
+        dbg!("{}", &settings);
+        assert!(settings.is_err());
+    }
+
+    #[test_case(r##"playback_rate: "-1♭..1♯""##, -1.0, 1.0 ; "signed flat to sharp")]
+    #[test_case(r##"playback_rate: 1♭..1♯"##, -1.0, 1.0 ; "flat to sharp")]
+    #[test_case(r##"playback_rate: -2♯..-1♭"##, -2.0, -1.0 ; "negative sharp to signed flat")]
+    fn playback_rate_range_in_semitones(yaml: &str, min: f64, max: f64) {
+        use super::Validate;
+        use kira::{PlaybackRate, Semitones};
+        let settings = serde_yaml::from_str::<Settings>(yaml).unwrap();
+        dbg!("{}", &settings);
+        assert!(settings.validate().is_ok());
+        let rate = settings.playback_rate.unwrap();
+        assert!((rate.min().0 - PlaybackRate::from(Semitones(min)).0).abs() < 1e-9);
+        assert!((rate.max().0 - PlaybackRate::from(Semitones(max)).0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn volume_too_loud_reports_decibels() {
+        use super::Validate;
+        let settings = serde_yaml::from_str::<Settings>(r##"volume: +90dB"##).unwrap();
+        let errors = settings.validate().unwrap_err();
+        dbg!("{}", &errors);
+        assert!(errors.iter().any(|x| x.why.contains("+90.0dB")));
+    }
+
+    #[test_case(r##"volume: 0.5..1.0"## ; "volume")]
+    #[test_case(r##"volume: -6dB..+3dB"## ; "volume in decibels")]
+    #[test_case(r##"playback_rate: 2♭..2♯"## ; "playback rate")]
+    fn sampled_within_range(yaml: &str) {
+        let settings = serde_yaml::from_str::<Settings>(yaml).unwrap();
+        for _ in 0..100 {
+            if let Some(volume) = settings.volume {
+                let sampled = volume.sample().as_decibels().0;
+                assert!(sampled >= volume.min().as_decibels().0 - 1e-4);
+                assert!(sampled <= volume.max().as_decibels().0 + 1e-4);
+            }
+            if let Some(rate) = settings.playback_rate {
+                let sampled = rate.sample();
+                assert!(sampled.0 >= rate.min().0 - 1e-9);
+                assert!(sampled.0 <= rate.max().0 + 1e-9);
+            }
+        }
+    }
 }
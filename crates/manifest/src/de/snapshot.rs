@@ -0,0 +1,193 @@
+//! Named mixes, blended to whenever activated.
+
+use std::collections::HashMap;
+
+use audioware_core::{Amplitude, error::ValidationError};
+use kira::Decibels;
+use serde::Deserialize;
+
+use super::Validate;
+
+/// Named mix (e.g. `combat`, `braindance`, `stealth` or `cutscene`)
+/// setting volume of tracks, environmental filters cutoff and reverb mix.
+///
+/// Active snapshots stack: each value comes from
+/// the highest priority snapshot defining it.
+#[derive(Debug, Default, Deserialize, Clone, PartialEq)]
+pub struct Snapshot {
+    /// Higher wins over lower, latest activated wins ties.
+    #[serde(default)]
+    pub priority: i32,
+    /// Volume of each track, on top of its volume setting.
+    pub volumes: Option<HashMap<MixTrack, Amplitude>>,
+    /// Environmental low-pass cutoff frequency, in Hz.
+    ///
+    /// Only filters the part of each track sent to environment,
+    /// see [Snapshot::cutoffs] to filter a track as a whole.
+    pub low_pass: Option<f64>,
+    /// Environmental high-pass cutoff frequency, in Hz.
+    pub high_pass: Option<f64>,
+    /// Cutoff frequencies of each track.
+    pub cutoffs: Option<HashMap<MixTrack, TrackCutoffs>>,
+    /// Between `0.0` and `1.0`, overrides player's reverb.
+    pub reverb_mix: Option<f32>,
+}
+
+/// Low-pass and high-pass cutoff frequencies of a track, in Hz.
+#[derive(Debug, Default, Deserialize, Clone, Copy, PartialEq)]
+pub struct TrackCutoffs {
+    pub low_pass: Option<f64>,
+    pub high_pass: Option<f64>,
+}
+
+/// Track whose volume and cutoffs can be set by [Snapshot].
+#[derive(Debug, Deserialize, Clone, Copy, PartialEq, Eq, Hash)]
+#[serde(rename_all = "kebab-case")]
+pub enum MixTrack {
+    Music,
+    Sfx,
+    Dialogue,
+    Radioport,
+    CarRadio,
+}
+
+impl MixTrack {
+    pub const ALL: [Self; 5] = [
+        Self::Music,
+        Self::Sfx,
+        Self::Dialogue,
+        Self::Radioport,
+        Self::CarRadio,
+    ];
+}
+
+impl Validate for Snapshot {
+    fn validate(&self) -> Result<(), Vec<ValidationError>> {
+        let mut errors = vec![];
+        if self.volumes.as_ref().is_none_or(HashMap::is_empty)
+            && self.low_pass.is_none()
+            && self.high_pass.is_none()
+            && self.cutoffs.as_ref().is_none_or(HashMap::is_empty)
+            && self.reverb_mix.is_none()
+        {
+            errors.push(ValidationError {
+                which: "snapshot",
+                why: "must set at least one volume, cutoff or reverb mix".into(),
+            });
+        }
+        if self
+            .cutoffs
+            .iter()
+            .flatten()
+            .any(|(_, x)| *x == TrackCutoffs::default())
+        {
+            errors.push(ValidationError {
+                which: "cutoffs",
+                why: "must set at least one of low_pass or high_pass for each track".into(),
+            });
+        }
+        if self
+            .volumes
+            .iter()
+            .flatten()
+            .any(|(_, x)| x.as_decibels() > Decibels::IDENTITY)
+        {
+            errors.push(ValidationError {
+                which: "volumes",
+                why: "volume cannot be louder than 0dB".into(),
+            });
+        }
+        let tracks = self
+            .cutoffs
+            .iter()
+            .flatten()
+            .flat_map(|(_, x)| [("cutoffs", x.low_pass), ("cutoffs", x.high_pass)]);
+        for (which, cutoff) in [("low_pass", self.low_pass), ("high_pass", self.high_pass)]
+            .into_iter()
+            .chain(tracks)
+        {
+            if let Some(cutoff) = cutoff
+                && !(20.0..=20_000.0).contains(&cutoff)
+            {
+                errors.push(ValidationError {
+                    which,
+                    why: format!("cutoff must be between 20.0 and 20000.0 (got {cutoff})").into(),
+                });
+            }
+        }
+        if let Some(reverb_mix) = self.reverb_mix
+            && !(0.0..=1.0).contains(&reverb_mix)
+        {
+            errors.push(ValidationError {
+                which: "reverb_mix",
+                why: format!("must be between 0.0 and 1.0 (got {reverb_mix})").into(),
+            });
+        }
+        if errors.is_empty() {
+            return Ok(());
+        }
+        Err(errors)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use test_case::test_case;
+
+    use super::{MixTrack, Snapshot};
+    use crate::Validate;
+
+    #[test_case(r##"priority: 10
+volumes:
+  music: -12dB
+  sfx: 50%
+reverb_mix: 0.2"##, 10 ; "combat")]
+    #[test_case(r##"volumes:
+  dialogue: -3dB
+low_pass: 1200
+high_pass: 300"##, 0 ; "braindance")]
+    #[test_case(r##"priority: -1
+reverb_mix: 0.5"##, -1 ; "reverb only")]
+    #[test_case(r##"cutoffs:
+  music:
+    low_pass: 800
+  dialogue:
+    high_pass: 200
+    low_pass: 6000"##, 0 ; "cutoffs per track")]
+    fn snapshot(yaml: &str, priority: i32) {
+        let snapshot = serde_yaml::from_str::<Snapshot>(yaml).unwrap();
+        dbg!("{}", &snapshot);
+        assert!(snapshot.validate().is_ok());
+        assert_eq!(snapshot.priority, priority);
+        assert!(
+            snapshot
+                .volumes
+                .iter()
+                .flatten()
+                .all(|(x, _)| MixTrack::ALL.contains(x))
+        );
+    }
+
+    #[test_case(r##"priority: 1"## ; "nothing set")]
+    #[test_case(r##"volumes: {}"## ; "no volume")]
+    #[test_case(r##"volumes:
+  holocall: -6dB"## ; "unknown track")]
+    #[test_case(r##"volumes:
+  music: +3dB"## ; "louder volume")]
+    #[test_case(r##"low_pass: 25000"## ; "cutoff too high")]
+    #[test_case(r##"high_pass: 5"## ; "cutoff too low")]
+    #[test_case(r##"reverb_mix: 1.5"## ; "reverb mix out of range")]
+    #[test_case(r##"cutoffs:
+  sfx:
+    low_pass: 10"## ; "track cutoff too low")]
+    #[test_case(r##"cutoffs:
+  music: {}"## ; "no track cutoff")]
+    #[test_case(r##"cutoffs:
+  holocall:
+    low_pass: 800"## ; "unknown track cutoff")]
+    fn invalid_snapshot(yaml: &str) {
+        let snapshot = serde_yaml::from_str::<Snapshot>(yaml);
+        dbg!("{}", &snapshot);
+        assert!(snapshot.is_err() || snapshot.unwrap().validate().is_err());
+    }
+}
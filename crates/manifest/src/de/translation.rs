@@ -0,0 +1,104 @@
+//! Translated subtitles, overlaid on top of [Voice](super::Voice) defined elsewhere.
+
+use std::collections::HashMap;
+
+use either::Either;
+use serde::Deserialize;
+
+use crate::{Locale, ScnDialogLineType};
+
+use super::{DialogLine, GenderBased};
+
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+pub enum Translation {
+    Single {
+        #[serde(flatten)]
+        subtitles: HashMap<Locale, String>,
+        line: Option<ScnDialogLineType>,
+    },
+    Dual {
+        #[serde(flatten)]
+        subtitles: HashMap<Locale, GenderBased<String>>,
+        line: Option<ScnDialogLineType>,
+    },
+}
+
+/// ultimately a translation is just subtitles for each locale,
+/// with optional gender.
+pub type AnyTranslation =
+    Either<HashMap<Locale, DialogLine>, HashMap<Locale, GenderBased<DialogLine>>>;
+
+impl From<Translation> for AnyTranslation {
+    fn from(value: Translation) -> Self {
+        let default_line = ScnDialogLineType::Regular;
+        match value {
+            Translation::Single { subtitles, line } => Either::Left(
+                subtitles
+                    .into_iter()
+                    .map(|(k, msg)| {
+                        (
+                            k,
+                            DialogLine {
+                                msg,
+                                line: line.unwrap_or(default_line),
+                            },
+                        )
+                    })
+                    .collect(),
+            ),
+            Translation::Dual { subtitles, line } => Either::Right(
+                subtitles
+                    .into_iter()
+                    .map(|(k, v)| {
+                        (
+                            k,
+                            v.map(|msg| DialogLine {
+                                msg,
+                                line: line.unwrap_or(default_line),
+                            }),
+                        )
+                    })
+                    .collect(),
+            ),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use super::Translation;
+    use test_case::test_case;
+
+    #[test_case(r##"id:
+    fr-fr: "bonjour tout le monde""## ; "single translation")]
+    #[test_case(r##"id:
+    fr-fr: "bonjour tout le monde"
+    de-de: "hallo Welt"
+    line: radio"## ; "single translation with line type")]
+    #[test_case(r##"id:
+    fr-fr:
+        fem: "bonjour ma belle"
+        male: "bonjour mon beau""## ; "dual translation")]
+    fn basic_format(yaml: &str) {
+        let translation = serde_yaml::from_str::<HashMap<String, Translation>>(yaml);
+        dbg!("{}", &translation);
+        assert!(translation.is_ok());
+    }
+
+    #[test_case(r##"id:
+    fr-fr: "bonjour tout le monde"
+    de-de:
+        fem: "hallo"
+        male: "hallo""## ; "format must be consistent")]
+    #[test_case(r##"id:
+    fr-fr:
+        fem: "bonjour ma belle""## ; "partially defined gender is not allowed")]
+    fn incompatibility(yaml: &str) {
+        let translation = serde_yaml::from_str::<HashMap<String, Translation>>(yaml);
+        dbg!("{}", &translation);
+        assert!(translation.is_err());
+    }
+}
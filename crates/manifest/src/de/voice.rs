@@ -7,7 +7,9 @@ use serde::Deserialize;
 
 use crate::{Locale, ScnDialogLineType};
 
-use super::{Audio, DialogLine, GenderBased, Settings, Usage, paths_into_audios};
+use super::{
+    Audio, DialogLine, GenderBased, Settings, Subtitles, TimedSubtitles, Usage, paths_into_audios,
+};
 
 #[derive(Debug, Deserialize)]
 #[serde(untagged)]
@@ -41,11 +43,35 @@ pub enum Voice {
     },
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Clone, Deserialize)]
 pub struct Dialog {
     #[serde(flatten)]
     pub basic: Audio,
-    pub subtitle: String,
+    #[serde(flatten)]
+    pub subtitle: DialogSubtitle,
+}
+
+/// Either a single subtitle, or a `.srt` / `.vtt` subtitles file.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(untagged)]
+pub enum DialogSubtitle {
+    Line { subtitle: String },
+    Timed { subtitles: PathBuf },
+}
+
+impl DialogSubtitle {
+    pub fn into_subtitles(self, line: ScnDialogLineType) -> Subtitles {
+        match self {
+            Self::Line { subtitle } => Subtitles::Line(DialogLine {
+                msg: subtitle,
+                line,
+            }),
+            Self::Timed { subtitles } => Subtitles::Timed(TimedSubtitles {
+                file: subtitles,
+                line,
+            }),
+        }
+    }
 }
 
 impl From<&Dialog> for Audio {
@@ -75,25 +101,26 @@ pub enum Dialogs {
     Shared {
         #[serde(flatten)]
         paths: GenderBased<PathBuf>,
-        subtitle: String,
+        #[serde(flatten)]
+        subtitle: DialogSubtitle,
     },
 }
 
 /// ultimately a voice is just either file path for each locale
 /// with optional gender,
-/// optional corresponding dialog lines,
+/// optional corresponding subtitles,
 /// audio usage
 /// and optional settings
 pub type AnyVoice = Either<
     (
         HashMap<Locale, Audio>,
         Usage,
-        Option<HashMap<Locale, DialogLine>>,
+        Option<HashMap<Locale, Subtitles>>,
     ),
     (
         HashMap<Locale, GenderBased<Audio>>,
         Usage,
-        Option<HashMap<Locale, GenderBased<DialogLine>>>,
+        Option<HashMap<Locale, GenderBased<Subtitles>>>,
     ),
 >;
 
@@ -117,16 +144,15 @@ impl From<Voice> for AnyVoice {
                 settings,
             } => {
                 let mut aud: HashMap<Locale, Audio> = HashMap::with_capacity(dialogs.len());
-                let mut sub: HashMap<Locale, DialogLine> = HashMap::with_capacity(dialogs.len());
+                let mut sub: HashMap<Locale, Subtitles> = HashMap::with_capacity(dialogs.len());
                 for (k, ref v) in dialogs.into_iter() {
                     let audio: Audio = (v, settings.as_ref()).into();
                     aud.insert(k, audio);
                     sub.insert(
                         k,
-                        DialogLine {
-                            msg: v.subtitle.clone(),
-                            line: line.unwrap_or(default_line),
-                        },
+                        v.subtitle
+                            .clone()
+                            .into_subtitles(line.unwrap_or(default_line)),
                     );
                 }
                 Either::Left((aud, usage.unwrap_or(default_usage), Some(sub)))
@@ -141,16 +167,10 @@ impl From<Voice> for AnyVoice {
                     .map(|(k, v)| {
                         (
                             k,
-                            GenderBased::<Audio> {
-                                female: Audio {
-                                    file: v.female,
-                                    settings: settings.clone(),
-                                },
-                                male: Audio {
-                                    file: v.male,
-                                    settings: settings.clone(),
-                                },
-                            },
+                            v.map(|file| Audio {
+                                file,
+                                settings: settings.clone(),
+                            }),
                         )
                     })
                     .collect();
@@ -164,7 +184,7 @@ impl From<Voice> for AnyVoice {
             } => {
                 let mut aud: HashMap<Locale, GenderBased<Audio>> =
                     HashMap::with_capacity(dialogs.len());
-                let mut sub: HashMap<Locale, GenderBased<DialogLine>> =
+                let mut sub: HashMap<Locale, GenderBased<Subtitles>> =
                     HashMap::with_capacity(dialogs.len());
                 for (k, v) in dialogs.into_iter() {
                     match v {
@@ -175,44 +195,34 @@ impl From<Voice> for AnyVoice {
                                 female.merge_settings(settings.clone());
                                 male.merge_settings(settings.clone());
                             }
-                            let aud_dialogs = GenderBased { female, male };
-                            let aud_subs = GenderBased {
-                                female: DialogLine {
-                                    msg: dialogs.female.subtitle.clone(),
-                                    line: line.unwrap_or(ScnDialogLineType::Regular),
-                                },
-                                male: DialogLine {
-                                    msg: dialogs.male.subtitle.clone(),
-                                    line: line.unwrap_or(ScnDialogLineType::Regular),
-                                },
+                            let aud_dialogs = GenderBased {
+                                female,
+                                male,
+                                any: dialogs.any,
+                                shared: dialogs.shared,
                             };
+                            let aud_subs = dialogs
+                                .map(|x| x.subtitle.into_subtitles(line.unwrap_or(default_line)));
                             aud.insert(k, aud_dialogs);
                             sub.insert(k, aud_subs);
                         }
                         super::Dialogs::Shared { paths, subtitle } => {
-                            let (fem, male) = (paths.female, paths.male);
+                            let (any, shared) = (paths.any, paths.shared);
                             aud.insert(
                                 k,
-                                GenderBased {
-                                    female: Audio {
-                                        file: fem.clone(),
-                                        settings: settings.clone(),
-                                    },
-                                    male: Audio {
-                                        file: male.clone(),
-                                        settings: settings.clone(),
-                                    },
-                                },
+                                paths.map(|file| Audio {
+                                    file,
+                                    settings: settings.clone(),
+                                }),
                             );
-                            let same = DialogLine {
-                                msg: subtitle.clone(),
-                                line: line.unwrap_or(default_line),
-                            };
+                            let same = subtitle.into_subtitles(line.unwrap_or(default_line));
                             sub.insert(
                                 k,
                                 GenderBased {
                                     female: same.clone(),
                                     male: same,
+                                    any,
+                                    shared,
                                 },
                             );
                         }
@@ -253,6 +263,13 @@ mod tests {
         file: ./somewhere/else/sfx.wav
         subtitle: "bonjour tout le monde"
     line: radio"## ; "format must be consistent across locales when there are subtitles")]
+        #[test_case(r##"id:
+    en-us:
+        file: ./somewhere/sfx.wav
+        subtitles: ./somewhere/sfx.srt
+    fr-fr:
+        file: ./somewhere/else/sfx.wav
+        subtitles: ./somewhere/else/sfx.vtt"## ; "unique dialog with timed subtitles")]
         fn basic_format(yaml: &str) {
             let unique_dialog = serde_yaml::from_str::<HashMap<String, Voice>>(yaml);
             dbg!("{}", &unique_dialog);
@@ -351,6 +368,19 @@ mod tests {
       male:
         file: ./thai/male_intro.mp3
         subtitle: "heya""## ; "dual dialog with different subtitles")]
+        #[test_case(r##"id:
+    en-us:
+      fem: ./somewhere/sfx.wav
+      male: ./somewhere/else/sfx.wav
+      subtitles: ./somewhere/sfx.srt"## ; "dual dialog with shared timed subtitles")]
+        #[test_case(r##"id:
+    en-us:
+      fem:
+        file: ./somewhere/sfx.wav
+        subtitles: ./somewhere/sfx.srt
+      male:
+        file: ./somewhere/else/sfx.wav
+        subtitle: "hello world""## ; "dual dialog with timed subtitles and subtitle")]
         fn basic_format_with_subtitles(yaml: &str) {
             let dual_dialog = serde_yaml::from_str::<HashMap<String, Voice>>(yaml);
             dbg!("{}", &dual_dialog);
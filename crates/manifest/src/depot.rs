@@ -137,6 +137,12 @@ impl Mod {
     }
 }
 
+impl From<PathBuf> for Mod {
+    fn from(value: PathBuf) -> Self {
+        Self(value)
+    }
+}
+
 impl AsRef<Path> for Mod {
     fn as_ref(&self) -> &Path {
         self.0.as_path()
@@ -44,9 +44,18 @@ pub enum ConversionError {
     /// Locale type is not supported by Locale subset.
     #[snafu(display("unsupported locale for {}: {value}", r#type))]
     UnsupportedLocale { r#type: String, value: String },
+    /// Cyberpunk 2077 does not support this [ScnDialogLineType](crate::ScnDialogLineType).
+    #[snafu(display("invalid line type: {value}"))]
+    InvalidLineType { value: String },
     /// Cyberpunk 2077 does not support this [PlayerGender](crate::PlayerGender).
     #[snafu(display("invalid gender: {value}"))]
     InvalidGender { value: String },
+    /// Gender-based value is missing one gender, without any `any` to fall back to.
+    #[snafu(display("missing gender: {gender} (define it or use 'any' instead)"))]
+    MissingGender { gender: crate::PlayerGender },
+    /// Gender-based value defines both genders alongside `any`, which would never be used for either.
+    #[snafu(display("'any' cannot be defined alongside both 'fem' and 'male'"))]
+    AmbiguousGenders,
     /// Audio stream buffer size is invalid.
     #[snafu(display("invalid buffer size: {value}"))]
     InvalidBufferSize { value: String },
@@ -54,3 +63,37 @@ pub enum ConversionError {
     #[snafu(display("missing buffer size"))]
     MissingBufferSize,
 }
+
+#[derive(Debug, Snafu)]
+#[snafu(visibility(pub(crate)))]
+pub enum CueError {
+    /// An error occured while reading subtitles file.
+    #[snafu(display("cannot read subtitles file: {path}"))]
+    CannotReadSubtitles {
+        path: String,
+        source: std::io::Error,
+    },
+    /// Subtitles file is neither SubRip nor WebVTT.
+    #[snafu(display("unsupported subtitles format: {path} (expected .srt or .vtt)"))]
+    UnsupportedSubtitles { path: String },
+    /// WebVTT file does not start with its mandatory header.
+    #[snafu(display("missing WEBVTT header"))]
+    MissingHeader,
+    /// Cue cannot be parsed.
+    #[snafu(display("malformed cue #{index}: {why}"))]
+    MalformedCue { index: usize, why: &'static str },
+}
+
+#[derive(Debug, Snafu)]
+#[snafu(visibility(pub(crate)))]
+pub enum TranslationError {
+    /// Translation table is neither CSV nor XLIFF.
+    #[snafu(display("unsupported translation table format: {path} (expected .csv or .xlf)"))]
+    UnsupportedTable { path: String },
+    /// Translation table is missing its locales.
+    #[snafu(display("missing source and target locales"))]
+    MissingLocales,
+    /// Translation table row cannot be parsed.
+    #[snafu(display("malformed row #{index}: {why}"))]
+    MalformedRow { index: usize, why: String },
+}
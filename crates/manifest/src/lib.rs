@@ -7,7 +7,9 @@
 mod de;
 mod depot;
 pub mod error;
+mod table;
 mod types;
 pub use de::*;
 pub use depot::*;
+pub use table::*;
 pub use types::*;
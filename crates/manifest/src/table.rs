@@ -0,0 +1,709 @@
+//! Translation tables, exported for translators and imported back
+//! as an overlay [Manifest](crate::Manifest) with [Translation](crate::Translation)s.
+//!
+//! Both [CSV](https://www.rfc-editor.org/rfc/rfc4180) and
+//! [XLIFF 1.2](https://docs.oasis-open.org/xliff/v1.2/os/xliff-core.html) are supported.
+
+use std::{collections::BTreeMap, path::Path};
+
+use either::Either;
+use serde_yaml::{Mapping, Value};
+use snafu::{OptionExt, ensure};
+
+use crate::{
+    AnyVoice, Locale, PlayerGender, ScnDialogLineType, Subtitles, Voice,
+    error::{MalformedRowSnafu, MissingLocalesSnafu, TranslationError, UnsupportedTableSnafu},
+};
+
+/// Single subtitle to translate.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TranslationRow {
+    pub key: String,
+    pub gender: Option<PlayerGender>,
+    pub line: ScnDialogLineType,
+    pub source: String,
+    pub target: String,
+}
+
+/// Supported translation table formats.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TableFormat {
+    /// `.csv`
+    Csv,
+    /// `.xlf` or `.xliff`
+    Xliff,
+}
+
+impl TableFormat {
+    pub fn try_from_path(path: &Path) -> Result<Self, TranslationError> {
+        match path
+            .extension()
+            .and_then(|x| x.to_str())
+            .map(|x| x.to_ascii_lowercase())
+            .as_deref()
+        {
+            Some("csv") => Ok(Self::Csv),
+            Some("xlf") | Some("xliff") => Ok(Self::Xliff),
+            _ => UnsupportedTableSnafu {
+                path: path.display().to_string(),
+            }
+            .fail(),
+        }
+    }
+}
+
+/// Subtitles to translate from a source [Locale] into a target [Locale].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TranslationTable {
+    pub source: Locale,
+    pub target: Locale,
+    pub rows: Vec<TranslationRow>,
+}
+
+impl TranslationTable {
+    pub fn new(source: Locale, target: Locale) -> Self {
+        Self {
+            source,
+            target,
+            rows: Vec::new(),
+        }
+    }
+
+    /// Walk [Voice]s and add one row per subtitle defined for source locale,
+    /// with an empty target to translate.
+    ///
+    /// Voices already subtitled in target locale are skipped,
+    /// as their translation could not be registered again.
+    /// Timed subtitles files are not part of the table.
+    pub fn from_voices(
+        voices: impl IntoIterator<Item = (String, Voice)>,
+        source: Locale,
+        target: Locale,
+    ) -> Self {
+        let mut table = Self::new(source, target);
+        for (key, voice) in voices {
+            match AnyVoice::from(voice) {
+                Either::Left((_, _, Some(subs))) if !subs.contains_key(&target) => {
+                    if let Some(Subtitles::Line(line)) = subs.get(&source) {
+                        table.push(key, None, line.line, line.msg.clone(), String::new());
+                    }
+                }
+                Either::Right((_, _, Some(subs))) if !subs.contains_key(&target) => {
+                    let Some(genders) = subs.get(&source) else {
+                        continue;
+                    };
+                    for (&gender, subtitles) in genders.iter() {
+                        let Subtitles::Line(line) = subtitles else {
+                            continue;
+                        };
+                        table.push(
+                            key.clone(),
+                            Some(gender),
+                            line.line,
+                            line.msg.clone(),
+                            String::new(),
+                        );
+                    }
+                }
+                _ => {}
+            }
+        }
+        table.sort();
+        table
+    }
+
+    pub fn push(
+        &mut self,
+        key: String,
+        gender: Option<PlayerGender>,
+        line: ScnDialogLineType,
+        source: String,
+        target: String,
+    ) {
+        self.rows.push(TranslationRow {
+            key,
+            gender,
+            line,
+            source,
+            target,
+        });
+    }
+
+    /// Sort rows by key then gender, for stable output.
+    pub fn sort(&mut self) {
+        self.rows.sort_by(|a, b| {
+            (&a.key, a.gender.map(u8::from)).cmp(&(&b.key, b.gender.map(u8::from)))
+        });
+    }
+
+    /// Write table in given format.
+    pub fn write(&self, format: TableFormat) -> String {
+        match format {
+            TableFormat::Csv => self.to_csv(),
+            TableFormat::Xliff => self.to_xliff(),
+        }
+    }
+
+    /// Read table from given format.
+    pub fn read(content: &str, format: TableFormat) -> Result<Self, TranslationError> {
+        match format {
+            TableFormat::Csv => Self::from_csv(content),
+            TableFormat::Xliff => Self::from_xliff(content),
+        }
+    }
+
+    /// Columns are: `key`, `gender`, `line`, then source and target locales.
+    pub fn to_csv(&self) -> String {
+        let mut out = format!(
+            "key,gender,line,{},{}\r\n",
+            self.source.as_code(),
+            self.target.as_code()
+        );
+        for row in self.rows.iter() {
+            out.push_str(
+                &[
+                    row.key.as_str(),
+                    gender_code(row.gender),
+                    row.line.as_str(),
+                    row.source.as_str(),
+                    row.target.as_str(),
+                ]
+                .map(csv_field)
+                .join(","),
+            );
+            out.push_str("\r\n");
+        }
+        out
+    }
+
+    pub fn from_csv(content: &str) -> Result<Self, TranslationError> {
+        let records = csv_records(content.trim_start_matches('\u{feff}'))?;
+        let mut records = records.into_iter();
+        let header = records.next().context(MissingLocalesSnafu)?;
+        let [_, _, _, source, target] = header.as_slice() else {
+            return MissingLocalesSnafu.fail();
+        };
+        let mut table = Self::new(locale(source, 0)?, locale(target, 0)?);
+        for (index, record) in records.enumerate().map(|(i, x)| (i + 1, x)) {
+            // trailing empty line(s)
+            if record.len() == 1 && record[0].is_empty() {
+                continue;
+            }
+            let Ok([key, gender, line, source, target]) = <[String; 5]>::try_from(record) else {
+                return MalformedRowSnafu {
+                    index,
+                    why: "expected 5 columns",
+                }
+                .fail();
+            };
+            table.push(
+                key,
+                parse_gender(&gender, index)?,
+                parse_line(&line, index)?,
+                source,
+                target,
+            );
+        }
+        Ok(table)
+    }
+
+    pub fn to_xliff(&self) -> String {
+        let mut out = format!(
+            r#"<?xml version="1.0" encoding="UTF-8"?>
+<xliff version="1.2" xmlns="urn:oasis:names:tc:xliff:document:1.2">
+  <file original="audioware" datatype="plaintext" source-language="{}" target-language="{}">
+    <body>
+"#,
+            self.source.as_code(),
+            self.target.as_code()
+        );
+        for row in self.rows.iter() {
+            let id = match row.gender {
+                Some(gender) => format!("{}#{}", row.key, gender_code(Some(gender))),
+                None => row.key.clone(),
+            };
+            out.push_str(&format!(
+                r#"      <trans-unit id="{}" resname="{}">
+        <source>{}</source>
+        <target>{}</target>
+        <note from="gender">{}</note>
+        <note from="line">{}</note>
+      </trans-unit>
+"#,
+                xml_escape(&id),
+                xml_escape(&row.key),
+                xml_escape(&row.source),
+                xml_escape(&row.target),
+                gender_code(row.gender),
+                row.line.as_str(),
+            ));
+        }
+        out.push_str(
+            r#"    </body>
+  </file>
+</xliff>
+"#,
+        );
+        out
+    }
+
+    pub fn from_xliff(content: &str) -> Result<Self, TranslationError> {
+        let file = content
+            .find("<file")
+            .map(|x| &content[x..])
+            .and_then(|x| x.find('>').map(|end| &x[..end]))
+            .context(MissingLocalesSnafu)?;
+        let source = xml_attribute(file, "source-language").context(MissingLocalesSnafu)?;
+        let target = xml_attribute(file, "target-language").context(MissingLocalesSnafu)?;
+        let mut table = Self::new(locale(&source, 0)?, locale(&target, 0)?);
+        for (index, unit) in content
+            .split("<trans-unit")
+            .skip(1)
+            .enumerate()
+            .map(|(i, x)| (i + 1, x))
+        {
+            let unit = unit
+                .split("</trans-unit>")
+                .next()
+                .expect("split always yields at least once");
+            let opening = &unit[..unit.find('>').unwrap_or(unit.len())];
+            // some tools drop resname, fall back on id
+            let key = xml_attribute(opening, "resname")
+                .or_else(|| {
+                    xml_attribute(opening, "id")
+                        .map(|x| x.split('#').next().unwrap_or_default().to_string())
+                })
+                .context(MalformedRowSnafu {
+                    index,
+                    why: "missing resname",
+                })?;
+            let source = xml_element(unit, "source", None).context(MalformedRowSnafu {
+                index,
+                why: "missing source",
+            })?;
+            // target is optional until translated
+            let target = xml_element(unit, "target", None).unwrap_or_default();
+            let gender = xml_element(unit, "note", Some("gender")).unwrap_or_default();
+            let line = xml_element(unit, "note", Some("line")).unwrap_or_default();
+            table.push(
+                key,
+                parse_gender(&gender, index)?,
+                parse_line(&line, index)?,
+                source,
+                target,
+            );
+        }
+        Ok(table)
+    }
+
+    /// Gender-based rows left untranslated while their opposite gender is translated,
+    /// see [TranslationTable::to_overlay].
+    pub fn half_translated(&self) -> Vec<&TranslationRow> {
+        self.rows
+            .iter()
+            .filter(|x| x.gender.is_some() && x.target.trim().is_empty())
+            .filter(|x| {
+                self.rows.iter().any(|y| {
+                    y.key == x.key
+                        && y.gender.is_some()
+                        && y.gender != x.gender
+                        && !y.target.trim().is_empty()
+                })
+            })
+            .collect()
+    }
+
+    /// Overlay [Manifest](crate::Manifest) registering translated subtitles for target locale.
+    ///
+    /// Rows left untranslated are skipped, except gender-based rows
+    /// whose opposite gender is translated: those keep their source subtitle,
+    /// see [TranslationTable::half_translated].
+    pub fn to_overlay(&self) -> String {
+        let mut grouped: BTreeMap<&str, Vec<&TranslationRow>> = BTreeMap::new();
+        for row in self.rows.iter() {
+            grouped.entry(row.key.as_str()).or_default().push(row);
+        }
+        let locale = Value::String(self.target.as_code().to_string());
+        let mut translations = Mapping::new();
+        for (key, rows) in grouped {
+            let mut translation = Mapping::new();
+            let translated = |row: &TranslationRow| !row.target.trim().is_empty();
+            match rows.as_slice() {
+                [row] if row.gender.is_none() && translated(row) => {
+                    translation.insert(locale.clone(), Value::String(row.target.clone()));
+                }
+                [a, b]
+                    if a.gender.is_some()
+                        && b.gender.is_some()
+                        && a.gender != b.gender
+                        && (translated(a) || translated(b)) =>
+                {
+                    let mut genders = Mapping::new();
+                    for row in [a, b] {
+                        genders.insert(
+                            Value::String(gender_code(row.gender).to_string()),
+                            Value::String(if translated(row) {
+                                row.target.clone()
+                            } else {
+                                row.source.clone()
+                            }),
+                        );
+                    }
+                    translation.insert(locale.clone(), Value::Mapping(genders));
+                }
+                _ => continue,
+            }
+            translation.insert(
+                Value::String("line".to_string()),
+                Value::String(rows[0].line.as_str().to_string()),
+            );
+            translations.insert(Value::String(key.to_string()), Value::Mapping(translation));
+        }
+        let mut manifest = Mapping::new();
+        manifest.insert(
+            Value::String("version".to_string()),
+            Value::String("1.0.0".to_string()),
+        );
+        manifest.insert(
+            Value::String("translations".to_string()),
+            Value::Mapping(translations),
+        );
+        serde_yaml::to_string(&manifest).expect("mapping of strings is always serializable")
+    }
+}
+
+fn gender_code(gender: Option<PlayerGender>) -> &'static str {
+    match gender {
+        Some(PlayerGender::Female) => "fem",
+        Some(PlayerGender::Male) => "male",
+        None => "",
+    }
+}
+
+fn parse_gender(value: &str, index: usize) -> Result<Option<PlayerGender>, TranslationError> {
+    match value.trim() {
+        "" => Ok(None),
+        "fem" => Ok(Some(PlayerGender::Female)),
+        "male" => Ok(Some(PlayerGender::Male)),
+        v => MalformedRowSnafu {
+            index,
+            why: format!("invalid gender: {v}"),
+        }
+        .fail(),
+    }
+}
+
+fn parse_line(value: &str, index: usize) -> Result<ScnDialogLineType, TranslationError> {
+    if value.trim().is_empty() {
+        return Ok(ScnDialogLineType::default());
+    }
+    ScnDialogLineType::try_from(value.trim()).map_err(|e| TranslationError::MalformedRow {
+        index,
+        why: e.to_string(),
+    })
+}
+
+/// Accepts BCP 47 casing too, e.g. `en-US`.
+fn locale(value: &str, index: usize) -> Result<Locale, TranslationError> {
+    Locale::try_from(value.trim().to_ascii_lowercase().as_str()).map_err(|e| {
+        TranslationError::MalformedRow {
+            index,
+            why: e.to_string(),
+        }
+    })
+}
+
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+fn csv_records(content: &str) -> Result<Vec<Vec<String>>, TranslationError> {
+    let mut records = Vec::new();
+    let mut record = Vec::new();
+    let mut field = String::new();
+    let mut quoted = false;
+    let mut chars = content.chars().peekable();
+    while let Some(c) = chars.next() {
+        match (quoted, c) {
+            (true, '"') if chars.peek() == Some(&'"') => {
+                chars.next();
+                field.push('"');
+            }
+            (true, '"') => quoted = false,
+            (true, c) => field.push(c),
+            (false, '"') if field.is_empty() => quoted = true,
+            (false, ',') => record.push(std::mem::take(&mut field)),
+            (false, '\r') if chars.peek() == Some(&'\n') => {}
+            (false, '\n') => {
+                record.push(std::mem::take(&mut field));
+                records.push(std::mem::take(&mut record));
+            }
+            (false, c) => field.push(c),
+        }
+    }
+    ensure!(
+        !quoted,
+        MalformedRowSnafu {
+            index: records.len(),
+            why: "unterminated quoted field",
+        }
+    );
+    if !field.is_empty() || !record.is_empty() {
+        record.push(field);
+        records.push(record);
+    }
+    Ok(records)
+}
+
+fn xml_escape(value: &str) -> String {
+    value
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&apos;")
+}
+
+fn xml_unescape(value: &str) -> String {
+    value
+        .replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&apos;", "'")
+        .replace("&amp;", "&")
+}
+
+fn xml_attribute(tag: &str, name: &str) -> Option<String> {
+    let pattern = format!(" {name}=\"");
+    let starts = tag.find(&pattern)? + pattern.len();
+    let ends = tag[starts..].find('"')?;
+    Some(xml_unescape(&tag[starts..starts + ends]))
+}
+
+/// Content of first element with given name, whatever its attributes,
+/// optionally restricted to those whose `from` attribute matches.
+fn xml_element(unit: &str, name: &str, from: Option<&str>) -> Option<String> {
+    let opening = format!("<{name}");
+    let closing = format!("</{name}>");
+    let mut rest = unit;
+    while let Some(starts) = rest.find(&opening) {
+        rest = &rest[starts + opening.len()..];
+        // skip longer names sharing the same prefix, e.g. `<target-x>`
+        if !rest.starts_with(|c: char| c == '>' || c == '/' || c.is_whitespace()) {
+            continue;
+        }
+        let ends = rest.find('>')?;
+        let tag = &rest[..ends];
+        rest = &rest[ends + 1..];
+        if from.is_some_and(|from| xml_attribute(tag, "from").as_deref() != Some(from)) {
+            continue;
+        }
+        if tag.ends_with('/') {
+            return Some(String::new());
+        }
+        let ends = rest.find(&closing)?;
+        return Some(xml_unescape(&rest[..ends]));
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use test_case::test_case;
+
+    use super::{TableFormat, TranslationTable};
+    use crate::{Locale, Manifest, PlayerGender, Translation, Voice};
+
+    const VOICES: &str = r##"simple:
+    en-us:
+        file: ./en-us/simple.wav
+        subtitle: "hello, \"world\""
+    line: radio
+dual:
+    en-us:
+        fem:
+            file: ./en-us/fem.wav
+            subtitle: "hello girl"
+        male:
+            file: ./en-us/male.wav
+            subtitle: "hello <boy> & co"
+    fr-fr:
+        fem:
+            file: ./fr-fr/fem.wav
+            subtitle: "salut ma belle"
+        male:
+            file: ./fr-fr/male.wav
+            subtitle: "salut mon beau"
+pair:
+    en-us:
+        fem:
+            file: ./en-us/pair_fem.wav
+            subtitle: "hello girl"
+        male:
+            file: ./en-us/pair_male.wav
+            subtitle: "hello <boy> & co"
+inline:
+    en-us: ./en-us/inline.wav"##;
+
+    /// As saved by a CAT tool: attributes on every element,
+    /// self-closing and translated targets, extra notes.
+    const TRANSLATED: &str = r##"<?xml version="1.0" encoding="UTF-8"?>
+<xliff xmlns="urn:oasis:names:tc:xliff:document:1.2" version="1.2">
+  <file original="audioware" source-language="en-us" target-language="fr-fr" datatype="plaintext">
+    <header><tool tool-id="cat" tool-name="CAT"/></header>
+    <body>
+      <trans-unit id="pair#fem" resname="pair" xml:space="preserve">
+        <source xml:lang="en-us">hello girl</source>
+        <target xml:lang="fr-fr" state="translated">salut ma belle</target>
+        <note from="translator" priority="2">checked</note>
+        <note from="gender">fem</note>
+        <note from="line">regular</note>
+      </trans-unit>
+      <trans-unit id="pair#male">
+        <source xml:lang="en-us">hello &lt;boy&gt; &amp; co</source>
+        <target xml:lang="fr-fr" state="needs-review-translation">salut &lt;mec&gt;</target>
+        <note annotates="general" from="gender">male</note>
+        <note from="line">regular</note>
+      </trans-unit>
+      <trans-unit id="simple" resname="simple">
+        <source xml:lang="en-us">hello, "world"</source>
+        <target xml:lang="fr-fr" state="new"/>
+        <note from="gender"></note>
+        <note from="line">radio</note>
+      </trans-unit>
+    </body>
+  </file>
+</xliff>"##;
+
+    /// Locales cased as BCP 47, only one gender translated.
+    const HALF_TRANSLATED: &str = r##"<?xml version="1.0" encoding="UTF-8"?>
+<xliff version="1.2" xmlns="urn:oasis:names:tc:xliff:document:1.2">
+  <file original="audioware" datatype="plaintext" source-language="en-US" target-language="fr-FR">
+    <body>
+      <trans-unit id="pair#fem" resname="pair">
+        <source>hello girl</source>
+        <target>salut ma belle</target>
+        <note from="gender">fem</note>
+        <note from="line">regular</note>
+      </trans-unit>
+      <trans-unit id="pair#male" resname="pair">
+        <source>hello &lt;boy&gt; &amp; co</source>
+        <target></target>
+        <note from="gender">male</note>
+        <note from="line">regular</note>
+      </trans-unit>
+    </body>
+  </file>
+</xliff>"##;
+
+    fn table() -> TranslationTable {
+        let voices = serde_yaml::from_str::<HashMap<String, Voice>>(VOICES).unwrap();
+        TranslationTable::from_voices(voices, Locale::English, Locale::French)
+    }
+
+    #[test]
+    fn from_voices() {
+        let table = table();
+        dbg!("{}", &table);
+        // dual is already translated
+        assert_eq!(table.rows.len(), 3);
+        assert!(table.rows.iter().all(|x| x.key != "dual"));
+        assert!(table.rows.iter().all(|x| x.target.is_empty()));
+    }
+
+    #[test]
+    fn from_tool() {
+        let table = TranslationTable::from_xliff(TRANSLATED);
+        dbg!("{}", &table);
+        let table = table.unwrap();
+        let targets = table
+            .rows
+            .iter()
+            .map(|x| x.target.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(targets, ["salut ma belle", "salut <mec>", ""]);
+        assert_eq!(table.rows[1].key, "pair");
+        let overlay = table.to_overlay();
+        let manifest = serde_yaml::from_str::<Manifest>(&overlay);
+        dbg!("{}", &overlay, &manifest);
+        assert_eq!(manifest.unwrap().translations.map(|x| x.len()), Some(1));
+    }
+
+    #[test]
+    fn unedited_export() {
+        let table = table();
+        let overlay = TranslationTable::read(&table.write(TableFormat::Xliff), TableFormat::Xliff)
+            .unwrap()
+            .to_overlay();
+        let manifest = serde_yaml::from_str::<Manifest>(&overlay);
+        dbg!("{}", &overlay, &manifest);
+        // nothing translated, nothing registered again
+        assert!(manifest.unwrap().translations.is_none_or(|x| x.is_empty()));
+    }
+
+    #[test_case(TableFormat::Csv ; "csv")]
+    #[test_case(TableFormat::Xliff ; "xliff")]
+    fn round_trip(format: TableFormat) {
+        let table = table();
+        let written = table.write(format);
+        let read = TranslationTable::read(&written, format);
+        dbg!("{}", &written, &read);
+        assert_eq!(read.ok(), Some(table));
+    }
+
+    #[test_case(r##"key,gender,line,en-us
+simple,,regular,hello"## ; "missing target locale")]
+    #[test_case(r##"key,gender,line,en-us,fr-fr
+simple,,regular,hello"## ; "missing column")]
+    #[test_case(r##"key,gender,line,en-us,fr-fr
+simple,both,regular,hello,bonjour"## ; "invalid gender")]
+    #[test_case(r##"key,gender,line,en-us,fr-fr
+simple,,regular,"hello,bonjour"## ; "unterminated quote")]
+    fn malformed_csv(csv: &str) {
+        let table = TranslationTable::from_csv(csv);
+        dbg!("{}", &table);
+        assert!(table.is_err());
+    }
+
+    #[test]
+    fn overlay() {
+        let mut table = table();
+        table.rows[0].target = "salut ma belle".to_string();
+        table.rows[2].target = "bonjour, \"monde\"".to_string();
+        let overlay = table.to_overlay();
+        let manifest = serde_yaml::from_str::<Manifest>(&overlay);
+        dbg!("{}", &overlay, &manifest);
+        // pair is only partially translated, male keeps its source subtitle
+        assert_eq!(manifest.unwrap().translations.map(|x| x.len()), Some(2));
+        let missing = table.half_translated();
+        assert_eq!(missing.len(), 1);
+        assert_eq!(missing[0].gender, Some(PlayerGender::Male));
+    }
+
+    #[test]
+    fn bcp47_half_translated() {
+        let table = TranslationTable::from_xliff(HALF_TRANSLATED);
+        dbg!("{}", &table);
+        let table = table.unwrap();
+        assert_eq!(table.source, Locale::English);
+        assert_eq!(table.target, Locale::French);
+        let read = TranslationTable::read(&table.write(TableFormat::Csv), TableFormat::Csv);
+        assert_eq!(read.as_ref().ok(), Some(&table));
+        let overlay = read.unwrap().to_overlay();
+        let manifest = serde_yaml::from_str::<Manifest>(&overlay);
+        dbg!("{}", &overlay, &manifest);
+        let translations = manifest.unwrap().translations.unwrap();
+        let Some(Translation::Dual { subtitles, .. }) = translations.get("pair") else {
+            panic!("pair should be gender-based");
+        };
+        let pair = subtitles.get(&Locale::French).unwrap();
+        assert_eq!(pair.female, "salut ma belle");
+        assert_eq!(pair.male, "hello <boy> & co");
+    }
+}
@@ -55,6 +55,31 @@ impl ScnDialogLineType {
     }
 }
 
+impl TryFrom<&str> for ScnDialogLineType {
+    type Error = crate::error::ConversionError;
+
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        match value {
+            "none" => Ok(Self::None),
+            "regular" => Ok(Self::Regular),
+            "holocall" => Ok(Self::Holocall),
+            "scene_comment" => Ok(Self::SceneComment),
+            "over_head" => Ok(Self::OverHead),
+            "radio" => Ok(Self::Radio),
+            "global_tv" => Ok(Self::GlobalTv),
+            "invisible" => Ok(Self::Invisible),
+            "over_head_always_visible" => Ok(Self::OverHeadAlwaysVisible),
+            "ownerless_regular" => Ok(Self::OwnerlessRegular),
+            "always_cinematic_no_speaker" => Ok(Self::AlwaysCinematicNoSpeaker),
+            "global_tv_always_visible" => Ok(Self::GlobalTvAlwaysVisible),
+            "narrator" => Ok(Self::Narrator),
+            v => Err(Self::Error::InvalidLineType {
+                value: v.to_string(),
+            }),
+        }
+    }
+}
+
 impl std::fmt::Display for ScnDialogLineType {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(f, "{}", self.as_str())
@@ -28,6 +28,12 @@ impl PartialEq<Locale> for SpokenLocale {
     }
 }
 
+impl SpokenLocale {
+    pub fn into_inner(self) -> Locale {
+        self.0
+    }
+}
+
 impl From<Locale> for SpokenLocale {
     fn from(value: Locale) -> Self {
         Self(value)
@@ -378,27 +384,7 @@ pub enum Locale {
 #[cfg(not(test))]
 impl From<Locale> for red4ext_rs::types::CName {
     fn from(val: Locale) -> Self {
-        red4ext_rs::types::CName::new(match val {
-            Locale::Polish => "pl-pl",
-            Locale::English => "en-us",
-            Locale::Spanish => "es-es",
-            Locale::French => "fr-fr",
-            Locale::Italian => "it-it",
-            Locale::German => "de-de",
-            Locale::LatinAmericanSpanish => "es-mx",
-            Locale::Korean => "kr-kr",
-            Locale::SimplifiedChinese => "zh-cn",
-            Locale::Russian => "ru-ru",
-            Locale::BrazilianPortuguese => "pt-br",
-            Locale::Japanese => "jp-jp",
-            Locale::TraditionalChinese => "zh-tw",
-            Locale::Arabic => "ar-ar",
-            Locale::Czech => "cz-cz",
-            Locale::Hungarian => "hu-hu",
-            Locale::Turkish => "tr-tr",
-            Locale::Thai => "th-th",
-            Locale::Ukrainian => "ua-ua",
-        })
+        red4ext_rs::types::CName::new(val.as_code())
     }
 }
 
@@ -475,7 +461,42 @@ impl TryFrom<red4ext_rs::types::CName> for Locale {
         if value == red4ext_rs::types::CName::undefined() {
             return Ok(Self::English);
         }
-        match value.as_str() {
+        Self::try_from(value.as_str())
+    }
+}
+
+impl Locale {
+    /// Locale code, as found in manifests.
+    pub fn as_code(&self) -> &'static str {
+        match self {
+            Self::Polish => "pl-pl",
+            Self::English => "en-us",
+            Self::Spanish => "es-es",
+            Self::French => "fr-fr",
+            Self::Italian => "it-it",
+            Self::German => "de-de",
+            Self::LatinAmericanSpanish => "es-mx",
+            Self::Korean => "kr-kr",
+            Self::SimplifiedChinese => "zh-cn",
+            Self::Russian => "ru-ru",
+            Self::BrazilianPortuguese => "pt-br",
+            Self::Japanese => "jp-jp",
+            Self::TraditionalChinese => "zh-tw",
+            Self::Arabic => "ar-ar",
+            Self::Czech => "cz-cz",
+            Self::Hungarian => "hu-hu",
+            Self::Turkish => "tr-tr",
+            Self::Thai => "th-th",
+            Self::Ukrainian => "ua-ua",
+        }
+    }
+}
+
+impl TryFrom<&str> for Locale {
+    type Error = crate::error::ConversionError;
+
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        match value {
             "pl-pl" => Ok(Self::Polish),
             "en-us" => Ok(Self::English),
             "es-es" => Ok(Self::Spanish),